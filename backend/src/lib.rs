@@ -0,0 +1,5 @@
+//! Account storage for the SideQuest local development backend. The HTTP
+//! server in `main.rs` is a thin layer over [`store::Store`].
+
+pub mod outbox;
+pub mod store;
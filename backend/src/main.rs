@@ -0,0 +1,178 @@
+//! Local development backend for SideQuest.
+//!
+//! Serves the endpoints listed in the README over plain HTTP and keeps all
+//! state in a JSON file, so the game's online and offline flows can be
+//! exercised without a hosted service.
+//!
+//! Configuration (environment variables):
+//! - `SIDEQUEST_BACKEND_ADDR`: listen address, default `127.0.0.1:7878`
+//! - `SIDEQUEST_BACKEND_DATA`: data directory, default `backend_data`
+//!
+//! Verification and password reset emails are written to `outbox/` in the
+//! data directory instead of being sent.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sidequest_backend::store::{Account, Store, StoreError};
+use std::path::PathBuf;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[derive(Deserialize)]
+struct LoginRequest {
+    email: String,
+    password: String,
+}
+
+#[derive(Deserialize)]
+struct SignUpRequest {
+    email: String,
+    password: String,
+    username: String,
+    /// Progress from a guest profile to store with the new account
+    #[serde(default)]
+    user_data: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct VerifyEmailRequest {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct PasswordResetRequest {
+    email: String,
+}
+
+#[derive(Deserialize)]
+struct ResetPasswordRequest {
+    email: String,
+    code: String,
+    new_password: String,
+}
+
+#[derive(Deserialize)]
+struct DeleteAccountRequest {
+    password: String,
+}
+
+#[derive(Deserialize)]
+struct PutUserDataRequest {
+    user_data: Value,
+    base_revision: u64,
+}
+
+fn main() {
+    let addr = std::env::var("SIDEQUEST_BACKEND_ADDR").unwrap_or_else(|_| "127.0.0.1:7878".to_string());
+    let data_dir = PathBuf::from(std::env::var("SIDEQUEST_BACKEND_DATA").unwrap_or_else(|_| "backend_data".to_string()));
+
+    let mut store = Store::open(&data_dir).expect("Failed to open backend data directory");
+    let server = Server::http(&addr).expect("Failed to bind backend address");
+
+    println!("SideQuest local backend listening on http://{} (data in {})", addr, data_dir.display());
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = route(&mut store, &mut request);
+        println!("{} {} -> {}", request.method(), request.url(), status);
+
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(Header::from_bytes("Content-Type", "application/json").unwrap());
+
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to send response: {}", e);
+        }
+    }
+}
+
+fn route(store: &mut Store, request: &mut Request) -> (u16, Value) {
+    let mut body = String::new();
+    if let Err(e) = request.as_reader().read_to_string(&mut body) {
+        return (400, error_body(&format!("Failed to read request body: {}", e)));
+    }
+
+    let token = bearer_token(request);
+    let token = token.as_deref();
+    let path = request.url().split('?').next().unwrap_or_default().to_string();
+
+    let result = match (request.method(), path.as_str()) {
+        (Method::Post, "/auth/login") => parse::<LoginRequest>(&body)
+            .and_then(|req| store.login(&req.email, &req.password))
+            .map(|(account, token)| auth_body("Login successful!", account, &token)),
+        (Method::Post, "/auth/signup") => parse::<SignUpRequest>(&body)
+            .and_then(|req| store.signup(&req.email, &req.password, &req.username, req.user_data))
+            .map(|(account, token)| auth_body("Account created successfully!", account, &token)),
+        (Method::Post, "/auth/verify-email") => parse::<VerifyEmailRequest>(&body)
+            .and_then(|req| store.verify_email(token, &req.code))
+            .map(|_| message_body("Email verified!")),
+        (Method::Post, "/auth/resend-verification") => store.resend_verification(token)
+            .map(|_| message_body("A new verification code has been sent")),
+        (Method::Post, "/auth/request-password-reset") => parse::<PasswordResetRequest>(&body)
+            .and_then(|req| store.request_password_reset(&req.email))
+            .map(|_| message_body("If an account exists for that email, a reset code has been sent")),
+        (Method::Post, "/auth/reset-password") => parse::<ResetPasswordRequest>(&body)
+            .and_then(|req| store.reset_password(&req.email, &req.code, &req.new_password))
+            .map(|_| message_body("Password updated, please log in")),
+        (Method::Get, "/user/data") => store.user_data(token)
+            .map(|account| json!({
+                "success": true,
+                "user_data": account.user_data_with_identity(),
+                "revision": account.revision,
+            })),
+        (Method::Put, "/user/data") => parse::<PutUserDataRequest>(&body)
+            .and_then(|req| store.put_user_data(token, req.user_data, req.base_revision))
+            .map(|revision| json!({ "success": true, "revision": revision })),
+        (Method::Delete, "/user") => parse::<DeleteAccountRequest>(&body)
+            .and_then(|req| store.delete_account(token, &req.password))
+            .map(|_| message_body("Account deleted")),
+        _ => return (404, error_body("Not found")),
+    };
+
+    match result {
+        Ok(body) => (200, body),
+        Err(StoreError::BadRequest(message)) => (400, error_body(&message)),
+        Err(StoreError::Unauthorized(message)) => (401, error_body(&message)),
+        Err(StoreError::Conflict { revision, user_data }) => (409, json!({
+            "success": false,
+            "message": "User data was changed by another device",
+            "user_data": user_data,
+            "revision": revision,
+        })),
+        Err(StoreError::Locked { retry_after }) => (429, json!({
+            "success": false,
+            "message": format!("Too many failed attempts. Try again in {} seconds", retry_after),
+            "retry_after": retry_after,
+        })),
+        Err(StoreError::Io(e)) => {
+            eprintln!("Storage error: {}", e);
+            (500, error_body("Internal storage error"))
+        }
+    }
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(body: &str) -> Result<T, StoreError> {
+    serde_json::from_str(body).map_err(|e| StoreError::BadRequest(format!("Invalid request: {}", e)))
+}
+
+fn bearer_token(request: &Request) -> Option<String> {
+    request.headers().iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .and_then(|header| header.value.as_str().strip_prefix("Bearer ").map(str::to_string))
+}
+
+fn auth_body(message: &str, account: &Account, token: &str) -> Value {
+    json!({
+        "success": true,
+        "message": message,
+        "user_data": account.user_data_with_identity(),
+        "session_token": token,
+        "email_verified": account.email_verified,
+    })
+}
+
+fn message_body(message: &str) -> Value {
+    json!({ "success": true, "message": message })
+}
+
+fn error_body(message: &str) -> Value {
+    json!({ "success": false, "message": message })
+}
@@ -0,0 +1,34 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Stands in for an email service: each message is written to a text file
+/// in the outbox directory and echoed to stdout.
+pub struct Outbox {
+    dir: PathBuf,
+}
+
+impl Outbox {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn send(&self, to: &str, subject: &str, body: &str) {
+        println!("[outbox] To: {} | {} | {}", to, subject, body);
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let recipient: String = to
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+            .collect();
+        let path = self.dir.join(format!("{}-{}.txt", nanos, recipient));
+        let contents = format!("To: {}\nSubject: {}\n\n{}\n", to, subject, body);
+
+        if let Err(e) = fs::create_dir_all(&self.dir).and_then(|_| fs::write(&path, contents)) {
+            eprintln!("Failed to write email to {}: {}", path.display(), e);
+        }
+    }
+}
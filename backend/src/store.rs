@@ -0,0 +1,415 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::outbox::Outbox;
+use sidequest_credentials::{validate_email, validate_password, validate_username};
+
+const SESSION_DURATION: Duration = Duration::from_secs(24 * 60 * 60); // 24 hours
+const VERIFICATION_CODE_DURATION: Duration = Duration::from_secs(24 * 60 * 60); // 24 hours
+const RESET_CODE_DURATION: Duration = Duration::from_secs(15 * 60); // 15 minutes
+const FREE_FAILED_ATTEMPTS: u32 = 5;
+const BASE_LOCKOUT: Duration = Duration::from_secs(30);
+const MAX_LOCKOUT: Duration = Duration::from_secs(15 * 60); // 15 minutes
+// Failures are forgotten once an email has gone this long without a new one or a lockout
+const FAILED_ATTEMPT_MEMORY: Duration = Duration::from_secs(15 * 60); // 15 minutes
+// Keeps a flood of made-up emails from growing the attempt table without limit
+const MAX_TRACKED_EMAILS: usize = 10_000;
+
+// One-time code sent by email
+#[derive(Serialize, Deserialize)]
+pub struct EmailCode {
+    code: String,
+    expires_at: u64,
+}
+
+impl EmailCode {
+    fn generate(duration: Duration) -> Self {
+        Self {
+            code: format!("{:06}", uuid::Uuid::new_v4().as_u128() % 1_000_000),
+            expires_at: unix_now() + duration.as_secs(),
+        }
+    }
+
+    fn matches(&self, code: &str) -> bool {
+        self.expires_at > unix_now() && self.code == code.trim()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Account {
+    pub user_id: String,
+    pub email: String,
+    pub username: String,
+    pub password_hash: String,
+    pub user_data: Value,
+    pub revision: u64,
+    #[serde(default)]
+    pub email_verified: bool,
+    #[serde(default)]
+    verification_code: Option<EmailCode>,
+    #[serde(default)]
+    reset_code: Option<EmailCode>,
+}
+
+impl Account {
+    /// The stored user data with the account identity fields filled in
+    pub fn user_data_with_identity(&self) -> Value {
+        let mut data = match &self.user_data {
+            Value::Object(map) => map.clone(),
+            _ => serde_json::Map::new(),
+        };
+        data.insert("user_id".to_string(), json!(self.user_id));
+        data.insert("email".to_string(), json!(self.email));
+        data.insert("username".to_string(), json!(self.username));
+        Value::Object(data)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Session {
+    email: String,
+    expires_at: u64,
+}
+
+// Failed password or reset code attempts for one email
+#[derive(Default)]
+struct FailedAttempts {
+    count: u32,
+    last_failed_at: u64,
+    locked_until: u64,
+}
+
+impl FailedAttempts {
+    fn is_stale(&self, now: u64) -> bool {
+        self.last_failed_at.max(self.locked_until) + FAILED_ATTEMPT_MEMORY.as_secs() <= now
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct StoreData {
+    accounts: HashMap<String, Account>,
+    sessions: HashMap<String, Session>,
+}
+
+#[derive(Debug)]
+pub enum StoreError {
+    BadRequest(String),
+    Unauthorized(String),
+    Conflict { revision: u64, user_data: Value },
+    /// Too many failed attempts for this email; seconds until the next one is allowed
+    Locked { retry_after: u64 },
+    Io(io::Error),
+}
+
+impl From<io::Error> for StoreError {
+    fn from(error: io::Error) -> Self {
+        StoreError::Io(error)
+    }
+}
+
+/// File-backed account, session and save storage
+pub struct Store {
+    path: PathBuf,
+    data: StoreData,
+    outbox: Outbox,
+    /// Kept in memory only, so bad attempts never cause a disk write
+    failed_attempts: HashMap<String, FailedAttempts>,
+}
+
+impl Store {
+    pub fn open(data_dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(data_dir)?;
+        let path = data_dir.join("store.json");
+
+        let data = if path.exists() {
+            let contents = fs::read_to_string(&path)?;
+            serde_json::from_str(&contents)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+        } else {
+            StoreData::default()
+        };
+
+        let outbox = Outbox::new(data_dir.join("outbox"));
+        Ok(Self { path, data, outbox, failed_attempts: HashMap::new() })
+    }
+
+    fn persist(&self) -> io::Result<()> {
+        let contents = serde_json::to_string_pretty(&self.data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, contents)?;
+        fs::rename(tmp_path, &self.path)
+    }
+
+    pub fn signup(&mut self, email: &str, password: &str, username: &str, user_data: Option<Value>) -> Result<(&Account, String), StoreError> {
+        let email = normalize_email(email);
+        if email.is_empty() || password.is_empty() || username.is_empty() {
+            return Err(StoreError::BadRequest("Please fill in all fields".to_string()));
+        }
+        validate_email(&email)
+            .and_then(|_| validate_username(username))
+            .and_then(|_| validate_password(password))
+            .map_err(StoreError::BadRequest)?;
+        if self.data.accounts.contains_key(&email) {
+            return Err(StoreError::BadRequest("An account with this email already exists".to_string()));
+        }
+
+        let password_hash = bcrypt::hash(password, bcrypt::DEFAULT_COST)
+            .map_err(|e| StoreError::Io(io::Error::new(io::ErrorKind::Other, e)))?;
+
+        let verification_code = EmailCode::generate(VERIFICATION_CODE_DURATION);
+        self.send_verification_email(&email, &verification_code);
+
+        self.data.accounts.insert(email.clone(), Account {
+            user_id: uuid::Uuid::new_v4().to_string(),
+            email: email.clone(),
+            username: username.to_string(),
+            password_hash,
+            user_data: user_data.unwrap_or_else(|| json!({})),
+            revision: 0,
+            email_verified: false,
+            verification_code: Some(verification_code),
+            reset_code: None,
+        });
+
+        let token = self.create_session(&email);
+        self.persist()?;
+        Ok((&self.data.accounts[&email], token))
+    }
+
+    pub fn verify_email(&mut self, token: Option<&str>, code: &str) -> Result<(), StoreError> {
+        let email = self.session_email(token)?;
+        let account = self.data.accounts.get_mut(&email).expect("session refers to a missing account");
+
+        if account.email_verified {
+            return Ok(());
+        }
+        if !account.verification_code.as_ref().is_some_and(|expected| expected.matches(code)) {
+            return Err(StoreError::BadRequest("Invalid or expired verification code".to_string()));
+        }
+
+        account.email_verified = true;
+        account.verification_code = None;
+        self.persist()?;
+        Ok(())
+    }
+
+    pub fn resend_verification(&mut self, token: Option<&str>) -> Result<(), StoreError> {
+        let email = self.session_email(token)?;
+        if self.data.accounts[&email].email_verified {
+            return Err(StoreError::BadRequest("Email is already verified".to_string()));
+        }
+
+        let verification_code = EmailCode::generate(VERIFICATION_CODE_DURATION);
+        self.send_verification_email(&email, &verification_code);
+        self.data.accounts.get_mut(&email).expect("session refers to a missing account")
+            .verification_code = Some(verification_code);
+        self.persist()?;
+        Ok(())
+    }
+
+    /// Emails a reset code if the account exists. Succeeds either way so
+    /// callers can't probe which emails have accounts.
+    pub fn request_password_reset(&mut self, email: &str) -> Result<(), StoreError> {
+        let email = normalize_email(email);
+        let Some(account) = self.data.accounts.get_mut(&email) else {
+            return Ok(());
+        };
+
+        let reset_code = EmailCode::generate(RESET_CODE_DURATION);
+        self.outbox.send(
+            &email,
+            "Reset your SideQuest password",
+            &format!("Your password reset code is {}. It expires in 15 minutes.", reset_code.code),
+        );
+        account.reset_code = Some(reset_code);
+        self.persist()?;
+        Ok(())
+    }
+
+    /// Sets a new password and signs out every existing session
+    pub fn reset_password(&mut self, email: &str, code: &str, new_password: &str) -> Result<(), StoreError> {
+        let email = normalize_email(email);
+        if new_password.is_empty() {
+            return Err(StoreError::BadRequest("Please enter a new password".to_string()));
+        }
+        validate_password(new_password).map_err(StoreError::BadRequest)?;
+        self.check_lockout(&email)?;
+
+        let code_matches = self.data.accounts.get(&email)
+            .and_then(|account| account.reset_code.as_ref())
+            .is_some_and(|expected| expected.matches(code));
+        if !code_matches {
+            self.record_failed_attempt(&email);
+            return Err(StoreError::BadRequest("Invalid or expired reset code".to_string()));
+        }
+
+        self.failed_attempts.remove(&email);
+        let account = self.data.accounts.get_mut(&email).expect("reset code belongs to an account");
+
+        account.password_hash = bcrypt::hash(new_password, bcrypt::DEFAULT_COST)
+            .map_err(|e| StoreError::Io(io::Error::new(io::ErrorKind::Other, e)))?;
+        account.reset_code = None;
+        // Receiving the code proves control of the inbox
+        account.email_verified = true;
+        account.verification_code = None;
+
+        self.data.sessions.retain(|_, session| session.email != email);
+        self.persist()?;
+        Ok(())
+    }
+
+    /// Removes the account, its sessions and its saved data. The password is
+    /// required again so a stolen session token can't delete an account.
+    pub fn delete_account(&mut self, token: Option<&str>, password: &str) -> Result<(), StoreError> {
+        let email = self.session_email(token)?;
+        self.check_lockout(&email)?;
+
+        let verified = bcrypt::verify(password, &self.data.accounts[&email].password_hash).unwrap_or(false);
+        if !verified {
+            self.record_failed_attempt(&email);
+            return Err(StoreError::Unauthorized("Incorrect password".to_string()));
+        }
+
+        self.data.accounts.remove(&email);
+        self.data.sessions.retain(|_, session| session.email != email);
+        self.failed_attempts.remove(&email);
+        self.persist()?;
+        Ok(())
+    }
+
+    fn check_lockout(&self, email: &str) -> Result<(), StoreError> {
+        let now = unix_now();
+        match self.failed_attempts.get(email) {
+            Some(attempts) if attempts.locked_until > now => Err(StoreError::Locked {
+                retry_after: attempts.locked_until - now,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Locks the email out for longer with each failure past the free attempts.
+    /// Emails without an account are tracked too, so a lockout doesn't reveal
+    /// which ones exist.
+    fn record_failed_attempt(&mut self, email: &str) {
+        let now = unix_now();
+        self.failed_attempts.retain(|_, attempts| !attempts.is_stale(now));
+        if self.failed_attempts.len() >= MAX_TRACKED_EMAILS && !self.failed_attempts.contains_key(email) {
+            let oldest = self.failed_attempts.iter()
+                .min_by_key(|(_, attempts)| attempts.last_failed_at)
+                .map(|(email, _)| email.clone());
+            if let Some(oldest) = oldest {
+                self.failed_attempts.remove(&oldest);
+            }
+        }
+
+        let attempts = self.failed_attempts.entry(email.to_string()).or_default();
+        attempts.count += 1;
+        attempts.last_failed_at = now;
+
+        if let Some(extra) = attempts.count.checked_sub(FREE_FAILED_ATTEMPTS) {
+            let lockout = BASE_LOCKOUT.saturating_mul(2u32.saturating_pow(extra)).min(MAX_LOCKOUT);
+            attempts.locked_until = now + lockout.as_secs();
+        }
+    }
+
+    fn send_verification_email(&self, email: &str, verification_code: &EmailCode) {
+        self.outbox.send(
+            email,
+            "Verify your SideQuest email",
+            &format!("Your verification code is {}.", verification_code.code),
+        );
+    }
+
+    pub fn login(&mut self, email: &str, password: &str) -> Result<(&Account, String), StoreError> {
+        let email = normalize_email(email);
+        self.check_lockout(&email)?;
+
+        // Unknown emails still pay for a bcrypt check, so they don't answer faster
+        let verified = match self.data.accounts.get(&email) {
+            Some(account) => bcrypt::verify(password, &account.password_hash).unwrap_or(false),
+            None => {
+                let _ = bcrypt::verify(password, dummy_password_hash());
+                false
+            }
+        };
+
+        if !verified {
+            self.record_failed_attempt(&email);
+            return Err(StoreError::Unauthorized("Invalid email or password".to_string()));
+        }
+
+        self.failed_attempts.remove(&email);
+        let token = self.create_session(&email);
+        self.persist()?;
+        Ok((&self.data.accounts[&email], token))
+    }
+
+    pub fn user_data(&self, token: Option<&str>) -> Result<&Account, StoreError> {
+        let email = self.session_email(token)?;
+        Ok(&self.data.accounts[&email])
+    }
+
+    /// Stores new user data if `base_revision` matches the current revision,
+    /// so a device that hasn't seen the latest copy can't overwrite it.
+    pub fn put_user_data(&mut self, token: Option<&str>, user_data: Value, base_revision: u64) -> Result<u64, StoreError> {
+        let email = self.session_email(token)?;
+        let account = self.data.accounts.get_mut(&email).expect("session refers to a missing account");
+
+        if account.revision != base_revision {
+            return Err(StoreError::Conflict {
+                revision: account.revision,
+                user_data: account.user_data_with_identity(),
+            });
+        }
+
+        account.user_data = user_data;
+        account.revision += 1;
+        let revision = account.revision;
+        self.persist()?;
+        Ok(revision)
+    }
+
+    fn create_session(&mut self, email: &str) -> String {
+        let token = uuid::Uuid::new_v4().to_string();
+        self.data.sessions.insert(token.clone(), Session {
+            email: email.to_string(),
+            expires_at: unix_now() + SESSION_DURATION.as_secs(),
+        });
+        token
+    }
+
+    fn session_email(&self, token: Option<&str>) -> Result<String, StoreError> {
+        let session = token
+            .and_then(|token| self.data.sessions.get(token))
+            .filter(|session| session.expires_at > unix_now())
+            .ok_or_else(|| StoreError::Unauthorized("Session is invalid or expired".to_string()))?;
+
+        if !self.data.accounts.contains_key(&session.email) {
+            return Err(StoreError::Unauthorized("Account no longer exists".to_string()));
+        }
+        Ok(session.email.clone())
+    }
+}
+
+// Hash checked against when the email has no account
+fn dummy_password_hash() -> &'static str {
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| bcrypt::hash("not a real password", bcrypt::DEFAULT_COST).unwrap_or_default())
+}
+
+fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,210 @@
+//! Email codes and sessions in the file-backed store. Each test runs against
+//! its own data directory and reads the codes back out of the outbox, the
+//! same way a developer would while trying the flows by hand.
+
+use serde_json::Value;
+use sidequest_backend::store::{Store, StoreError};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const EMAIL: &str = "player@example.com";
+const PASSWORD: &str = "hunter22";
+
+fn open_store() -> (TempDir, Store) {
+    let dir = tempfile::tempdir().expect("failed to create data directory");
+    let store = Store::open(dir.path()).expect("failed to open store");
+    (dir, store)
+}
+
+fn sign_up(store: &mut Store) -> String {
+    let (_, token) = store.signup(EMAIL, PASSWORD, "Player", None).expect("signup failed");
+    token
+}
+
+/// The code in the newest outbox message whose subject contains `subject`
+fn latest_code(data_dir: &Path, subject: &str) -> String {
+    let mut messages: Vec<_> = fs::read_dir(data_dir.join("outbox"))
+        .expect("outbox is missing")
+        .map(|entry| entry.expect("failed to read outbox").path())
+        .collect();
+    // File names start with the time they were written
+    messages.sort();
+
+    let message = messages.iter().rev()
+        .map(|path| fs::read_to_string(path).expect("failed to read email"))
+        .find(|contents| contents.lines().any(|line| line.starts_with("Subject:") && line.contains(subject)))
+        .unwrap_or_else(|| panic!("no email with subject {:?}", subject));
+
+    let (_, rest) = message.split_once("code is ").expect("email has no code");
+    rest.chars().take_while(char::is_ascii_digit).collect()
+}
+
+#[test]
+fn signup_emails_a_code_that_verifies_the_address() {
+    let (dir, mut store) = open_store();
+    let token = sign_up(&mut store);
+    assert!(!store.user_data(Some(&token)).unwrap().email_verified);
+
+    let code = latest_code(dir.path(), "Verify");
+    assert_eq!(code.len(), 6);
+
+    let wrong_code = if code == "000000" { "111111" } else { "000000" };
+    assert!(matches!(store.verify_email(Some(&token), wrong_code), Err(StoreError::BadRequest(_))));
+    assert!(!store.user_data(Some(&token)).unwrap().email_verified);
+
+    store.verify_email(Some(&token), &format!(" {} ", code)).expect("code should verify");
+    assert!(store.user_data(Some(&token)).unwrap().email_verified);
+}
+
+#[test]
+fn resending_verification_replaces_the_old_code() {
+    let (dir, mut store) = open_store();
+    let token = sign_up(&mut store);
+    let first_code = latest_code(dir.path(), "Verify");
+
+    store.resend_verification(Some(&token)).unwrap();
+    let second_code = latest_code(dir.path(), "Verify");
+
+    if first_code != second_code {
+        assert!(store.verify_email(Some(&token), &first_code).is_err());
+    }
+    store.verify_email(Some(&token), &second_code).expect("new code should verify");
+    assert!(matches!(store.resend_verification(Some(&token)), Err(StoreError::BadRequest(_))));
+}
+
+#[test]
+fn reset_code_works_once() {
+    let (dir, mut store) = open_store();
+    sign_up(&mut store);
+
+    store.request_password_reset(EMAIL).unwrap();
+    let code = latest_code(dir.path(), "Reset");
+
+    store.reset_password(EMAIL, &code, "new password").expect("reset should succeed");
+    assert!(matches!(
+        store.reset_password(EMAIL, &code, "another password"),
+        Err(StoreError::BadRequest(_)),
+    ));
+
+    assert!(store.login(EMAIL, PASSWORD).is_err());
+    let (account, _) = store.login(EMAIL, "new password").expect("new password should log in");
+    // Receiving the code proves the address works
+    assert!(account.email_verified);
+}
+
+#[test]
+fn expired_reset_code_is_rejected() {
+    let (dir, mut store) = open_store();
+    sign_up(&mut store);
+    store.request_password_reset(EMAIL).unwrap();
+    let code = latest_code(dir.path(), "Reset");
+    drop(store);
+
+    // Move the expiry into the past on disk, as if 15 minutes had gone by
+    let store_path = dir.path().join("store.json");
+    let mut data: Value = serde_json::from_str(&fs::read_to_string(&store_path).unwrap()).unwrap();
+    data["accounts"][EMAIL]["reset_code"]["expires_at"] = Value::from(1);
+    fs::write(&store_path, data.to_string()).unwrap();
+
+    let mut store = Store::open(dir.path()).unwrap();
+    assert!(matches!(store.reset_password(EMAIL, &code, "new password"), Err(StoreError::BadRequest(_))));
+    store.login(EMAIL, PASSWORD).expect("old password should still work");
+}
+
+#[test]
+fn reset_for_unknown_email_sends_nothing() {
+    let (dir, mut store) = open_store();
+    store.request_password_reset("nobody@example.com").expect("should not reveal missing accounts");
+    assert!(!dir.path().join("outbox").exists());
+}
+
+#[test]
+fn password_reset_signs_out_every_session() {
+    let (dir, mut store) = open_store();
+    let signup_token = sign_up(&mut store);
+    let (_, login_token) = store.login(EMAIL, PASSWORD).unwrap();
+    assert!(store.user_data(Some(&login_token)).is_ok());
+
+    store.request_password_reset(EMAIL).unwrap();
+    let code = latest_code(dir.path(), "Reset");
+    store.reset_password(EMAIL, &code, "new password").unwrap();
+
+    for token in [&signup_token, &login_token] {
+        assert!(matches!(store.user_data(Some(token)), Err(StoreError::Unauthorized(_))));
+    }
+
+    // Sessions stay gone after a restart too
+    let store = Store::open(dir.path()).unwrap();
+    assert!(store.user_data(Some(&login_token)).is_err());
+}
+
+#[test]
+fn signup_enforces_username_and_password_rules() {
+    let (dir, mut store) = open_store();
+    let cases = [
+        ("Player", "short1"),
+        ("Player", "lowercaseab"),
+        ("ab", PASSWORD),
+        ("_player", PASSWORD),
+        ("two words", PASSWORD),
+    ];
+    for (username, password) in cases {
+        assert!(
+            matches!(store.signup(EMAIL, password, username, None), Err(StoreError::BadRequest(_))),
+            "{:?} / {:?} should be rejected", username, password,
+        );
+    }
+    // Nothing was stored or emailed
+    assert!(!dir.path().join("outbox").exists());
+    sign_up(&mut store);
+}
+
+#[test]
+fn reset_enforces_password_rules() {
+    let (dir, mut store) = open_store();
+    sign_up(&mut store);
+    store.request_password_reset(EMAIL).unwrap();
+    let code = latest_code(dir.path(), "Reset");
+
+    assert!(matches!(store.reset_password(EMAIL, &code, "weakpass"), Err(StoreError::BadRequest(_))));
+    // The code is still good for a valid password
+    store.reset_password(EMAIL, &code, "better pass 2").unwrap();
+}
+
+#[test]
+fn signup_rejects_an_invalid_email() {
+    let (dir, mut store) = open_store();
+    for email in ["a@b", "player", "player@localhost", "pla yer@example.com"] {
+        assert!(
+            matches!(store.signup(email, PASSWORD, "Player", None), Err(StoreError::BadRequest(_))),
+            "{:?} should be rejected", email,
+        );
+    }
+    assert!(!dir.path().join("outbox").exists());
+}
+
+#[test]
+fn failed_logins_lock_the_email_without_touching_the_store_file() {
+    let (dir, mut store) = open_store();
+    sign_up(&mut store);
+    let store_path = dir.path().join("store.json");
+    let saved = fs::read_to_string(&store_path).unwrap();
+
+    for email in [EMAIL, "nobody@example.com"] {
+        for _ in 0..5 {
+            assert!(matches!(store.login(email, "wrong password"), Err(StoreError::Unauthorized(_))));
+        }
+        // Unknown emails lock the same way, so a lockout doesn't reveal an account
+        assert!(
+            matches!(store.login(email, "wrong password"), Err(StoreError::Locked { retry_after: 1..=30 })),
+            "{} should be locked", email,
+        );
+    }
+    assert!(matches!(store.login(EMAIL, PASSWORD), Err(StoreError::Locked { .. })));
+    assert_eq!(fs::read_to_string(&store_path).unwrap(), saved);
+
+    // Reopening starts with a clean slate
+    let mut store = Store::open(dir.path()).unwrap();
+    store.login(EMAIL, PASSWORD).expect("lockout should not outlive the process");
+}
@@ -0,0 +1,140 @@
+//! Credential rules checked by the game's auth form before anything is sent,
+//! and again by the backend so a client that skips the form can't store
+//! anything weaker.
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+pub const MIN_USERNAME_LENGTH: usize = 3;
+pub const MAX_USERNAME_LENGTH: usize = 20;
+const MAX_EMAIL_LENGTH: usize = 254;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PasswordStrength {
+    Weak,
+    Fair,
+    Good,
+    Strong,
+}
+
+impl PasswordStrength {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PasswordStrength::Weak => "Weak",
+            PasswordStrength::Fair => "Fair",
+            PasswordStrength::Good => "Good",
+            PasswordStrength::Strong => "Strong",
+        }
+    }
+
+    /// How full the strength meter should be, from 0.25 to 1.0
+    pub fn fraction(&self) -> f32 {
+        (*self as u8 + 1) as f32 / 4.0
+    }
+}
+
+/// Strict check for new accounts
+pub fn validate_email(email: &str) -> Result<(), String> {
+    let email = email.trim();
+    if email.is_empty() {
+        return Err("Email is required".to_string());
+    }
+    if email.len() > MAX_EMAIL_LENGTH || email.chars().any(char::is_whitespace) {
+        return Err("Enter a valid email address".to_string());
+    }
+
+    let Some((local, domain)) = email.split_once('@') else {
+        return Err("Email must contain an @".to_string());
+    };
+    if local.is_empty() || domain.contains('@') {
+        return Err("Enter a valid email address".to_string());
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    let valid_labels = labels.iter().all(|label| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+    if labels.len() < 2 || !valid_labels {
+        return Err("Enter a valid email domain, like example.com".to_string());
+    }
+
+    Ok(())
+}
+
+/// Loose check for an account that already exists. Older accounts and local
+/// or internationalized addresses may not pass the signup rules, and the
+/// server knows best whether the address is real.
+pub fn validate_existing_email(email: &str) -> Result<(), String> {
+    let email = email.trim();
+    if email.is_empty() {
+        return Err("Email is required".to_string());
+    }
+    if !email.contains('@') {
+        return Err("Email must contain an @".to_string());
+    }
+    Ok(())
+}
+
+/// Usernames are 3-20 letters, digits, `_` or `-`, starting with a letter or digit
+pub fn validate_username(username: &str) -> Result<(), String> {
+    let length = username.chars().count();
+    if !(MIN_USERNAME_LENGTH..=MAX_USERNAME_LENGTH).contains(&length) {
+        return Err(format!(
+            "Username must be {}-{} characters",
+            MIN_USERNAME_LENGTH, MAX_USERNAME_LENGTH
+        ));
+    }
+    if !username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err("Username can only use letters, numbers, _ and -".to_string());
+    }
+    if !username.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return Err("Username must start with a letter or number".to_string());
+    }
+    Ok(())
+}
+
+pub fn validate_password(password: &str) -> Result<(), String> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(format!("Password must be at least {} characters", MIN_PASSWORD_LENGTH));
+    }
+    if password_strength(password) == PasswordStrength::Weak {
+        return Err("Password is too weak - mix letters with numbers or symbols".to_string());
+    }
+    Ok(())
+}
+
+/// Scores length and character variety
+pub fn password_strength(password: &str) -> PasswordStrength {
+    let length = password.chars().count();
+    let classes = [
+        password.chars().any(|c| c.is_ascii_lowercase()),
+        password.chars().any(|c| c.is_ascii_uppercase()),
+        password.chars().any(|c| c.is_ascii_digit()),
+        password.chars().any(|c| !c.is_ascii_alphanumeric()),
+    ]
+    .iter()
+    .filter(|present| **present)
+    .count();
+
+    let mut score = 0;
+    if length >= MIN_PASSWORD_LENGTH {
+        score += 1;
+    }
+    if length >= 12 {
+        score += 1;
+    }
+    if classes >= 2 {
+        score += 1;
+    }
+    if classes >= 3 {
+        score += 1;
+    }
+
+    match score {
+        0 | 1 => PasswordStrength::Weak,
+        2 => PasswordStrength::Fair,
+        3 => PasswordStrength::Good,
+        _ => PasswordStrength::Strong,
+    }
+}
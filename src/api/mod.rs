@@ -0,0 +1,308 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+use crate::resources::{migrate_response, MigrationError, UserData};
+
+pub use sidequest_credentials as validation;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Errors from backend requests
+#[derive(Debug, Clone)]
+pub enum ApiError {
+    /// The backend could not be reached; callers fall back to local saves
+    Offline(String),
+    /// The backend answered but refused the request
+    Rejected { status: u16, message: String },
+    /// The backend answered with something we couldn't parse
+    InvalidResponse(String),
+    /// Another device kept storing newer data before our upload could land
+    Conflict,
+}
+
+impl ApiError {
+    pub fn is_offline(&self) -> bool {
+        matches!(self, ApiError::Offline(_))
+    }
+
+    /// The session token was refused, so retrying won't help until the user logs in again
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(self, ApiError::Rejected { status: 401, .. })
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Offline(e) => write!(f, "Can't reach the server: {}", e),
+            ApiError::Rejected { message, .. } => write!(f, "{}", message),
+            ApiError::InvalidResponse(e) => write!(f, "Failed to parse response: {}", e),
+            ApiError::Conflict => write!(f, "User data kept changing on another device"),
+        }
+    }
+}
+
+impl From<MigrationError> for ApiError {
+    fn from(error: MigrationError) -> Self {
+        ApiError::InvalidResponse(error.to_string())
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_decode() {
+            ApiError::InvalidResponse(error.to_string())
+        } else {
+            ApiError::Offline(error.to_string())
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct LoginRequest {
+    email: String,
+    password: String,
+}
+
+#[derive(Serialize)]
+struct SignUpRequest<'a> {
+    email: String,
+    password: String,
+    username: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_data: Option<&'a UserData>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AuthResponse {
+    pub success: bool,
+    pub message: String,
+    pub user_data: Option<UserData>,
+    pub session_token: Option<String>,
+    /// Backends without email verification don't send this
+    #[serde(default = "default_email_verified")]
+    pub email_verified: bool,
+    /// Seconds until the backend accepts another attempt, when locked out
+    #[serde(default)]
+    pub retry_after: Option<u64>,
+}
+
+fn default_email_verified() -> bool {
+    true
+}
+
+// Reply from endpoints that only report success and a message
+#[derive(Serialize, Deserialize)]
+pub struct MessageResponse {
+    pub success: bool,
+    pub message: String,
+    #[serde(default)]
+    pub retry_after: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct VerifyEmailRequest {
+    code: String,
+}
+
+#[derive(Serialize)]
+struct PasswordResetRequest {
+    email: String,
+}
+
+#[derive(Serialize)]
+struct ResetPasswordRequest {
+    email: String,
+    code: String,
+    new_password: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RemoteUserData {
+    pub user_data: UserData,
+    pub revision: u64,
+}
+
+#[derive(Serialize)]
+struct DeleteAccountRequest {
+    password: String,
+}
+
+#[derive(Serialize)]
+struct PutUserDataRequest<'a> {
+    user_data: &'a UserData,
+    base_revision: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PutUserDataResponse {
+    revision: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ErrorResponse {
+    message: String,
+}
+
+pub enum PutOutcome {
+    Stored { revision: u64 },
+    /// Someone else wrote a newer revision since we fetched
+    Conflict,
+}
+
+fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+pub async fn send_login_request(api_base_url: &str, email: String, password: String) -> Result<AuthResponse, ApiError> {
+    let login_data = LoginRequest { email, password };
+
+    let response = client()
+        .post(format!("{}/auth/login", api_base_url))
+        .json(&login_data)
+        .send()
+        .await?;
+
+    parse_auth_response(response).await
+}
+
+/// Creates an account. `user_data` seeds the new account, e.g. with a guest's progress.
+pub async fn send_signup_request(
+    api_base_url: &str,
+    email: String,
+    password: String,
+    username: String,
+    user_data: Option<&UserData>,
+) -> Result<AuthResponse, ApiError> {
+    let signup_data = SignUpRequest { email, password, username, user_data };
+
+    let response = client()
+        .post(format!("{}/auth/signup", api_base_url))
+        .json(&signup_data)
+        .send()
+        .await?;
+
+    parse_auth_response(response).await
+}
+
+pub async fn verify_email(api_base_url: &str, session_token: &str, code: String) -> Result<MessageResponse, ApiError> {
+    let response = client()
+        .post(format!("{}/auth/verify-email", api_base_url))
+        .bearer_auth(session_token)
+        .json(&VerifyEmailRequest { code })
+        .send()
+        .await?;
+
+    parse_auth_response(response).await
+}
+
+pub async fn resend_verification(api_base_url: &str, session_token: &str) -> Result<MessageResponse, ApiError> {
+    let response = client()
+        .post(format!("{}/auth/resend-verification", api_base_url))
+        .bearer_auth(session_token)
+        .send()
+        .await?;
+
+    parse_auth_response(response).await
+}
+
+pub async fn request_password_reset(api_base_url: &str, email: String) -> Result<MessageResponse, ApiError> {
+    let response = client()
+        .post(format!("{}/auth/request-password-reset", api_base_url))
+        .json(&PasswordResetRequest { email })
+        .send()
+        .await?;
+
+    parse_auth_response(response).await
+}
+
+pub async fn reset_password(api_base_url: &str, email: String, code: String, new_password: String) -> Result<MessageResponse, ApiError> {
+    let response = client()
+        .post(format!("{}/auth/reset-password", api_base_url))
+        .json(&ResetPasswordRequest { email, code, new_password })
+        .send()
+        .await?;
+
+    parse_auth_response(response).await
+}
+
+/// Permanently deletes the account. The backend asks for the password again.
+pub async fn delete_account(api_base_url: &str, session_token: &str, password: String) -> Result<MessageResponse, ApiError> {
+    let response = client()
+        .delete(format!("{}/user", api_base_url))
+        .bearer_auth(session_token)
+        .json(&DeleteAccountRequest { password })
+        .send()
+        .await?;
+
+    parse_auth_response(response).await
+}
+
+pub async fn fetch_user_data(api_base_url: &str, session_token: &str) -> Result<RemoteUserData, ApiError> {
+    let response = client()
+        .get(format!("{}/user/data", api_base_url))
+        .bearer_auth(session_token)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(rejection(response).await);
+    }
+
+    let body = response.text().await?;
+    parse_response(&body)
+}
+
+pub async fn put_user_data(api_base_url: &str, session_token: &str, user_data: &UserData, base_revision: u64) -> Result<PutOutcome, ApiError> {
+    let response = client()
+        .put(format!("{}/user/data", api_base_url))
+        .bearer_auth(session_token)
+        .json(&PutUserDataRequest { user_data, base_revision })
+        .send()
+        .await?;
+
+    if response.status() == reqwest::StatusCode::CONFLICT {
+        return Ok(PutOutcome::Conflict);
+    }
+    if !response.status().is_success() {
+        return Err(rejection(response).await);
+    }
+
+    let stored = response.json::<PutUserDataResponse>().await?;
+    Ok(PutOutcome::Stored { revision: stored.revision })
+}
+
+// Auth endpoints report credential errors in the body, so parse it whatever the status
+async fn parse_auth_response<T: DeserializeOwned>(response: reqwest::Response) -> Result<T, ApiError> {
+    let status = response.status();
+    let body = response.text().await?;
+
+    match parse_response::<T>(&body) {
+        Ok(auth_response) => Ok(auth_response),
+        Err(_) if !status.is_success() => Err(ApiError::Rejected {
+            status: status.as_u16(),
+            message: format!("Request failed with status: {}", status),
+        }),
+        Err(e) => Err(e),
+    }
+}
+
+/// Parses a response body, upgrading any user data from an older schema
+pub fn parse_response<T: DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+    let mut value = serde_json::from_str(body).map_err(MigrationError::from)?;
+    migrate_response(&mut value)?;
+    Ok(serde_json::from_value(value).map_err(MigrationError::from)?)
+}
+
+async fn rejection(response: reqwest::Response) -> ApiError {
+    let status = response.status();
+    let message = response.json::<ErrorResponse>().await
+        .map(|body| body.message)
+        .unwrap_or_else(|_| format!("Request failed with status: {}", status));
+
+    ApiError::Rejected { status: status.as_u16(), message }
+}
@@ -1,10 +1,12 @@
 mod camera;
 mod player;
+mod platform;
 mod light;
 mod skybox;
 
 pub use camera::*;
 pub use player::*;
+pub use platform::*;
 pub use light::*;
 pub use skybox::*;
 
@@ -16,6 +18,8 @@ pub struct DeathVignette {
     pub start_time: f32,
     pub duration: f32,
     pub max_intensity: f32,
+    /// Set once the player has been moved back, while the vignette finishes fading
+    pub respawned: bool,
 }
 
 impl Default for DeathVignette {
@@ -24,10 +28,41 @@ impl Default for DeathVignette {
             start_time: 0.0,
             duration: 1.5, // 1.5 seconds
             max_intensity: 0.8,
+            respawned: false,
         }
     }
 }
 
-// Trampoline top surface marker
+// Brief red flash over the screen when the player takes a hit
 #[derive(Component)]
-pub struct TrampolineTopSurface;
+pub struct DamageFlash {
+    pub start_time: f32,
+    pub duration: f32,
+    pub max_intensity: f32,
+}
+
+impl Default for DamageFlash {
+    fn default() -> Self {
+        Self {
+            start_time: 0.0,
+            duration: 0.4,
+            max_intensity: 0.45,
+        }
+    }
+}
+
+// Marks walkable top surfaces whose edges the player can grab
+#[derive(Component)]
+pub struct LedgeSurface;
+
+// Marks colliders the player can slide down and jump off
+#[derive(Component)]
+pub struct WallSurface;
+
+// Colliders the player jumps up through and lands on; solid only while the
+// player's feet are above `top`, the platform's surface height relative to
+// the collider's centre
+#[derive(Component)]
+pub struct OneWayPlatform {
+    pub top: f32,
+}
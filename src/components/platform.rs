@@ -1,2 +1,64 @@
-// Platform components moved to plugins/physics.rs to avoid conflicts
-// This file is kept for backwards compatibility
\ No newline at end of file
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+// What a platform's top is made of, and how it changes the player's footing
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub enum SurfaceMaterial {
+    #[default]
+    Normal,
+    /// Slippery: speeding up, slowing down and turning all take longer
+    Ice,
+    /// Mud that slows the player down
+    Sticky,
+    /// Carries the player along x at `speed`, which they can walk with or against
+    Conveyor { speed: f32 },
+    /// Launches the player upwards at `strength` on landing
+    Bouncy { strength: f32 },
+}
+
+impl SurfaceMaterial {
+    /// Colour that marks the surface, replacing the platform type's
+    pub fn color(&self) -> Option<Color> {
+        match self {
+            SurfaceMaterial::Normal => None,
+            SurfaceMaterial::Ice => Some(Color::rgb(0.7, 0.9, 1.0)),
+            SurfaceMaterial::Sticky => Some(Color::rgb(0.35, 0.25, 0.15)),
+            SurfaceMaterial::Conveyor { .. } => Some(Color::rgb(0.8, 0.7, 0.2)),
+            SurfaceMaterial::Bouncy { .. } => Some(Color::rgb(0.2, 0.8, 0.2)),
+        }
+    }
+
+    /// Friction of the top collider, for the dynamic player body
+    pub fn friction(&self) -> Friction {
+        match self {
+            SurfaceMaterial::Ice => Friction {
+                coefficient: 0.0,
+                combine_rule: CoefficientCombineRule::Min,
+            },
+            SurfaceMaterial::Sticky => Friction {
+                coefficient: 1.5,
+                combine_rule: CoefficientCombineRule::Max,
+            },
+            _ => Friction {
+                coefficient: 0.8,
+                combine_rule: CoefficientCombineRule::Max,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlatformType {
+    Ground,
+    Floating,
+    Small,
+    SteppingStone,
+    Bridge,
+    Moving,
+    Trampoline,
+    Wall,
+    /// Gives way shortly after the player lands, then comes back
+    Crumbling,
+    /// Blinks in and out with `PlatformRhythm`
+    Timed,
+}
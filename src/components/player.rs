@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use super::{PlatformType, SurfaceMaterial};
 
 #[derive(Component)]
 pub struct Player {
@@ -9,7 +10,246 @@ pub struct Player {
     pub is_dive_rolling: bool,
     pub flip_direction: Vec3,
     pub facing_left: bool,
-    pub is_falling: bool,
+    /// Died and waiting for the respawn
+    pub is_dead: bool,
+    /// Holding onto a ledge, or climbing up from one
+    pub is_hanging: bool,
+}
+
+/// Timers that make jumping forgiving: a press shortly before landing still
+/// counts, and so does one shortly after walking off a ledge
+#[derive(Component, Default)]
+pub struct JumpState {
+    /// Time left to act on a jump press that couldn't be used yet
+    pub buffer_timer: f32,
+    /// Time left to jump since the player was last on the ground
+    pub coyote_timer: f32,
+    /// Rising from a jump that can still be cut short by releasing the button
+    pub is_jumping: bool,
+}
+
+/// Contact with a `WallSurface` while airborne
+#[derive(Component, Default)]
+pub struct WallState {
+    /// Which side the wall is on: -1.0 for left, 1.0 for right
+    pub contact_side: Option<f32>,
+    /// Pressing into the wall while falling, so the fall is slowed
+    pub is_sliding: bool,
+    /// Time left during which a wall jump ignores movement input
+    pub lockout_timer: f32,
+}
+
+/// Where the player is hanging and how far the climb up has got
+#[derive(Component, Default)]
+pub struct LedgeState {
+    /// Player position while hanging
+    pub hang_position: Vec3,
+    /// Player position standing on the ledge once climbed up
+    pub climb_target: Vec3,
+    /// 0 to 1 through the climb up, if one has started
+    pub climb_progress: Option<f32>,
+    /// Time left before another ledge can be grabbed after dropping
+    pub regrab_timer: f32,
+}
+
+/// Recovery from being knocked back
+#[derive(Component, Default)]
+pub struct HurtState {
+    /// Time left during which the knockback ignores movement input
+    pub stun_timer: f32,
+}
+
+/// Hit points, and the grace period after a hit during which no more damage is taken
+#[derive(Component)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+    /// Time left during which hits are ignored
+    pub invulnerable_timer: f32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self {
+            current: max,
+            max,
+            invulnerable_timer: 0.0,
+        }
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable_timer > 0.0
+    }
+
+    /// Back to full, as on respawning or reaching a checkpoint
+    pub fn refill(&mut self) {
+        self.current = self.max;
+        self.invulnerable_timer = 0.0;
+    }
+}
+
+/// What the player is standing on, from the ground probe
+#[derive(Component)]
+pub struct GroundState {
+    /// Platform under the player, or the bare collider if it isn't part of one
+    pub entity: Option<Entity>,
+    /// Surface normal at the contact; straight up while airborne
+    pub normal: Vec3,
+    pub surface: Option<PlatformType>,
+    /// Material of the platform underfoot; `Normal` while airborne
+    pub material: SurfaceMaterial,
+    /// Velocity on the last airborne frame, reported as the landing impact
+    pub air_velocity: Vec3,
+    /// Velocity of the moving platform underfoot, handed to the player on leaving it
+    pub platform_velocity: Vec3,
+}
+
+impl Default for GroundState {
+    fn default() -> Self {
+        Self {
+            entity: None,
+            normal: Vec3::Y,
+            surface: None,
+            material: SurfaceMaterial::Normal,
+            air_velocity: Vec3::ZERO,
+            platform_velocity: Vec3::ZERO,
+        }
+    }
+}
+
+/// Air moves used since the player last stood on the ground
+#[derive(Component, Default)]
+pub struct AbilityState {
+    pub air_jumps_used: u32,
+    pub air_dashes_used: u32,
+    /// Time left in the current air dash
+    pub dash_timer: f32,
+    /// Slamming straight down until landing
+    pub is_ground_pounding: bool,
+}
+
+/// Frame rate the attack timings below are written at; the swing clips are
+/// played back at whatever speed fits them to the move.
+///
+/// The frame counts are placeholders, not timings taken from the clips, and
+/// Boss3 only has stand-in clips for its swings (see `preload_animations`).
+/// Retime the moves against real attack clips once the models have them.
+pub const ATTACK_FPS: f32 = 30.0;
+
+/// Melee moves: three ground swings that chain into each other, and one in the air
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttackMove {
+    Slash,
+    Backhand,
+    Finisher,
+    AirSlash,
+}
+
+impl AttackMove {
+    /// Length of the whole move, in frames
+    pub fn frames(&self) -> u32 {
+        match self {
+            AttackMove::Slash => 14,
+            AttackMove::Backhand => 14,
+            AttackMove::Finisher => 20,
+            AttackMove::AirSlash => 14,
+        }
+    }
+
+    /// Frames the hitbox is out for, from the first up to but not including the second
+    pub fn active_frames(&self) -> (u32, u32) {
+        match self {
+            AttackMove::Slash => (4, 8),
+            AttackMove::Backhand => (4, 8),
+            AttackMove::Finisher => (7, 12),
+            AttackMove::AirSlash => (3, 10),
+        }
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.frames() as f32 / ATTACK_FPS
+    }
+
+    pub fn is_active_at(&self, elapsed: f32) -> bool {
+        let (start, end) = self.active_frames();
+        let frame = elapsed * ATTACK_FPS;
+        frame >= start as f32 && frame < end as f32
+    }
+
+    /// Step of the combo a press during this move chains into
+    pub fn next(&self) -> Option<AttackMove> {
+        match self {
+            AttackMove::Slash => Some(AttackMove::Backhand),
+            AttackMove::Backhand => Some(AttackMove::Finisher),
+            AttackMove::Finisher | AttackMove::AirSlash => None,
+        }
+    }
+
+    /// Presses before the hitbox comes out are ignored rather than chained,
+    /// so mashing doesn't skip through the combo
+    pub fn can_chain_at(&self, elapsed: f32) -> bool {
+        self.next().is_some() && elapsed * ATTACK_FPS >= self.active_frames().0 as f32
+    }
+
+    pub fn is_air(&self) -> bool {
+        matches!(self, AttackMove::AirSlash)
+    }
+
+    pub fn damage(&self) -> u32 {
+        match self {
+            AttackMove::Finisher => 2,
+            _ => 1,
+        }
+    }
+
+    /// Half extents of the hitbox, and its centre relative to the player facing right
+    pub fn hitbox(&self) -> (Vec3, Vec3) {
+        match self {
+            AttackMove::Slash | AttackMove::Backhand => (Vec3::new(0.6, 0.5, 1.0), Vec3::new(0.8, 0.1, 0.0)),
+            AttackMove::Finisher => (Vec3::new(0.8, 0.6, 1.0), Vec3::new(1.0, 0.1, 0.0)),
+            // Wide sweep that also catches things just below
+            AttackMove::AirSlash => (Vec3::new(0.9, 0.8, 1.0), Vec3::new(0.6, -0.2, 0.0)),
+        }
+    }
+
+    /// Forward speed the player steps in with until the hitbox is gone
+    pub fn lunge(&self) -> f32 {
+        match self {
+            AttackMove::Slash | AttackMove::Backhand => 2.0,
+            AttackMove::Finisher => 4.0,
+            AttackMove::AirSlash => 0.0,
+        }
+    }
+
+    /// Velocity a hit sends the target off with, facing right
+    pub fn knockback(&self) -> Vec3 {
+        match self {
+            AttackMove::Finisher => Vec3::new(8.0, 4.0, 0.0),
+            AttackMove::AirSlash => Vec3::new(4.0, -2.0, 0.0),
+            _ => Vec3::new(4.0, 2.0, 0.0),
+        }
+    }
+}
+
+/// The melee attack being swung, and whether another is lined up after it
+#[derive(Component, Default)]
+pub struct AttackState {
+    /// Move being swung, if any
+    pub current: Option<AttackMove>,
+    /// Seconds into the current move
+    pub elapsed: f32,
+    /// Attack pressed late enough in the current move to chain the next combo step
+    pub queued: bool,
+    /// Targets the current move has already hit
+    pub hit: Vec<Entity>,
+    /// Used the air attack since last standing on the ground
+    pub air_attack_used: bool,
+}
+
+impl AttackState {
+    pub fn is_attacking(&self) -> bool {
+        self.current.is_some()
+    }
 }
 
 /// Marker component for the player's main collider
@@ -24,9 +24,18 @@ pub struct PlayerJumpEvent {
     pub entity: Entity,
 }
 
+/// The jump button was let go, cutting a rising jump short
+#[derive(Event)]
+pub struct PlayerJumpReleaseEvent {
+    pub entity: Entity,
+}
+
+/// The player touched down after being airborne
 #[derive(Event)]
 pub struct PlayerLandEvent {
     pub entity: Entity,
+    /// Velocity on the last airborne frame, before the ground stopped it
+    pub impact_velocity: Vec3,
 }
 
 #[derive(Event)]
@@ -35,16 +44,69 @@ pub struct PlayerFlipEvent {
     pub flip_type: FlipType,
 }
 
+/// An air move was requested; it only happens if unlocked and not used up
+#[derive(Event)]
+pub struct PlayerAbilityEvent {
+    pub entity: Entity,
+    pub ability: crate::resources::Ability,
+}
+
+/// The attack button was pressed; starts an attack, or chains the next step
+/// of the combo, if the player is free to swing
+#[derive(Event)]
+pub struct AttackEvent {
+    pub entity: Entity,
+}
+
+/// An attack hitbox touched something it can hurt; each target is hit once per swing
+#[derive(Event)]
+pub struct HitEvent {
+    pub attacker: Entity,
+    pub target: Entity,
+    pub attack: crate::components::AttackMove,
+    pub damage: u32,
+    /// Where the target was when the hit landed
+    pub position: Vec3,
+    /// Velocity the attack sends the target off with, for things that can move
+    pub knockback: Vec3,
+}
+
 #[derive(Event)]
 pub struct PlayerMoveEvent {
     pub entity: Entity,
     pub direction: Vec3,
 }
 
+/// Something hit the player, costing health and knocking them back
+#[derive(Event)]
+pub struct PlayerHurtEvent {
+    pub entity: Entity,
+    /// Health lost
+    pub damage: u32,
+    /// Velocity the player is sent off with
+    pub knockback: Vec3,
+    /// Reported as the cause of death if the hit takes the last of their health
+    pub cause: DeathCause,
+}
+
+/// The player died; the death vignette plays and they respawn at the last checkpoint
 #[derive(Event)]
-pub struct PlayerFallEvent {
+pub struct PlayerDeathEvent {
     pub entity: Entity,
     pub position: Vec3,
+    pub cause: DeathCause,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    /// Fell below the bottom of the world
+    Fall,
+    /// Dropped through one of the level's kill planes
+    KillPlane,
+    Spikes,
+    Laser,
+    Projectile,
+    Enemy,
 }
 
 #[derive(Debug, Clone)]
@@ -95,28 +157,43 @@ pub struct TrampolineBounceEvent {
 }
 
 // Authentication Events
-#[derive(Event)]
+#[derive(Event, Clone)]
 pub struct AuthRequestEvent {
     pub request_type: AuthRequestType,
     pub email: String,
     pub password: String,
     pub username: Option<String>,
+    /// Emailed verification or reset code
+    pub code: Option<String>,
 }
 
 #[derive(Event)]
 pub struct AuthResponseEvent {
+    pub request_type: AuthRequestType,
     pub success: bool,
     pub message: String,
     pub user_data: Option<crate::resources::UserData>,
     pub session_token: Option<String>,
+    pub offline: bool,
+    pub email_verified: bool,
+    /// Lockout reported by the backend after too many failed attempts
+    pub retry_after: Option<std::time::Duration>,
 }
 
 #[derive(Debug, Clone)]
 pub enum AuthRequestType {
     Login,
     SignUp,
+    VerifyEmail,
+    ResendVerification,
+    RequestPasswordReset,
+    ResetPassword,
 }
 
+// Cloud Sync Events
+#[derive(Event)]
+pub struct SyncRequestEvent;
+
 // Plugin to register all events
 pub struct EventsPlugin;
 
@@ -129,10 +206,15 @@ impl Plugin for EventsPlugin {
             .add_event::<GameOverEvent>()
             .add_event::<PlayerSpawnEvent>()
             .add_event::<PlayerJumpEvent>()
+            .add_event::<PlayerJumpReleaseEvent>()
             .add_event::<PlayerLandEvent>()
             .add_event::<PlayerFlipEvent>()
+            .add_event::<PlayerAbilityEvent>()
+            .add_event::<AttackEvent>()
+            .add_event::<HitEvent>()
             .add_event::<PlayerMoveEvent>()
-            .add_event::<PlayerFallEvent>()
+            .add_event::<PlayerDeathEvent>()
+            .add_event::<PlayerHurtEvent>()
             .add_event::<AnimationStartEvent>()
             .add_event::<AnimationEndEvent>()
             .add_event::<SystemErrorEvent>()
@@ -140,6 +222,7 @@ impl Plugin for EventsPlugin {
             .add_event::<CoinCollectedEvent>()
             .add_event::<TrampolineBounceEvent>()
             .add_event::<AuthRequestEvent>()
-            .add_event::<AuthResponseEvent>();
+            .add_event::<AuthResponseEvent>()
+            .add_event::<SyncRequestEvent>();
     }
 }
\ No newline at end of file
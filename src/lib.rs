@@ -5,6 +5,7 @@ pub mod resources;
 pub mod events;
 pub mod plugins;
 pub mod states;
+pub mod api;
 
 // Re-export commonly used items
 pub use components::*;
@@ -11,7 +11,6 @@ fn main() {
             DefaultPlugins.set(LogPlugin {
                 filter: "wgpu=error,bevy_render=info,bevy_gltf=error".to_string(),
                 level: bevy::log::Level::INFO,
-                ..default()
             }),
             EguiPlugin,
             RapierPhysicsPlugin::<NoUserData>::default(),
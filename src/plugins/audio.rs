@@ -28,8 +28,14 @@ fn load_audio_assets(
 fn handle_audio_events(
     mut jump_events: EventReader<PlayerJumpEvent>,
     mut flip_events: EventReader<PlayerFlipEvent>,
+    mut land_events: EventReader<PlayerLandEvent>,
+    mut death_events: EventReader<PlayerDeathEvent>,
+    mut hit_events: EventReader<HitEvent>,
     config: Res<GameConfig>,
 ) {
+    // Slower touchdowns, like stepping off a curb, stay silent
+    const LAND_SOUND_SPEED: f32 = 4.0;
+
     for _event in jump_events.read() {
         // Play jump sound
         if config.sfx_volume > 0.0 {
@@ -43,6 +49,27 @@ fn handle_audio_events(
             info!("Playing flip sound");
         }
     }
+
+    for event in land_events.read() {
+        // Play land sound
+        if config.sfx_volume > 0.0 && -event.impact_velocity.y >= LAND_SOUND_SPEED {
+            info!("Playing land sound");
+        }
+    }
+
+    for event in death_events.read() {
+        // Play death sound
+        if config.sfx_volume > 0.0 {
+            info!("Playing death sound for {:?}", event.cause);
+        }
+    }
+
+    for event in hit_events.read() {
+        // Play hit sound
+        if config.sfx_volume > 0.0 {
+            info!("Playing hit sound for {:?}", event.attack);
+        }
+    }
 }
 
 fn update_audio_settings(
@@ -1,10 +1,16 @@
 use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
 use bevy_egui::{egui, EguiContexts};
+use crate::api;
+use crate::api::validation::{self, PasswordStrength};
 use crate::states::{GameState, AuthState};
-use crate::resources::{AuthSession, AuthFormData, UserData, AuthConfig, PlayerStats};
-use crate::events::{AuthRequestEvent, AuthResponseEvent, AuthRequestType};
-use serde::{Deserialize, Serialize};
-use std::time::SystemTime;
+use crate::resources::{
+    AuthSession, AuthFormData, UserData, AuthConfig, ApiRuntime, LocalSave, SyncRecord, LoginBackoff,
+    DEMO_SESSION_TOKEN, DEMO_USER_ID,
+};
+use crate::events::{AuthRequestEvent, AuthResponseEvent, AuthRequestType, SyncRequestEvent};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 pub struct AuthPlugin;
 
@@ -16,35 +22,22 @@ impl Plugin for AuthPlugin {
             .add_systems(OnExit(GameState::Authentication), cleanup_auth_ui)
             .add_systems(Update, (
                 auth_ui_system,
+                dispatch_auth_requests,
+                poll_auth_request,
                 handle_auth_response,
                 check_session_validity,
-            ).run_if(in_state(GameState::Authentication)))
-            .add_systems(Update, auto_save_player_data.run_if(in_state(GameState::Playing)));
+            ).run_if(in_state(GameState::Authentication)));
     }
 }
 
 #[derive(Component)]
 pub struct AuthUiCleanup;
 
-#[derive(Serialize, Deserialize)]
-struct LoginRequest {
-    email: String,
-    password: String,
-}
-
-#[derive(Serialize, Deserialize)]
-struct SignUpRequest {
-    email: String,
-    password: String,
-    username: String,
-}
-
-#[derive(Serialize, Deserialize)]
-struct AuthResponse {
-    success: bool,
-    message: String,
-    user_data: Option<UserData>,
-    session_token: Option<String>,
+// In-flight auth request
+#[derive(Resource)]
+struct PendingAuthRequest {
+    handle: tokio::task::JoinHandle<AuthResponseEvent>,
+    request_type: AuthRequestType,
 }
 
 fn setup_auth_ui(mut commands: Commands) {
@@ -62,15 +55,36 @@ fn cleanup_auth_ui(
     commands.remove_resource::<AuthFormData>();
 }
 
+// The form being filled in, and how long failed logins lock it for
+#[derive(SystemParam)]
+struct AuthForm<'w> {
+    auth_form: ResMut<'w, AuthFormData>,
+    login_backoff: ResMut<'w, LoginBackoff>,
+}
+
+// The auth screen showing now, and where the menus go next
+#[derive(SystemParam)]
+struct AuthNavigation<'w> {
+    current_auth_state: Res<'w, State<AuthState>>,
+    auth_state: ResMut<'w, NextState<AuthState>>,
+    game_state: ResMut<'w, NextState<GameState>>,
+}
+
 fn auth_ui_system(
     mut contexts: EguiContexts,
-    mut auth_form: ResMut<AuthFormData>,
-    mut auth_state: ResMut<NextState<AuthState>>,
-    mut game_state: ResMut<NextState<GameState>>,
-    current_auth_state: Res<State<AuthState>>,
+    form: AuthForm,
+    navigation: AuthNavigation,
     mut auth_session: ResMut<AuthSession>,
+    mut auth_requests: EventWriter<AuthRequestEvent>,
+    auth_config: Res<AuthConfig>,
     mut commands: Commands,
 ) {
+    let AuthForm { mut auth_form, login_backoff } = form;
+    let AuthNavigation { current_auth_state, mut auth_state, mut game_state } = navigation;
+    let is_guest = auth_session.is_demo();
+    let current_auth_state = current_auth_state.get().clone();
+    let login_wait = login_backoff.remaining();
+
     egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
         ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
             ui.add_space(100.0);
@@ -80,299 +94,667 @@ fn auth_ui_system(
             ui.add_space(40.0);
 
             // Auth state tabs
-            ui.horizontal(|ui| {
-                                if ui.selectable_label(
-                    current_auth_state.get() == &AuthState::Login,
-                    "Login"
-                ).clicked() {
-                    auth_state.set(AuthState::Login);
-                    auth_form.clear();
-                }
+            if matches!(current_auth_state, AuthState::Login | AuthState::SignUp) {
+                ui.horizontal(|ui| {
+                    if ui.selectable_label(
+                        current_auth_state == AuthState::Login,
+                        "Login"
+                    ).clicked() {
+                        auth_state.set(AuthState::Login);
+                        auth_form.clear();
+                    }
 
-                if ui.selectable_label(
-                    current_auth_state.get() == &AuthState::SignUp,
-                    "Sign Up"
-                ).clicked() {
-                    auth_state.set(AuthState::SignUp);
-                    auth_form.clear();
-                }
-            });
+                    if ui.selectable_label(
+                        current_auth_state == AuthState::SignUp,
+                        "Sign Up"
+                    ).clicked() {
+                        auth_state.set(AuthState::SignUp);
+                        auth_form.clear();
+                    }
+                });
+            }
 
             ui.add_space(20.0);
 
-            // Error message
+            // Error and status messages
             if let Some(error) = &auth_form.error_message {
                 ui.colored_label(egui::Color32::RED, error);
                 ui.add_space(10.0);
             }
-
-            // Form fields
-            ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.email)
-                .hint_text("Email"));
-            ui.add_space(10.0);
-
-            ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.password)
-                .password(true)
-                .hint_text("Password"));
-            ui.add_space(10.0);
-
-            // Sign up specific fields
-            if current_auth_state.get() == &AuthState::SignUp {
-                ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.confirm_password)
-                    .password(true)
-                    .hint_text("Confirm Password"));
-                ui.add_space(10.0);
-
-                ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.username)
-                    .hint_text("Username"));
+            if let Some(info) = &auth_form.info_message {
+                ui.colored_label(egui::Color32::LIGHT_GREEN, info);
                 ui.add_space(10.0);
             }
 
-            ui.add_space(20.0);
+            match current_auth_state {
+                AuthState::Login | AuthState::SignUp => {
+                    let is_signup = current_auth_state == AuthState::SignUp;
+
+                    // Form fields
+                    ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.email)
+                        .hint_text("Email"));
+                    let email_check = if is_signup { validation::validate_email } else { validation::validate_existing_email };
+                    field_error(ui, &auth_form.email, email_check(&auth_form.email));
+                    ui.add_space(10.0);
+
+                    ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.password)
+                        .password(true)
+                        .hint_text("Password"));
+                    if is_signup {
+                        password_strength_meter(ui, &auth_form.password);
+                        field_error(ui, &auth_form.password, validation::validate_password(&auth_form.password));
+                    }
+                    ui.add_space(10.0);
+
+                    // Sign up specific fields
+                    if is_signup {
+                        ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.confirm_password)
+                            .password(true)
+                            .hint_text("Confirm Password"));
+                        field_error(ui, &auth_form.confirm_password, passwords_match(&auth_form));
+                        ui.add_space(10.0);
+
+                        ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.username)
+                            .hint_text("Username"));
+                        field_error(ui, &auth_form.username, validation::validate_username(&auth_form.username));
+                        ui.add_space(10.0);
+
+                        if is_guest {
+                            ui.label("Your guest progress will be kept in the new account");
+                        }
+                    }
 
-                        // Action buttons
-            ui.horizontal(|ui| {
-                let button_text = match current_auth_state.get() {
-                    AuthState::Login => "Login",
-                    AuthState::SignUp => "Sign Up",
-                    AuthState::Authenticating => "Authenticating...",
-                    AuthState::Authenticated => "Authenticated",
-                };
-
-                let button_enabled = !auth_form.is_loading &&
-                    !auth_form.email.is_empty() &&
-                    !auth_form.password.is_empty() &&
-                    (current_auth_state.get() == &AuthState::Login ||
-                     (!auth_form.username.is_empty() && auth_form.password == auth_form.confirm_password));
-
-                if ui.add_enabled(button_enabled, egui::Button::new(button_text)).clicked() {
-                    if current_auth_state.get() == &AuthState::Login {
-                        handle_login(&mut auth_form, &mut auth_state, &mut commands);
-                    } else if current_auth_state.get() == &AuthState::SignUp {
-                        handle_signup(&mut auth_form, &mut auth_state, &mut commands);
+                    if let (Some(wait), false) = (login_wait, is_signup) {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!("Too many failed attempts. Try again in {}s", wait.as_secs() + 1),
+                        );
                     }
-                }
 
-                if ui.button("Skip (Demo Mode)").clicked() {
-                    // For demo purposes, allow skipping auth
-                    auth_session.is_authenticated = true;
-                    auth_session.session_token = Some("demo_token".to_string());
-                    auth_session.expires_at = Some(SystemTime::now() + std::time::Duration::from_secs(3600));
-
-                    // Create demo user data
-                    let demo_user = UserData {
-                        user_id: Some("demo_user".to_string()),
-                        email: Some("demo@example.com".to_string()),
-                        username: Some("DemoPlayer".to_string()),
-                        player_stats: PlayerStats::default(),
-                        preferences: crate::resources::UserPreferences::default(),
-                    };
-                    commands.insert_resource(demo_user);
+                    ui.add_space(20.0);
+
+                    // Action buttons
+                    ui.horizontal(|ui| {
+                        let button_text = if is_signup { "Sign Up" } else { "Login" };
+
+                        let button_enabled = !auth_form.is_loading && if is_signup {
+                            signup_form_valid(&auth_form)
+                        } else {
+                            login_wait.is_none() &&
+                            validation::validate_existing_email(&auth_form.email).is_ok() &&
+                            !auth_form.password.is_empty()
+                        };
+
+                        if ui.add_enabled(button_enabled, egui::Button::new(button_text)).clicked() {
+                            if current_auth_state == AuthState::Login {
+                                handle_login(&mut auth_form, &mut auth_state, &mut auth_requests);
+                            } else {
+                                handle_signup(&mut auth_form, &mut auth_state, &mut auth_requests);
+                            }
+                        }
+
+                        let skip_text = if is_guest { "Continue as Guest" } else { "Skip (Demo Mode)" };
+                        if ui.button(skip_text).clicked() {
+                            start_guest_session(&mut auth_session, &auth_config, &mut commands);
+                            game_state.set(GameState::CharacterSelection);
+                        }
+                    });
+
+                    if current_auth_state == AuthState::Login {
+                        ui.add_space(10.0);
+                        if ui.link("Forgot password?").clicked() {
+                            auth_form.error_message = None;
+                            auth_form.info_message = None;
+                            auth_state.set(AuthState::ForgotPassword);
+                        }
+                    }
 
-                    game_state.set(GameState::CharacterSelection);
+                    ui.add_space(10.0);
+                    if ui.link("Switch Profile").clicked() {
+                        game_state.set(GameState::ProfileSelection);
+                    }
                 }
-            });
+                AuthState::ForgotPassword => {
+                    ui.label("Enter your email and we'll send you a reset code");
+                    ui.add_space(10.0);
+
+                    ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.email)
+                        .hint_text("Email"));
+                    ui.add_space(20.0);
+
+                    field_error(ui, &auth_form.email, validation::validate_existing_email(&auth_form.email));
+                    ui.add_space(10.0);
+
+                    ui.horizontal(|ui| {
+                        let button_enabled = !auth_form.is_loading && validation::validate_existing_email(&auth_form.email).is_ok();
+                        if ui.add_enabled(button_enabled, egui::Button::new("Send Reset Code")).clicked() {
+                            submit_request(&mut auth_form, &mut auth_state, &mut auth_requests, AuthRequestType::RequestPasswordReset);
+                        }
+
+                        if ui.button("I have a code").clicked() {
+                            auth_form.error_message = None;
+                            auth_state.set(AuthState::ResetPassword);
+                        }
+
+                        if ui.button("Back to Login").clicked() {
+                            auth_form.clear();
+                            auth_state.set(AuthState::Login);
+                        }
+                    });
+                }
+                AuthState::ResetPassword => {
+                    ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.email)
+                        .hint_text("Email"));
+                    ui.add_space(10.0);
+
+                    ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.code)
+                        .hint_text("Reset Code"));
+                    ui.add_space(10.0);
+
+                    ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.password)
+                        .password(true)
+                        .hint_text("New Password"));
+                    password_strength_meter(ui, &auth_form.password);
+                    field_error(ui, &auth_form.password, validation::validate_password(&auth_form.password));
+                    ui.add_space(10.0);
+
+                    ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.confirm_password)
+                        .password(true)
+                        .hint_text("Confirm New Password"));
+                    field_error(ui, &auth_form.confirm_password, passwords_match(&auth_form));
+                    ui.add_space(20.0);
+
+                    ui.horizontal(|ui| {
+                        let button_enabled = !auth_form.is_loading &&
+                            validation::validate_existing_email(&auth_form.email).is_ok() &&
+                            !auth_form.code.is_empty() &&
+                            validation::validate_password(&auth_form.password).is_ok() &&
+                            passwords_match(&auth_form).is_ok();
+
+                        if ui.add_enabled(button_enabled, egui::Button::new("Reset Password")).clicked() {
+                            handle_password_reset(&mut auth_form, &mut auth_state, &mut auth_requests);
+                        }
+
+                        if ui.button("Send New Code").clicked() {
+                            auth_form.error_message = None;
+                            auth_state.set(AuthState::ForgotPassword);
+                        }
+
+                        if ui.button("Back to Login").clicked() {
+                            auth_form.clear();
+                            auth_state.set(AuthState::Login);
+                        }
+                    });
+                }
+                AuthState::VerifyEmail => {
+                    ui.label("Enter the verification code we emailed you");
+                    ui.add_space(10.0);
+
+                    ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut auth_form.code)
+                        .hint_text("Verification Code"));
+                    ui.add_space(20.0);
+
+                    ui.horizontal(|ui| {
+                        let button_enabled = !auth_form.is_loading && !auth_form.code.is_empty();
+                        if ui.add_enabled(button_enabled, egui::Button::new("Verify")).clicked() {
+                            submit_request(&mut auth_form, &mut auth_state, &mut auth_requests, AuthRequestType::VerifyEmail);
+                        }
+
+                        if ui.add_enabled(!auth_form.is_loading, egui::Button::new("Resend Code")).clicked() {
+                            submit_request(&mut auth_form, &mut auth_state, &mut auth_requests, AuthRequestType::ResendVerification);
+                        }
+
+                        if ui.button("Verify Later").clicked() {
+                            auth_state.set(AuthState::Authenticated);
+                            game_state.set(GameState::CharacterSelection);
+                        }
+                    });
+                }
+                AuthState::Authenticating => {
+                    ui.label("Please wait...");
+                }
+                AuthState::Authenticated => {}
+            }
         });
     });
 }
 
+// Demo Mode plays as a guest whose progress is only saved on this machine
+fn start_guest_session(auth_session: &mut AuthSession, auth_config: &AuthConfig, commands: &mut Commands) {
+    auth_session.is_authenticated = true;
+    auth_session.session_token = Some(DEMO_SESSION_TOKEN.to_string());
+    auth_session.expires_at = Some(SystemTime::now() + std::time::Duration::from_secs(3600));
+    auth_session.offline = false;
+
+    let user_data = LocalSave::load(&auth_config.save_directory, DEMO_USER_ID)
+        .map(|save| save.user_data)
+        .unwrap_or_else(UserData::guest);
+    info!("Playing as guest (level {}, high score {})", user_data.player_stats.level, user_data.player_stats.high_score);
+
+    commands.insert_resource(user_data);
+    commands.insert_resource(SyncRecord::default());
+}
+
 fn handle_login(
     auth_form: &mut AuthFormData,
     auth_state: &mut NextState<AuthState>,
-    commands: &mut Commands,
+    auth_requests: &mut EventWriter<AuthRequestEvent>,
 ) {
     if auth_form.email.is_empty() || auth_form.password.is_empty() {
         auth_form.error_message = Some("Please fill in all fields".to_string());
         return;
     }
 
-    auth_form.is_loading = true;
-    auth_form.error_message = None;
-    auth_state.set(AuthState::Authenticating);
-
-    // Send login request event
-    commands.add(|world: &mut World| {
-        let event = AuthRequestEvent {
-            request_type: AuthRequestType::Login,
-            email: world.resource::<AuthFormData>().email.clone(),
-            password: world.resource::<AuthFormData>().password.clone(),
-            username: None,
-        };
+    if let Err(error) = validation::validate_existing_email(&auth_form.email) {
+        auth_form.error_message = Some(error);
+        return;
+    }
 
-        // For now, simulate a successful login
-        let response = AuthResponseEvent {
-            success: true,
-            message: "Login successful!".to_string(),
-            user_data: Some(UserData {
-                user_id: Some("user_123".to_string()),
-                email: Some(event.email.clone()),
-                username: Some("Player".to_string()),
-                player_stats: PlayerStats::default(),
-                preferences: crate::resources::UserPreferences::default(),
-            }),
-            session_token: Some("session_token_123".to_string()),
-        };
-
-        world.send_event(response);
-    });
+    submit_request(auth_form, auth_state, auth_requests, AuthRequestType::Login);
 }
 
 fn handle_signup(
     auth_form: &mut AuthFormData,
     auth_state: &mut NextState<AuthState>,
-    commands: &mut Commands,
+    auth_requests: &mut EventWriter<AuthRequestEvent>,
 ) {
     if auth_form.email.is_empty() || auth_form.password.is_empty() || auth_form.username.is_empty() {
         auth_form.error_message = Some("Please fill in all fields".to_string());
         return;
     }
 
-    if auth_form.password != auth_form.confirm_password {
-        auth_form.error_message = Some("Passwords do not match".to_string());
+    let validated = validation::validate_email(&auth_form.email)
+        .and_then(|_| validation::validate_username(&auth_form.username))
+        .and_then(|_| validation::validate_password(&auth_form.password))
+        .and_then(|_| passwords_match(auth_form));
+    if let Err(error) = validated {
+        auth_form.error_message = Some(error);
         return;
     }
 
-    if auth_form.password.len() < 6 {
-        auth_form.error_message = Some("Password must be at least 6 characters".to_string());
+    submit_request(auth_form, auth_state, auth_requests, AuthRequestType::SignUp);
+}
+
+fn handle_password_reset(
+    auth_form: &mut AuthFormData,
+    auth_state: &mut NextState<AuthState>,
+    auth_requests: &mut EventWriter<AuthRequestEvent>,
+) {
+    if auth_form.email.is_empty() || auth_form.code.is_empty() || auth_form.password.is_empty() {
+        auth_form.error_message = Some("Please fill in all fields".to_string());
         return;
     }
 
+    let validated = validation::validate_password(&auth_form.password)
+        .and_then(|_| passwords_match(auth_form));
+    if let Err(error) = validated {
+        auth_form.error_message = Some(error);
+        return;
+    }
+
+    submit_request(auth_form, auth_state, auth_requests, AuthRequestType::ResetPassword);
+}
+
+fn passwords_match(auth_form: &AuthFormData) -> Result<(), String> {
+    if auth_form.password == auth_form.confirm_password {
+        Ok(())
+    } else {
+        Err("Passwords do not match".to_string())
+    }
+}
+
+fn signup_form_valid(auth_form: &AuthFormData) -> bool {
+    validation::validate_email(&auth_form.email).is_ok() &&
+    validation::validate_username(&auth_form.username).is_ok() &&
+    validation::validate_password(&auth_form.password).is_ok() &&
+    passwords_match(auth_form).is_ok()
+}
+
+// Inline error under a form field, hidden until the field has been typed in
+fn field_error(ui: &mut egui::Ui, value: &str, result: Result<(), String>) {
+    if let (false, Err(error)) = (value.is_empty(), result) {
+        ui.label(egui::RichText::new(error).small().color(egui::Color32::LIGHT_RED));
+    }
+}
+
+fn password_strength_meter(ui: &mut egui::Ui, password: &str) {
+    if password.is_empty() {
+        return;
+    }
+
+    let strength = validation::password_strength(password);
+    let color = match strength {
+        PasswordStrength::Weak => egui::Color32::RED,
+        PasswordStrength::Fair => egui::Color32::YELLOW,
+        PasswordStrength::Good => egui::Color32::LIGHT_GREEN,
+        PasswordStrength::Strong => egui::Color32::GREEN,
+    };
+
+    ui.add_sized([300.0, 12.0], egui::ProgressBar::new(strength.fraction())
+        .fill(color)
+        .text(format!("Strength: {}", strength.label())));
+}
+
+// Sends the form contents to the backend and waits in Authenticating
+fn submit_request(
+    auth_form: &mut AuthFormData,
+    auth_state: &mut NextState<AuthState>,
+    auth_requests: &mut EventWriter<AuthRequestEvent>,
+    request_type: AuthRequestType,
+) {
     auth_form.is_loading = true;
     auth_form.error_message = None;
+    auth_form.info_message = None;
     auth_state.set(AuthState::Authenticating);
 
-    // Send signup request event
-    commands.add(|world: &mut World| {
-        let form_data = world.resource::<AuthFormData>();
-        let event = AuthRequestEvent {
-            request_type: AuthRequestType::SignUp,
-            email: form_data.email.clone(),
-            password: form_data.password.clone(),
-            username: Some(form_data.username.clone()),
-        };
-
-        // For now, simulate a successful signup
-        let response = AuthResponseEvent {
-            success: true,
-            message: "Account created successfully!".to_string(),
-            user_data: Some(UserData {
-                user_id: Some("user_123".to_string()),
-                email: Some(event.email.clone()),
-                username: event.username.clone(),
-                player_stats: PlayerStats::default(),
-                preferences: crate::resources::UserPreferences::default(),
-            }),
-            session_token: Some("session_token_123".to_string()),
-        };
-
-        world.send_event(response);
+    let username = matches!(request_type, AuthRequestType::SignUp).then(|| auth_form.username.clone());
+    let code = (!auth_form.code.is_empty()).then(|| auth_form.code.trim().to_string());
+
+    auth_requests.send(AuthRequestEvent {
+        request_type,
+        email: auth_form.email.clone(),
+        password: auth_form.password.clone(),
+        username,
+        code,
     });
 }
 
-fn handle_auth_response(
-    mut auth_response_events: EventReader<AuthResponseEvent>,
-    mut auth_form: ResMut<AuthFormData>,
-    mut auth_state: ResMut<NextState<AuthState>>,
-    mut game_state: ResMut<NextState<GameState>>,
-    mut auth_session: ResMut<AuthSession>,
+fn dispatch_auth_requests(
     mut commands: Commands,
+    mut auth_requests: EventReader<AuthRequestEvent>,
+    runtime: Res<ApiRuntime>,
+    auth_config: Res<AuthConfig>,
+    auth_session: Res<AuthSession>,
+    user_data: Res<UserData>,
 ) {
-    for event in auth_response_events.read() {
-        auth_form.is_loading = false;
+    // Only the latest request matters if several arrive in one frame
+    if let Some(request) = auth_requests.read().last() {
+        // A guest signing up takes their progress into the new account
+        let guest_data = (auth_session.is_demo() && matches!(request.request_type, AuthRequestType::SignUp))
+            .then(|| user_data.clone());
+
+        let api_base_url = auth_config.api_base_url.clone();
+        let save_directory = auth_config.save_directory.clone();
+        let session_token = auth_session.session_token.clone().unwrap_or_default();
+        let request = request.clone();
+        let request_type = request.request_type.clone();
+
+        let handle = runtime.0.spawn(async move {
+            run_auth_request(api_base_url, save_directory, session_token, request, guest_data).await
+        });
+        commands.insert_resource(PendingAuthRequest { handle, request_type });
+    }
+}
 
-        if event.success {
-            auth_session.is_authenticated = true;
-            auth_session.session_token = event.session_token.clone();
-            auth_session.expires_at = Some(SystemTime::now() + std::time::Duration::from_secs(3600));
+fn poll_auth_request(
+    mut commands: Commands,
+    runtime: Res<ApiRuntime>,
+    pending: Option<ResMut<PendingAuthRequest>>,
+    mut auth_responses: EventWriter<AuthResponseEvent>,
+) {
+    let Some(mut pending) = pending else {
+        return;
+    };
+    if !pending.handle.is_finished() {
+        return;
+    }
+
+    commands.remove_resource::<PendingAuthRequest>();
+    let request_type = pending.request_type.clone();
+    let response = runtime.0.block_on(&mut pending.handle)
+        .unwrap_or_else(|e| failed_response(request_type, format!("Authentication request failed: {}", e)));
+    auth_responses.send(response);
+}
 
-            if let Some(user_data) = &event.user_data {
-                commands.insert_resource(user_data.clone());
+async fn run_auth_request(
+    api_base_url: String,
+    save_directory: PathBuf,
+    session_token: String,
+    request: AuthRequestEvent,
+    guest_data: Option<UserData>,
+) -> AuthResponseEvent {
+    let AuthRequestEvent { request_type, email, password, username, code } = request;
+    let code = code.unwrap_or_default();
+
+    let result = match request_type {
+        AuthRequestType::Login => {
+            api::send_login_request(&api_base_url, email.clone(), password.clone()).await
+        }
+        AuthRequestType::SignUp => {
+            api::send_signup_request(
+                &api_base_url,
+                email.clone(),
+                password.clone(),
+                username.unwrap_or_default(),
+                guest_data.as_ref(),
+            ).await
+        }
+        AuthRequestType::VerifyEmail => {
+            let result = api::verify_email(&api_base_url, &session_token, code).await;
+            return message_response(request_type, result);
+        }
+        AuthRequestType::ResendVerification => {
+            let result = api::resend_verification(&api_base_url, &session_token).await;
+            return message_response(request_type, result);
+        }
+        AuthRequestType::RequestPasswordReset => {
+            let result = api::request_password_reset(&api_base_url, email).await;
+            return message_response(request_type, result);
+        }
+        AuthRequestType::ResetPassword => {
+            let result = api::reset_password(&api_base_url, email, code, password).await;
+            return message_response(request_type, result);
+        }
+    };
+
+    match result {
+        Ok(response) if response.success => {
+            if let Some(user_data) = &response.user_data {
+                remember_credentials(&save_directory, user_data, &password, response.session_token.clone());
             }
 
-            auth_state.set(AuthState::Authenticated);
-            game_state.set(GameState::CharacterSelection);
+            // The guest's progress now lives in the account
+            if guest_data.is_some() {
+                if let Err(e) = LocalSave::remove(&save_directory, DEMO_USER_ID) {
+                    warn!("Failed to remove guest save: {}", e);
+                }
+            }
 
-            info!("Authentication successful: {}", event.message);
-        } else {
-            auth_form.error_message = Some(event.message.clone());
-            auth_state.set(AuthState::Login);
-            warn!("Authentication failed: {}", event.message);
+            AuthResponseEvent {
+                request_type,
+                success: true,
+                message: response.message,
+                user_data: response.user_data,
+                session_token: response.session_token,
+                offline: false,
+                email_verified: response.email_verified,
+                retry_after: None,
+            }
+        }
+        Ok(response) => AuthResponseEvent {
+            retry_after: response.retry_after.map(Duration::from_secs),
+            ..failed_response(request_type, response.message)
+        },
+        Err(e) if e.is_offline() && matches!(request_type, AuthRequestType::Login) => {
+            offline_login(&save_directory, &email, &password, &e)
         }
+        Err(e) => failed_response(request_type, e.to_string()),
     }
 }
 
-fn check_session_validity(
-    auth_session: Res<AuthSession>,
-    mut game_state: ResMut<NextState<GameState>>,
-) {
-    if !auth_session.is_valid() && auth_session.is_authenticated {
-        warn!("Session expired, redirecting to authentication");
-        game_state.set(GameState::Authentication);
+// Stores what's needed to log in again without a connection
+fn remember_credentials(save_directory: &Path, user_data: &UserData, password: &str, session_token: Option<String>) {
+    let Some(user_id) = user_data.user_id.as_deref() else {
+        return;
+    };
+
+    let mut save = LocalSave::load(save_directory, user_id)
+        .unwrap_or_else(|| LocalSave::from_server(user_data.clone()));
+    save.password_hash = bcrypt::hash(password, bcrypt::DEFAULT_COST).ok();
+    save.session_token = session_token;
+
+    if let Err(e) = save.write(save_directory) {
+        warn!("Failed to write local save for {}: {}", user_id, e);
     }
 }
 
-fn auto_save_player_data(
-    time: Res<Time>,
-    mut last_save: Local<f32>,
-    user_data: Res<UserData>,
+fn offline_login(save_directory: &Path, email: &str, password: &str, error: &api::ApiError) -> AuthResponseEvent {
+    match LocalSave::find_by_email(save_directory, email) {
+        Some(save) if save.verify_password(password) => AuthResponseEvent {
+            request_type: AuthRequestType::Login,
+            success: true,
+            message: "Server unreachable - playing offline, progress will sync later".to_string(),
+            user_data: Some(save.user_data),
+            session_token: save.session_token,
+            offline: true,
+            // Can't check while offline, so don't hold the player up
+            email_verified: true,
+            retry_after: None,
+        },
+        Some(_) => failed_response(AuthRequestType::Login, "Invalid email or password".to_string()),
+        None => failed_response(AuthRequestType::Login, error.to_string()),
+    }
+}
+
+// Verification and password reset requests don't change the session
+fn message_response(request_type: AuthRequestType, result: Result<api::MessageResponse, api::ApiError>) -> AuthResponseEvent {
+    match result {
+        Ok(response) => AuthResponseEvent {
+            request_type,
+            success: response.success,
+            message: response.message,
+            user_data: None,
+            session_token: None,
+            offline: false,
+            email_verified: false,
+            retry_after: response.retry_after.map(Duration::from_secs),
+        },
+        Err(e) => failed_response(request_type, e.to_string()),
+    }
+}
+
+fn failed_response(request_type: AuthRequestType, message: String) -> AuthResponseEvent {
+    AuthResponseEvent {
+        request_type,
+        success: false,
+        message,
+        user_data: None,
+        session_token: None,
+        offline: false,
+        email_verified: false,
+        retry_after: None,
+    }
+}
+
+fn handle_auth_response(
+    mut auth_response_events: EventReader<AuthResponseEvent>,
+    form: AuthForm,
+    navigation: AuthNavigation,
+    mut auth_session: ResMut<AuthSession>,
+    mut sync_requests: EventWriter<SyncRequestEvent>,
     auth_config: Res<AuthConfig>,
-    auth_session: Res<AuthSession>,
+    mut commands: Commands,
 ) {
-    if !auth_session.is_valid() {
-        return;
-    }
+    let AuthForm { mut auth_form, mut login_backoff } = form;
+    let AuthNavigation { mut auth_state, mut game_state, .. } = navigation;
+    for event in auth_response_events.read() {
+        auth_form.is_loading = false;
+
+        if matches!(event.request_type, AuthRequestType::Login) {
+            if event.success {
+                login_backoff.record_success();
+            } else {
+                login_backoff.record_failure(event.retry_after);
+            }
+        }
 
-    *last_save += time.delta_seconds();
+        if !event.success {
+            auth_form.error_message = Some(event.message.clone());
+            auth_state.set(form_state(&event.request_type));
+            warn!("Authentication failed: {}", event.message);
+            continue;
+        }
 
-    if *last_save >= auth_config.auto_save_interval.as_secs_f32() {
-        *last_save = 0.0;
+        match event.request_type {
+            AuthRequestType::Login | AuthRequestType::SignUp => {
+                auth_session.is_authenticated = true;
+                auth_session.session_token = event.session_token.clone();
+                auth_session.expires_at = Some(SystemTime::now() + std::time::Duration::from_secs(3600));
+                auth_session.offline = event.offline;
+
+                if let Some(user_data) = &event.user_data {
+                    // Progress saved on this machine may be ahead of the server copy
+                    let local_save = user_data.user_id.as_deref()
+                        .and_then(|user_id| LocalSave::load(&auth_config.save_directory, user_id));
+
+                    match local_save {
+                        Some(save) => {
+                            commands.insert_resource(save.user_data);
+                            commands.insert_resource(save.sync);
+                        }
+                        None => {
+                            commands.insert_resource(user_data.clone());
+                            commands.insert_resource(SyncRecord {
+                                last_synced_revision: 0,
+                                base: Some(user_data.clone()),
+                            });
+                        }
+                    }
+                    sync_requests.send(SyncRequestEvent);
+                }
 
-        // In a real implementation, this would send the data to your backend
-        info!("Auto-saving player data for user: {:?}", user_data.user_id);
+                if event.email_verified {
+                    auth_state.set(AuthState::Authenticated);
+                    game_state.set(GameState::CharacterSelection);
+                } else {
+                    auth_form.code.clear();
+                    auth_form.info_message = Some(format!("We sent a verification code to {}", auth_form.email));
+                    auth_state.set(AuthState::VerifyEmail);
+                }
+            }
+            AuthRequestType::VerifyEmail => {
+                auth_state.set(AuthState::Authenticated);
+                game_state.set(GameState::CharacterSelection);
+            }
+            AuthRequestType::ResendVerification => {
+                auth_form.info_message = Some(event.message.clone());
+                auth_state.set(AuthState::VerifyEmail);
+            }
+            AuthRequestType::RequestPasswordReset => {
+                auth_form.code.clear();
+                auth_form.info_message = Some(event.message.clone());
+                auth_state.set(AuthState::ResetPassword);
+            }
+            AuthRequestType::ResetPassword => {
+                auth_form.code.clear();
+                auth_form.password.clear();
+                auth_form.confirm_password.clear();
+                auth_form.info_message = Some(event.message.clone());
+                auth_state.set(AuthState::Login);
+            }
+        }
 
-        // Here you would serialize user_data and send it to your API
-        // let serialized = serde_json::to_string(&*user_data).unwrap();
-        // send_to_api(serialized);
+        info!("Authentication successful: {}", event.message);
     }
 }
 
-// Future implementation for real API calls
-#[allow(dead_code)]
-async fn send_login_request(config: &AuthConfig, email: String, password: String) -> Result<AuthResponse, String> {
-    let client = reqwest::Client::new();
-    let login_data = LoginRequest { email, password };
-
-    let response = client
-        .post(&format!("{}/auth/login", config.api_base_url))
-        .json(&login_data)
-        .send()
-        .await
-        .map_err(|e| format!("Network error: {}", e))?;
-
-    if response.status().is_success() {
-        response.json::<AuthResponse>().await
-            .map_err(|e| format!("Failed to parse response: {}", e))
-    } else {
-        Err(format!("Login failed with status: {}", response.status()))
+// The form to return to after a request finishes
+fn form_state(request_type: &AuthRequestType) -> AuthState {
+    match request_type {
+        AuthRequestType::Login => AuthState::Login,
+        AuthRequestType::SignUp => AuthState::SignUp,
+        AuthRequestType::VerifyEmail | AuthRequestType::ResendVerification => AuthState::VerifyEmail,
+        AuthRequestType::RequestPasswordReset => AuthState::ForgotPassword,
+        AuthRequestType::ResetPassword => AuthState::ResetPassword,
     }
 }
 
-#[allow(dead_code)]
-async fn send_signup_request(config: &AuthConfig, email: String, password: String, username: String) -> Result<AuthResponse, String> {
-    let client = reqwest::Client::new();
-    let signup_data = SignUpRequest { email, password, username };
-
-    let response = client
-        .post(&format!("{}/auth/signup", config.api_base_url))
-        .json(&signup_data)
-        .send()
-        .await
-        .map_err(|e| format!("Network error: {}", e))?;
-
-    if response.status().is_success() {
-        response.json::<AuthResponse>().await
-            .map_err(|e| format!("Failed to parse response: {}", e))
-    } else {
-        Err(format!("Signup failed with status: {}", response.status()))
+fn check_session_validity(
+    auth_session: Res<AuthSession>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    if !auth_session.is_valid() && auth_session.is_authenticated {
+        warn!("Session expired, redirecting to authentication");
+        game_state.set(GameState::Authentication);
     }
-}
\ No newline at end of file
+}
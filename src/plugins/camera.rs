@@ -42,13 +42,13 @@ fn setup_camera(mut commands: Commands) {
 }
 
 fn handle_camera_toggle(
-    keyboard: Res<Input<KeyCode>>,
+    actions: Res<ActionState>,
     input_config: Res<InputConfig>,
     mut camera_query: Query<(&mut FreeCamera, &mut CameraController, &Transform)>,
     player_query: Query<&Transform, (With<Player>, Without<FreeCamera>)>,
     mut debug_events: EventWriter<DebugEvent>,
 ) {
-    if keyboard.just_pressed(input_config.camera_toggle) {
+    if actions.just_pressed(InputAction::CameraToggle) {
         for (mut free_camera, mut controller, camera_transform) in camera_query.iter_mut() {
             free_camera.is_active = !free_camera.is_active;
 
@@ -56,7 +56,15 @@ fn handle_camera_toggle(
                 // Store current camera position when entering free camera mode
                 controller.target_position = camera_transform.translation;
                 debug_events.send(DebugEvent {
-                    message: "Free camera mode ON - Use IJKL to move, UO to zoom".to_string(),
+                    message: format!(
+                        "Free camera mode ON - Use {}{}{}{} to move, {}{} to zoom",
+                        key_name(input_config.camera_forward),
+                        key_name(input_config.camera_left),
+                        key_name(input_config.camera_backward),
+                        key_name(input_config.camera_right),
+                        key_name(input_config.camera_up),
+                        key_name(input_config.camera_down),
+                    ),
                 });
             } else {
                 // Reset to follow mode
@@ -72,8 +80,7 @@ fn handle_camera_toggle(
 }
 
 fn update_free_camera(
-    keyboard: Res<Input<KeyCode>>,
-    input_config: Res<InputConfig>,
+    actions: Res<ActionState>,
     mut camera_query: Query<(&mut Transform, &mut FreeCamera, &mut CameraController)>,
     time: Res<Time>,
     mut scroll_events: EventReader<bevy::input::mouse::MouseWheel>,
@@ -85,27 +92,8 @@ fn update_free_camera(
 
         let delta_time = time.delta_seconds();
         let movement_speed = free_camera.movement_speed * delta_time;
-        let mut movement = Vec3::ZERO;
-
-        // Handle keyboard movement
-        if keyboard.pressed(input_config.camera_forward) {
-            movement.z -= movement_speed;
-        }
-        if keyboard.pressed(input_config.camera_backward) {
-            movement.z += movement_speed;
-        }
-        if keyboard.pressed(input_config.camera_left) {
-            movement.x -= movement_speed;
-        }
-        if keyboard.pressed(input_config.camera_right) {
-            movement.x += movement_speed;
-        }
-        if keyboard.pressed(input_config.camera_up) {
-            movement.y += movement_speed;
-        }
-        if keyboard.pressed(input_config.camera_down) {
-            movement.y -= movement_speed;
-        }
+        // Keys or right stick; forward is -z in camera space
+        let movement = Vec3::new(actions.camera.x, actions.camera.y, -actions.camera.z) * movement_speed;
 
         // Apply movement relative to camera's rotation
         let forward = camera_transform.forward();
@@ -139,7 +127,7 @@ fn update_free_camera(
 
 fn update_camera_follow(
     player_query: Query<&Transform, (With<Player>, Without<FollowCamera>)>,
-    mut camera_query: Query<(&mut Transform, &FollowCamera, &mut CameraShake, &FreeCamera), (Without<Player>, With<FollowCamera>)>,
+    mut camera_query: Query<(&mut Transform, &FollowCamera, &mut CameraShake, &FreeCamera), Without<Player>>,
     time: Res<Time>,
 ) {
     let player_transform = if let Ok(transform) = player_query.get_single() {
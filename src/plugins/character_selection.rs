@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use crate::{
-    resources::{SelectedCharacter, CharacterType},
+    plugins::ControlsMenu,
+    resources::{key_name, ActionState, AuthSession, SelectedCharacter, CharacterType, InputAction, InputConfig},
     states::*,
 };
 
@@ -14,7 +15,13 @@ impl Plugin for CharacterSelectionPlugin {
             .add_systems(Update, (
                 handle_character_selection_input,
                 update_button_interactions,
-            ).run_if(in_state(GameState::CharacterSelection)));
+                handle_create_account_button,
+                handle_switch_profile_button,
+                handle_controls_button,
+            ).run_if(in_state(GameState::CharacterSelection)).run_if(super::controls_menu_closed))
+            .add_systems(Update, update_instructions
+                .run_if(in_state(GameState::CharacterSelection))
+                .run_if(resource_changed::<InputConfig>()));
     }
 }
 
@@ -32,10 +39,31 @@ struct CharacterButton {
 #[derive(Component)]
 struct CharacterPreview;
 
+// Component to mark the guest "create account" button
+#[derive(Component)]
+struct CreateAccountButton;
+
+// Component to mark the button back to the profile picker
+#[derive(Component)]
+struct SwitchProfileButton;
+
+// Component to mark the key hints, which follow the current bindings
+#[derive(Component)]
+struct InstructionsText;
+
+// Component to mark the button that opens the rebinding screen
+#[derive(Component)]
+struct ControlsButton;
+
+// Buttons with the marker `T` whose interaction changed this frame
+type ButtonChanged<T> = (Changed<Interaction>, With<T>);
+
 fn setup_character_selection_ui(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     selected_character: Res<SelectedCharacter>,
+    auth_session: Res<AuthSession>,
+    input_config: Res<InputConfig>,
 ) {
     // Create the main UI container
     commands.spawn((
@@ -104,15 +132,94 @@ fn setup_character_selection_ui(
         // Instructions
         parent.spawn((
             TextBundle::from_section(
-                "Use A/D to navigate, SPACE to select",
+                instructions(&input_config),
                 TextStyle {
                     font_size: 24.0,
                     color: Color::rgba(0.8, 0.8, 0.8, 1.0),
                     ..default()
                 },
             ),
+            InstructionsText,
             Name::new("Instructions"),
         ));
+
+        // Guests can turn their local progress into an account
+        if auth_session.is_demo() {
+            parent.spawn((
+                ButtonBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(30.0)),
+                        padding: UiRect::axes(Val::Px(20.0), Val::Px(10.0)),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    border_color: Color::rgba(0.8, 0.8, 0.2, 1.0).into(),
+                    background_color: Color::rgba(0.2, 0.2, 0.2, 0.8).into(),
+                    ..default()
+                },
+                CreateAccountButton,
+                Name::new("CreateAccountButton"),
+            )).with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    "Create an account to keep your progress",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ));
+            });
+        }
+
+        parent.spawn((
+            ButtonBundle {
+                style: Style {
+                    margin: UiRect::top(Val::Px(15.0)),
+                    padding: UiRect::axes(Val::Px(20.0), Val::Px(10.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                border_color: Color::rgba(0.3, 0.3, 0.3, 1.0).into(),
+                background_color: Color::rgba(0.2, 0.2, 0.2, 0.8).into(),
+                ..default()
+            },
+            SwitchProfileButton,
+            Name::new("SwitchProfileButton"),
+        )).with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Switch Profile",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+
+        parent.spawn((
+            ButtonBundle {
+                style: Style {
+                    margin: UiRect::top(Val::Px(15.0)),
+                    padding: UiRect::axes(Val::Px(20.0), Val::Px(10.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                border_color: Color::rgba(0.3, 0.3, 0.3, 1.0).into(),
+                background_color: Color::rgba(0.2, 0.2, 0.2, 0.8).into(),
+                ..default()
+            },
+            ControlsButton,
+            Name::new("ControlsButton"),
+        )).with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Controls",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
     });
 
     info!("Character selection UI setup complete");
@@ -205,7 +312,7 @@ fn create_character_option(
 }
 
 fn handle_character_selection_input(
-    keyboard: Res<Input<KeyCode>>,
+    actions: Res<ActionState>,
     mut selected_character: ResMut<SelectedCharacter>,
     mut next_state: ResMut<NextState<GameState>>,
     mut button_query: Query<(&CharacterButton, &mut BorderColor, &mut BackgroundColor)>,
@@ -213,7 +320,7 @@ fn handle_character_selection_input(
     let mut changed = false;
 
     // Navigate between characters
-    if keyboard.just_pressed(KeyCode::A) {
+    if actions.just_pressed(InputAction::MoveLeft) {
         let old_character = selected_character.character_type;
         selected_character.character_type = match selected_character.character_type {
             CharacterType::Boss3 => CharacterType::SwordHero,
@@ -223,7 +330,7 @@ fn handle_character_selection_input(
         info!("Character selection changed from {:?} to {:?}", old_character, selected_character.character_type);
     }
 
-    if keyboard.just_pressed(KeyCode::D) {
+    if actions.just_pressed(InputAction::MoveRight) {
         let old_character = selected_character.character_type;
         selected_character.character_type = match selected_character.character_type {
             CharacterType::Boss3 => CharacterType::SwordHero,
@@ -234,7 +341,7 @@ fn handle_character_selection_input(
     }
 
     // Confirm selection
-    if keyboard.just_pressed(KeyCode::Space) || keyboard.just_pressed(KeyCode::Return) {
+    if actions.just_pressed(InputAction::Jump) || actions.just_pressed(InputAction::Confirm) {
         info!("Character selection CONFIRMED: {:?}", selected_character.character_type);
         info!("Transitioning to Playing state...");
         next_state.set(GameState::Playing);
@@ -294,6 +401,86 @@ fn update_button_interactions(
     }
 }
 
+fn handle_create_account_button(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor), ButtonChanged<CreateAccountButton>>,
+    mut auth_state: ResMut<NextState<AuthState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, mut background_color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                info!("Guest is creating an account");
+                auth_state.set(AuthState::SignUp);
+                next_state.set(GameState::Authentication);
+            }
+            Interaction::Hovered => {
+                background_color.0 = Color::rgba(0.3, 0.3, 0.1, 0.8);
+            }
+            Interaction::None => {
+                background_color.0 = Color::rgba(0.2, 0.2, 0.2, 0.8);
+            }
+        }
+    }
+}
+
+fn handle_switch_profile_button(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor), ButtonChanged<SwitchProfileButton>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, mut background_color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                info!("Switching profile");
+                next_state.set(GameState::ProfileSelection);
+            }
+            Interaction::Hovered => {
+                background_color.0 = Color::rgba(0.3, 0.3, 0.3, 0.8);
+            }
+            Interaction::None => {
+                background_color.0 = Color::rgba(0.2, 0.2, 0.2, 0.8);
+            }
+        }
+    }
+}
+
+fn instructions(input_config: &InputConfig) -> String {
+    format!(
+        "Use {}/{} to navigate, {} or {} to select",
+        key_name(input_config.move_left),
+        key_name(input_config.move_right),
+        key_name(input_config.jump).to_uppercase(),
+        key_name(input_config.confirm).to_uppercase(),
+    )
+}
+
+fn update_instructions(
+    input_config: Res<InputConfig>,
+    mut text_query: Query<&mut Text, With<InstructionsText>>,
+) {
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = instructions(&input_config);
+    }
+}
+
+fn handle_controls_button(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor), ButtonChanged<ControlsButton>>,
+    mut controls_menu: ResMut<ControlsMenu>,
+) {
+    for (interaction, mut background_color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                controls_menu.open = true;
+            }
+            Interaction::Hovered => {
+                background_color.0 = Color::rgba(0.3, 0.3, 0.3, 0.8);
+            }
+            Interaction::None => {
+                background_color.0 = Color::rgba(0.2, 0.2, 0.2, 0.8);
+            }
+        }
+    }
+}
+
 fn cleanup_character_selection_ui(
     mut commands: Commands,
     ui_query: Query<Entity, With<CharacterSelectionUI>>,
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use crate::{
+    components::*,
+    states::*,
+};
+
+/// Where a new run starts, and where the player respawns until they reach a checkpoint
+pub const PLAYER_START: Vec3 = Vec3::new(0.0, 7.0, 0.0);
+
+pub struct CheckpointsPlugin;
+
+impl Plugin for CheckpointsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ActiveCheckpoint>()
+            .add_systems(OnEnter(PlayState::Setup), setup_checkpoints)
+            .add_systems(OnEnter(GameState::CharacterSelection), reset_checkpoint_on_restart)
+            .add_systems(Update, (
+                check_checkpoint_contact,
+                update_checkpoint_flags.run_if(resource_changed::<ActiveCheckpoint>()),
+            ).run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// Flag post that becomes the respawn point once touched, and refills health
+#[derive(Component)]
+pub struct Checkpoint {
+    /// Player position on respawning here
+    pub respawn: Vec3,
+}
+
+// The flag on a checkpoint's post, coloured by whether it's the active one
+#[derive(Component)]
+struct CheckpointFlag;
+
+/// The checkpoint the player respawns at; back to the start for each new run
+#[derive(Resource)]
+pub struct ActiveCheckpoint {
+    pub entity: Option<Entity>,
+    pub position: Vec3,
+}
+
+impl Default for ActiveCheckpoint {
+    fn default() -> Self {
+        Self {
+            entity: None,
+            position: PLAYER_START,
+        }
+    }
+}
+
+const FLAG_COLOR: Color = Color::rgb(0.55, 0.2, 0.2);
+const ACTIVE_FLAG_COLOR: Color = Color::rgb(0.2, 0.85, 0.3);
+
+fn setup_checkpoints(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Checkpoints: the top of the platform each one stands on
+    let checkpoints = vec![
+        // On the bridge after the branching paths of Section 3
+        Vec3::new(39.0, 23.25, 0.0),
+        // Before the wall climb
+        Vec3::new(18.0, 32.25, 0.0),
+        // On the convergence bridge of Section 8
+        Vec3::new(40.0, 61.25, 0.0),
+    ];
+
+    let pole_mesh = meshes.add(Mesh::from(shape::Box::new(0.1, 2.0, 0.1)));
+    let pole_material = materials.add(StandardMaterial {
+        base_color: Color::rgb(0.8, 0.8, 0.8),
+        metallic: 0.6,
+        ..default()
+    });
+    let flag_mesh = meshes.add(Mesh::from(shape::Box::new(0.6, 0.4, 0.05)));
+
+    for base in checkpoints {
+        commands.spawn((
+            SpatialBundle::from_transform(Transform::from_translation(base + Vec3::Y)),
+            Collider::cuboid(0.5, 1.0, 1.5),
+            Sensor,
+            Checkpoint {
+                respawn: base + Vec3::Y,
+            },
+            Name::new("Checkpoint"),
+        ))
+        .with_children(|parent| {
+            parent.spawn(PbrBundle {
+                mesh: pole_mesh.clone(),
+                material: pole_material.clone(),
+                ..default()
+            });
+            // Each flag gets its own material so it can change colour alone
+            parent.spawn((
+                PbrBundle {
+                    mesh: flag_mesh.clone(),
+                    material: materials.add(StandardMaterial {
+                        base_color: FLAG_COLOR,
+                        ..default()
+                    }),
+                    transform: Transform::from_xyz(0.35, 0.75, 0.0),
+                    ..default()
+                },
+                CheckpointFlag,
+            ));
+        });
+    }
+
+    info!("Checkpoints setup complete");
+}
+
+/// A new run starts from the beginning, whatever was reached last time
+fn reset_checkpoint_on_restart(mut active: ResMut<ActiveCheckpoint>) {
+    *active = ActiveCheckpoint::default();
+}
+
+/// Activates a checkpoint the player walks through, and tops up their
+/// health every time they pass one
+fn check_checkpoint_contact(
+    mut player_query: Query<(Entity, &Transform, &Player, &mut Health)>,
+    checkpoint_query: Query<&Checkpoint>,
+    rapier_context: Res<RapierContext>,
+    mut active: ResMut<ActiveCheckpoint>,
+) {
+    for (entity, transform, player, mut health) in player_query.iter_mut() {
+        if player.is_dead {
+            continue;
+        }
+
+        let mut touched = None;
+        let is_checkpoint = |collider: Entity| checkpoint_query.contains(collider);
+        rapier_context.intersections_with_shape(
+            transform.translation,
+            Quat::IDENTITY,
+            &Collider::capsule_y(0.4, 0.4),
+            QueryFilter::default().exclude_rigid_body(entity).predicate(&is_checkpoint),
+            |collider| {
+                touched = Some(collider);
+                false
+            },
+        );
+
+        let Some(checkpoint_entity) = touched else {
+            continue;
+        };
+        if health.current < health.max {
+            health.current = health.max;
+        }
+        if active.entity != Some(checkpoint_entity) {
+            let Ok(checkpoint) = checkpoint_query.get(checkpoint_entity) else {
+                continue;
+            };
+            active.entity = Some(checkpoint_entity);
+            active.position = checkpoint.respawn;
+            info!("Checkpoint reached at {:?}", checkpoint.respawn);
+        }
+    }
+}
+
+fn update_checkpoint_flags(
+    active: Res<ActiveCheckpoint>,
+    flag_query: Query<(&Parent, &Handle<StandardMaterial>), With<CheckpointFlag>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (parent, material) in flag_query.iter() {
+        if let Some(material) = materials.get_mut(material) {
+            material.base_color = if active.entity == Some(parent.get()) { ACTIVE_FLAG_COLOR } else { FLAG_COLOR };
+        }
+    }
+}
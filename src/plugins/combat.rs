@@ -0,0 +1,227 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use crate::{
+    components::*,
+    events::*,
+    plugins::player::handle_player_animation,
+    states::*,
+};
+
+// Declared with the components, since the player's attack state holds one
+pub use crate::components::{AttackMove, ATTACK_FPS};
+
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, (
+                update_player_attacks,
+                start_player_attacks,
+                manage_attack_hitbox,
+                detect_attack_hits,
+            ).chain().before(handle_player_animation).run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// Can be hit by the player's attacks, which send a `HitEvent` for it
+#[derive(Component)]
+pub struct Attackable;
+
+/// Sensor collider in front of the player while an attack's active frames last
+#[derive(Component)]
+pub struct AttackHitbox(pub AttackMove);
+
+fn begin_attack(attack: &mut AttackState, attack_move: AttackMove, velocity: &mut Velocity) {
+    attack.current = Some(attack_move);
+    attack.elapsed = 0.0;
+    attack.queued = false;
+    attack.hit.clear();
+
+    if attack_move.is_air() {
+        // The swing checks a fall for a moment, without ever lifting the player
+        velocity.linvel.y = velocity.linvel.y.max(0.0);
+    }
+}
+
+/// Runs the current move's clock, chaining a queued press into the next
+/// combo step, and drops the attack if something else takes over the player
+fn update_player_attacks(
+    mut player_query: Query<(&Player, &HurtState, &AbilityState, &mut AttackState, &mut Velocity)>,
+    time: Res<Time>,
+) {
+    for (player, hurt, abilities, mut attack, mut velocity) in player_query.iter_mut() {
+        if player.is_grounded {
+            attack.air_attack_used = false;
+        }
+
+        let Some(current) = attack.current else {
+            continue;
+        };
+
+        // Jumping or walking off a ledge ends a ground swing, and landing
+        // ends an air one
+        let interrupted = player.is_dead
+            || player.is_hanging
+            || player.is_front_flipping
+            || player.is_dive_rolling
+            || hurt.stun_timer > 0.0
+            || abilities.dash_timer > 0.0
+            || abilities.is_ground_pounding
+            || current.is_air() == player.is_grounded;
+        if interrupted {
+            attack.current = None;
+            continue;
+        }
+
+        attack.elapsed += time.delta_seconds();
+        if attack.elapsed >= current.duration() {
+            match current.next().filter(|_| attack.queued) {
+                Some(next) => begin_attack(&mut attack, next, &mut velocity),
+                None => attack.current = None,
+            }
+        }
+
+        if let Some(current) = attack.current.filter(|current| !current.is_air()) {
+            let facing = if player.facing_left { -1.0 } else { 1.0 };
+            let (_, active_end) = current.active_frames();
+            velocity.linvel.x = if attack.elapsed * ATTACK_FPS < active_end as f32 {
+                facing * current.lunge()
+            } else {
+                0.0
+            };
+        }
+    }
+}
+
+/// Starts the combo, or the air attack, on a press; a press during a swing
+/// is held until it ends
+fn start_player_attacks(
+    mut attack_events: EventReader<AttackEvent>,
+    mut player_query: Query<(&Player, &HurtState, &AbilityState, &mut AttackState, &mut Velocity)>,
+) {
+    for event in attack_events.read() {
+        let Ok((player, hurt, abilities, mut attack, mut velocity)) = player_query.get_mut(event.entity) else {
+            continue;
+        };
+
+        let busy = player.is_dead
+            || player.is_hanging
+            || player.is_front_flipping
+            || player.is_dive_rolling
+            || hurt.stun_timer > 0.0
+            || abilities.dash_timer > 0.0
+            || abilities.is_ground_pounding;
+        if busy {
+            continue;
+        }
+
+        match attack.current {
+            Some(current) if current.can_chain_at(attack.elapsed) => {
+                attack.queued = true;
+            }
+            Some(_) => {}
+            None if player.is_grounded => {
+                begin_attack(&mut attack, AttackMove::Slash, &mut velocity);
+            }
+            None if !attack.air_attack_used => {
+                attack.air_attack_used = true;
+                begin_attack(&mut attack, AttackMove::AirSlash, &mut velocity);
+            }
+            None => {}
+        }
+    }
+}
+
+/// Puts the hitbox out for the active frames of the current move, the same
+/// way the dive roll swaps in its own collider
+fn manage_attack_hitbox(
+    mut commands: Commands,
+    player_query: Query<(Entity, &AttackState), With<Player>>,
+    hitbox_query: Query<(Entity, &AttackHitbox, &Parent)>,
+) {
+    for (player_entity, attack) in player_query.iter() {
+        let active = attack.current.filter(|current| current.is_active_at(attack.elapsed));
+
+        let mut has_hitbox = false;
+        for (hitbox_entity, hitbox, parent) in hitbox_query.iter() {
+            if parent.get() != player_entity {
+                continue;
+            }
+            if active == Some(hitbox.0) {
+                has_hitbox = true;
+            } else {
+                commands.entity(hitbox_entity).despawn();
+            }
+        }
+
+        if let Some(attack_move) = active.filter(|_| !has_hitbox) {
+            let (half_extents, offset) = attack_move.hitbox();
+            // The player turns to face left, so the child's offset turns with it
+            commands.entity(player_entity).with_children(|parent| {
+                parent.spawn((
+                    TransformBundle::from_transform(Transform::from_translation(offset)),
+                    Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+                    Sensor,
+                    ColliderMassProperties::Density(0.0),
+                    AttackHitbox(attack_move),
+                    Name::new("AttackHitbox"),
+                ));
+            });
+        }
+    }
+}
+
+/// Sends a `HitEvent` for everything attackable inside a hitbox that its
+/// move hasn't hit yet
+fn detect_attack_hits(
+    hitbox_query: Query<(&AttackHitbox, &Parent, &Transform, &Collider)>,
+    mut player_query: Query<(&Transform, &Player, &mut AttackState), Without<AttackHitbox>>,
+    target_query: Query<&GlobalTransform, With<Attackable>>,
+    rapier_context: Res<RapierContext>,
+    mut hit_events: EventWriter<HitEvent>,
+) {
+    for (hitbox, parent, offset, collider) in hitbox_query.iter() {
+        let attacker = parent.get();
+        let Ok((transform, player, mut attack)) = player_query.get_mut(attacker) else {
+            continue;
+        };
+
+        // Placed from the player rather than the hitbox's own global
+        // transform, which lags a frame behind when it has just been spawned
+        let mut touching = Vec::new();
+        let is_target = |collider: Entity| target_query.contains(collider);
+        rapier_context.intersections_with_shape(
+            transform.transform_point(offset.translation),
+            transform.rotation,
+            collider,
+            QueryFilter::default().exclude_rigid_body(attacker).predicate(&is_target),
+            |collider| {
+                touching.push(collider);
+                true
+            },
+        );
+
+        let facing = if player.facing_left { -1.0 } else { 1.0 };
+        let knockback = hitbox.0.knockback() * Vec3::new(facing, 1.0, 1.0);
+        for target in touching {
+            if attack.hit.contains(&target) {
+                continue;
+            }
+            attack.hit.push(target);
+
+            let Ok(target_transform) = target_query.get(target) else {
+                continue;
+            };
+            hit_events.send(HitEvent {
+                attacker,
+                target,
+                attack: hitbox.0,
+                damage: hitbox.0.damage(),
+                position: target_transform.translation(),
+                knockback,
+            });
+            info!("{:?} hit {:?}", hitbox.0, target);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use std::path::PathBuf;
+use crate::resources::{key_name, AuthConfig, InputAction, InputConfig, LocalSave, UserData};
+use crate::states::GameState;
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ControlsMenu>()
+            .add_systems(Update, (
+                pause_menu_system.run_if(in_state(GameState::Paused)),
+                controls_ui_system.run_if(controls_menu_open),
+            ).chain())
+            .add_systems(Update, sync_user_bindings)
+            .add_systems(OnExit(GameState::Paused), close_controls_menu)
+            .add_systems(OnExit(GameState::CharacterSelection), close_controls_menu);
+    }
+}
+
+// State of the key rebinding screen
+#[derive(Resource, Default)]
+pub struct ControlsMenu {
+    pub open: bool,
+    /// Action waiting for the player to press its new key
+    capturing: Option<InputAction>,
+    message: Option<String>,
+}
+
+/// Run condition for systems that read keys the rebinding screen might be capturing
+pub fn controls_menu_closed(menu: Res<ControlsMenu>) -> bool {
+    !menu.open
+}
+
+fn controls_menu_open(menu: Res<ControlsMenu>) -> bool {
+    menu.open
+}
+
+fn close_controls_menu(mut menu: ResMut<ControlsMenu>) {
+    *menu = ControlsMenu::default();
+}
+
+/// Loads each user's bindings from their save when they start playing, and
+/// writes any change back to it
+fn sync_user_bindings(
+    user_data: Res<UserData>,
+    auth_config: Res<AuthConfig>,
+    mut input_config: ResMut<InputConfig>,
+    mut loaded_from: Local<Option<PathBuf>>,
+) {
+    let save_path = user_data.user_id.as_deref()
+        .map(|user_id| LocalSave::path(&auth_config.save_directory, user_id));
+
+    if save_path != *loaded_from {
+        let bindings = user_data.user_id.as_deref()
+            .and_then(|user_id| LocalSave::load(&auth_config.save_directory, user_id))
+            .and_then(|save| save.input)
+            .unwrap_or_default();
+        if *input_config != bindings {
+            *input_config = bindings;
+        }
+        *loaded_from = save_path;
+        return;
+    }
+
+    if save_path.is_some() && input_config.is_changed() {
+        if let Err(e) = LocalSave::store_input(&auth_config.save_directory, &user_data, &input_config) {
+            warn!("Failed to save key bindings: {}", e);
+        }
+    }
+}
+
+fn pause_menu_system(
+    mut contexts: EguiContexts,
+    mut menu: ResMut<ControlsMenu>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if menu.open {
+        return;
+    }
+
+    egui::Window::new("Paused")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.vertical_centered(|ui| {
+                if ui.add_sized([200.0, 30.0], egui::Button::new("Resume")).clicked() {
+                    next_state.set(GameState::Playing);
+                }
+                ui.add_space(10.0);
+                if ui.add_sized([200.0, 30.0], egui::Button::new("Controls")).clicked() {
+                    menu.open = true;
+                }
+            });
+        });
+}
+
+fn controls_ui_system(
+    mut contexts: EguiContexts,
+    keyboard: Res<Input<KeyCode>>,
+    mut menu: ResMut<ControlsMenu>,
+    mut input_config: ResMut<InputConfig>,
+) {
+    // The next key pressed becomes the binding
+    if let Some(action) = menu.capturing {
+        if let Some(key) = keyboard.get_just_pressed().next().copied() {
+            menu.capturing = None;
+            menu.message = input_config.rebind(action, key).map(|other| {
+                format!(
+                    "{} was already used by {}, which is now bound to {}",
+                    key_name(key),
+                    other.label(),
+                    key_name(input_config.key(other)),
+                )
+            });
+            info!("Bound {} to {:?}", action.label(), key);
+        }
+    }
+
+    let mut capture = None;
+    let mut cancel_capture = false;
+    let mut reset = false;
+    let mut close = false;
+
+    egui::Window::new("Controls")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut(), |ui| {
+            if let Some(message) = &menu.message {
+                ui.colored_label(egui::Color32::YELLOW, message);
+                ui.add_space(10.0);
+            }
+
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("controls_grid")
+                    .num_columns(2)
+                    .spacing([40.0, 6.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for action in InputAction::ALL {
+                            ui.label(action.label());
+                            if menu.capturing == Some(action) {
+                                ui.colored_label(egui::Color32::LIGHT_BLUE, "Press a key...");
+                            } else {
+                                let button = egui::Button::new(key_name(input_config.key(action)));
+                                if ui.add_sized([140.0, 20.0], button).clicked() {
+                                    capture = Some(action);
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if menu.capturing.is_some() {
+                    if ui.button("Cancel").clicked() {
+                        cancel_capture = true;
+                    }
+                } else {
+                    if ui.button("Reset to Defaults").clicked() {
+                        reset = true;
+                    }
+                    if ui.button("Done").clicked() {
+                        close = true;
+                    }
+                }
+            });
+        });
+
+    if let Some(action) = capture {
+        menu.capturing = Some(action);
+        menu.message = None;
+    }
+    if cancel_capture {
+        menu.capturing = None;
+    }
+    if reset {
+        *input_config = InputConfig::default();
+        menu.message = Some("Controls reset to defaults".to_string());
+    }
+    if close {
+        *menu = ControlsMenu::default();
+    }
+}
@@ -14,7 +14,7 @@ impl Plugin for CorePlugin {
                 setup_loading_screen,
             ))
             .add_systems(Update, (
-                handle_game_state_transitions,
+                handle_game_state_transitions.run_if(super::controls_menu_closed),
                 update_game_stats,
                 update_performance_metrics,
                 update_loading_progress,
@@ -91,28 +91,22 @@ fn update_loading_progress(
 
 fn handle_game_state_transitions(
     mut next_state: ResMut<NextState<GameState>>,
-    keyboard: Res<Input<KeyCode>>,
+    actions: Res<ActionState>,
     current_state: Res<State<GameState>>,
     loading_progress: Res<LoadingProgress>,
 ) {
     match current_state.get() {
-        GameState::Loading => {
-            if loading_progress.progress() >= 1.0 {
-                next_state.set(GameState::Authentication);
-            }
+        GameState::Loading if loading_progress.progress() >= 1.0 => {
+            next_state.set(GameState::ProfileSelection);
         }
         GameState::CharacterSelection => {
             // Character selection handles its own transition to Playing
         }
-        GameState::Playing => {
-            if keyboard.just_pressed(KeyCode::Escape) {
-                next_state.set(GameState::Paused);
-            }
+        GameState::Playing if actions.just_pressed(InputAction::Pause) => {
+            next_state.set(GameState::Paused);
         }
-        GameState::Paused => {
-            if keyboard.just_pressed(KeyCode::Escape) {
-                next_state.set(GameState::Playing);
-            }
+        GameState::Paused if actions.just_pressed(InputAction::Pause) => {
+            next_state.set(GameState::Playing);
         }
         _ => {}
     }
@@ -166,6 +160,8 @@ fn enter_playing_state(mut play_state: ResMut<NextState<PlayState>>) {
 
 fn exit_playing_state(
     mut commands: Commands,
+    // Already the state being entered by the time OnExit runs
+    new_state: Res<State<GameState>>,
     query: Query<Entity, With<StateCleanup>>,
     player_query: Query<Entity, With<crate::Player>>,
 ) {
@@ -173,6 +169,12 @@ fn exit_playing_state(
         commands.entity(entity).despawn_recursive();
     }
 
+    // Pausing keeps the player where they are for when the game resumes
+    if *new_state.get() == GameState::Paused {
+        info!("Exited playing state to pause");
+        return;
+    }
+
     // Despawn player when exiting playing state
     for player_entity in player_query.iter() {
         commands.entity(player_entity).despawn_recursive();
@@ -204,6 +206,15 @@ fn preload_animations(
         idle: asset_server.load("boss3.glb#Animation6"),
         front_flip: asset_server.load("boss3.glb#Animation3"),
         dive_roll: asset_server.load("boss3.glb#Animation4"),
+        // No hang clip in the model yet; the fall pose stands in
+        hang: asset_server.load("boss3.glb#Animation5"),
+        // Placeholders until the model has attack clips: the unnamed Mixamo
+        // takes stand in for swings, and the front flip for the air slash
+        attack: [
+            asset_server.load("boss3.glb#Animation2"),
+            asset_server.load("boss3.glb#Animation1"),
+        ],
+        air_attack: asset_server.load("boss3.glb#Animation3"),
     };
 
     // Preload animations for SwordHero
@@ -213,6 +224,12 @@ fn preload_animations(
         idle: asset_server.load("swordHero.glb#Animation5"),
         front_flip: asset_server.load("swordHero.glb#Animation2"),
         dive_roll: asset_server.load("swordHero.glb#Animation4"),
+        hang: asset_server.load("swordHero.glb#Animation1"),
+        attack: [
+            asset_server.load("swordHero.glb#Animation3"),
+            asset_server.load("swordHero.glb#Animation6"),
+        ],
+        air_attack: asset_server.load("swordHero.glb#Animation7"),
     };
 
     commands.insert_resource(PreloadedAnimations {
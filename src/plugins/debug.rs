@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy_egui::{egui, EguiContexts};
 use bevy_rapier3d::prelude::{Collider, Friction};
 use crate::{
     events::*,
@@ -15,6 +16,8 @@ impl Plugin for DebugPlugin {
             .add_systems(OnEnter(GameState::Playing), (
                 setup_fps_ui,
                 setup_coin_counter_ui,
+                setup_ability_hud,
+                setup_health_hud,
             ))
             .add_systems(Update, (
                 handle_debug_events,
@@ -22,6 +25,9 @@ impl Plugin for DebugPlugin {
                 handle_debug_commands,
                 update_fps_display,
                 update_coin_counter_display,
+                update_ability_hud,
+                update_health_hud,
+                tuning_panel_system,
             ).run_if(in_state(GameState::Playing)));
     }
 }
@@ -71,11 +77,11 @@ fn update_debug_display(
 }
 
 fn handle_debug_commands(
-    keyboard: Res<Input<KeyCode>>,
+    actions: Res<ActionState>,
     mut debug_events: EventWriter<DebugEvent>,
     mut stats: ResMut<GameStats>,
 ) {
-    if keyboard.just_pressed(KeyCode::F6) {
+    if actions.just_pressed(InputAction::ResetStats) {
         stats.jump_count = 0;
         stats.flip_count = 0;
         stats.play_time = 0.0;
@@ -86,6 +92,136 @@ fn handle_debug_commands(
     }
 }
 
+/// Live editing of the movement tuning while debug mode is on
+fn tuning_panel_system(
+    mut contexts: EguiContexts,
+    config: Res<GameConfig>,
+    mut tuning: ResMut<MovementTuning>,
+    mut status: Local<Option<String>>,
+) {
+    if !config.debug_mode {
+        return;
+    }
+
+    let mut edited = tuning.clone();
+    egui::Window::new("Movement Tuning")
+        .default_open(false)
+        .resizable(false)
+        .anchor(egui::Align2::RIGHT_TOP, [-10.0, 10.0])
+        .show(contexts.ctx_mut(), |ui| {
+            egui::ScrollArea::vertical().max_height(500.0).show(ui, |ui| {
+                egui::Grid::new("movement_tuning_grid").num_columns(2).show(ui, |ui| {
+                    tuning_slider(ui, "Ground acceleration", &mut edited.ground_acceleration, 1.0..=200.0);
+                    tuning_slider(ui, "Ground deceleration", &mut edited.ground_deceleration, 1.0..=200.0);
+                    tuning_slider(ui, "Ground turn", &mut edited.ground_turn_acceleration, 1.0..=300.0);
+                    tuning_slider(ui, "Air acceleration", &mut edited.air_acceleration, 0.0..=200.0);
+                    tuning_slider(ui, "Air deceleration", &mut edited.air_deceleration, 0.0..=200.0);
+                    tuning_slider(ui, "Stick curve exponent", &mut edited.input_exponent, 0.5..=3.0);
+                });
+
+                ui.separator();
+                egui::Grid::new("jump_tuning_grid").num_columns(2).show(ui, |ui| {
+                    tuning_slider(ui, "Jump velocity", &mut edited.jump_velocity, 1.0..=20.0);
+                    tuning_slider(ui, "Jump buffer (s)", &mut edited.jump_buffer_time, 0.0..=0.5);
+                    tuning_slider(ui, "Coyote time (s)", &mut edited.coyote_time, 0.0..=0.5);
+                    tuning_slider(ui, "Jump cut multiplier", &mut edited.jump_cut_multiplier, 0.0..=1.0);
+                });
+
+                ui.separator();
+                egui::Grid::new("wall_tuning_grid").num_columns(2).show(ui, |ui| {
+                    tuning_slider(ui, "Wall slide speed", &mut edited.wall_slide_speed, 0.0..=10.0);
+                    tuning_slider(ui, "Wall jump horizontal", &mut edited.wall_jump_horizontal, 0.0..=20.0);
+                    tuning_slider(ui, "Wall jump vertical", &mut edited.wall_jump_vertical, 0.0..=20.0);
+                    tuning_slider(ui, "Wall jump lockout (s)", &mut edited.wall_jump_lockout, 0.0..=1.0);
+                    tuning_slider(ui, "Ledge climb time (s)", &mut edited.ledge_climb_time, 0.05..=1.5);
+                });
+
+                ui.separator();
+                egui::Grid::new("ability_tuning_grid").num_columns(2).show(ui, |ui| {
+                    ui.label("Air jumps");
+                    ui.add(egui::Slider::new(&mut edited.air_jumps, 0..=3));
+                    ui.end_row();
+                    tuning_slider(ui, "Double jump velocity", &mut edited.double_jump_velocity, 1.0..=20.0);
+                    ui.label("Air dashes");
+                    ui.add(egui::Slider::new(&mut edited.air_dashes, 0..=3));
+                    ui.end_row();
+                    tuning_slider(ui, "Air dash speed", &mut edited.air_dash_speed, 1.0..=40.0);
+                    tuning_slider(ui, "Air dash time (s)", &mut edited.air_dash_time, 0.05..=1.0);
+                    tuning_slider(ui, "Ground pound speed", &mut edited.ground_pound_speed, 1.0..=50.0);
+                });
+
+                ui.separator();
+                egui::Grid::new("controller_tuning_grid").num_columns(2).show(ui, |ui| {
+                    ui.label("Controller");
+                    ui.horizontal(|ui| {
+                        for mode in [ControllerMode::Dynamic, ControllerMode::Kinematic] {
+                            ui.radio_value(&mut edited.controller, mode, mode.label());
+                        }
+                    });
+                    ui.end_row();
+
+                    // Characters can override the controller above, to compare feel side by side
+                    for character in CharacterType::ALL {
+                        ui.label(character.display_name());
+                        let mut choice = edited.character_controllers.get(character.id()).copied();
+                        egui::ComboBox::from_id_source(character.id())
+                            .selected_text(choice.map_or("Default", |mode| mode.label()))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut choice, None, "Default");
+                                for mode in [ControllerMode::Dynamic, ControllerMode::Kinematic] {
+                                    ui.selectable_value(&mut choice, Some(mode), mode.label());
+                                }
+                            });
+                        match choice {
+                            Some(mode) => edited.character_controllers.insert(character.id().to_string(), mode),
+                            None => edited.character_controllers.remove(character.id()),
+                        };
+                        ui.end_row();
+                    }
+
+                    tuning_slider(ui, "Max slope (deg)", &mut edited.max_slope_angle, 0.0..=80.0);
+                    tuning_slider(ui, "Step height", &mut edited.step_height, 0.0..=1.0);
+                    tuning_slider(ui, "Snap to ground", &mut edited.snap_to_ground, 0.0..=1.0);
+                });
+
+                ui.separator();
+                egui::Grid::new("surface_tuning_grid").num_columns(2).show(ui, |ui| {
+                    tuning_slider(ui, "Ice traction", &mut edited.ice_traction, 0.01..=1.0);
+                    tuning_slider(ui, "Sticky speed multiplier", &mut edited.sticky_speed_multiplier, 0.1..=1.0);
+                });
+            });
+
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    let path = std::path::Path::new(MOVEMENT_TUNING_PATH);
+                    *status = Some(match edited.write(path) {
+                        Ok(()) => format!("Saved to {}", path.display()),
+                        Err(e) => format!("Save failed: {}", e),
+                    });
+                }
+                if ui.button("Defaults").clicked() {
+                    edited = MovementTuning::default();
+                }
+            });
+
+            if let Some(status) = status.as_ref() {
+                ui.label(status);
+            }
+        });
+
+    // Only touch the resource when something changed, to keep change detection useful
+    if edited != *tuning {
+        *tuning = edited;
+    }
+}
+
+fn tuning_slider(ui: &mut egui::Ui, label: &str, value: &mut f32, range: std::ops::RangeInclusive<f32>) {
+    ui.label(label);
+    ui.add(egui::Slider::new(value, range));
+    ui.end_row();
+}
+
 // Component for the FPS text entity
 #[derive(Component)]
 struct FpsText;
@@ -94,6 +230,17 @@ struct FpsText;
 #[derive(Component)]
 struct CoinCounterText;
 
+// One HUD icon per ability, dimmed while locked or used up
+#[derive(Component)]
+struct AbilityIcon(Ability);
+
+// Row of health pips, one per point of the player's maximum health
+#[derive(Component)]
+struct HealthBar;
+
+#[derive(Component)]
+struct HealthPip;
+
 fn setup_fps_ui(mut commands: Commands) {
     // Create FPS counter UI
     commands.spawn((
@@ -174,3 +321,146 @@ fn update_coin_counter_display(
     }
 }
 
+fn setup_ability_hud(mut commands: Commands) {
+    // Ability icons in the bottom left
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(15.0),
+                bottom: Val::Px(15.0),
+                column_gap: Val::Px(8.0),
+                ..default()
+            },
+            ..default()
+        },
+        StateCleanup,
+    )).with_children(|parent| {
+        for ability in Ability::ALL {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Px(44.0),
+                        height: Val::Px(44.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                },
+                AbilityIcon(ability),
+            )).with_children(|icon| {
+                icon.spawn(TextBundle::from_section(
+                    ability.icon(),
+                    TextStyle {
+                        font: default(),
+                        font_size: 18.0,
+                        color: Color::WHITE,
+                    },
+                ));
+            });
+        }
+    });
+}
+
+fn update_ability_hud(
+    user_data: Res<UserData>,
+    tuning: Res<MovementTuning>,
+    player_query: Query<&crate::AbilityState, With<crate::Player>>,
+    mut icon_query: Query<(&AbilityIcon, &mut BackgroundColor, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    let abilities = player_query.get_single().ok();
+
+    for (icon, mut background, children) in icon_query.iter_mut() {
+        let ability = icon.0;
+        let unlocked = user_data.player_stats.has_ability(ability);
+        let ready = abilities.map_or(true, |abilities| match ability {
+            Ability::DoubleJump => abilities.air_jumps_used < tuning.air_jumps,
+            Ability::AirDash => abilities.air_dashes_used < tuning.air_dashes,
+            Ability::GroundPound => !abilities.is_ground_pounding,
+        });
+
+        let (color, label) = if !unlocked {
+            (Color::rgba(0.2, 0.2, 0.2, 0.6), format!("{}\n{}", ability.icon(), ability.unlock_score()))
+        } else if ready {
+            (Color::rgba(0.2, 0.5, 0.9, 0.85), ability.icon().to_string())
+        } else {
+            (Color::rgba(0.2, 0.3, 0.45, 0.6), ability.icon().to_string())
+        };
+        background.0 = color;
+
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = label.clone();
+                text.sections[0].style.color = if unlocked { Color::WHITE } else { Color::GRAY };
+            }
+        }
+    }
+}
+
+fn setup_health_hud(mut commands: Commands) {
+    // Health pips under the coin counter; filled in once the player exists
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(15.0),
+                top: Val::Px(50.0),
+                column_gap: Val::Px(6.0),
+                ..default()
+            },
+            ..default()
+        },
+        HealthBar,
+        StateCleanup,
+    ));
+}
+
+fn update_health_hud(
+    mut commands: Commands,
+    player_query: Query<&crate::Health, With<crate::Player>>,
+    bar_query: Query<(Entity, Option<&Children>), With<HealthBar>>,
+    mut pip_query: Query<&mut BackgroundColor, With<HealthPip>>,
+    time: Res<Time>,
+) {
+    let Ok(health) = player_query.get_single() else {
+        return;
+    };
+
+    for (bar, children) in bar_query.iter() {
+        let pips = children.map_or(0, |children| children.len());
+        if pips != health.max as usize {
+            commands.entity(bar).despawn_descendants().with_children(|parent| {
+                for _ in 0..health.max {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Px(22.0),
+                                height: Val::Px(22.0),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        HealthPip,
+                    ));
+                }
+            });
+            continue;
+        }
+
+        // Pips blink while the player can't be hurt
+        let blink = health.is_invulnerable() && (time.elapsed_seconds() * 12.0).sin() > 0.0;
+        for (i, child) in children.into_iter().flatten().enumerate() {
+            if let Ok(mut background) = pip_query.get_mut(*child) {
+                background.0 = if (i as u32) >= health.current {
+                    Color::rgba(0.25, 0.1, 0.1, 0.6)
+                } else if blink {
+                    Color::rgba(1.0, 0.6, 0.6, 0.9)
+                } else {
+                    Color::rgba(0.9, 0.15, 0.2, 0.9)
+                };
+            }
+        }
+    }
+}
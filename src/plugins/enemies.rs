@@ -0,0 +1,375 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use crate::{
+    components::*,
+    events::*,
+    plugins::combat::Attackable,
+    resources::MovementTuning,
+    states::*,
+};
+
+pub struct EnemiesPlugin;
+
+impl Plugin for EnemiesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(PlayState::Setup), setup_enemies)
+            .add_systems(Update, (
+                update_enemy_ai,
+                handle_enemy_contact.after(update_enemy_ai),
+                handle_enemy_hits,
+                update_defeated_enemies,
+                link_enemy_animators,
+                animate_enemies.after(link_enemy_animators),
+                reset_enemies_on_spawn,
+            ).run_if(in_state(GameState::Playing)));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnemyKind {
+    /// Slow and heavy, from `boss.glb`
+    Brute,
+    /// Quick to give chase, from `boss2.glb`
+    Stalker,
+}
+
+impl EnemyKind {
+    fn scene_path(&self) -> &'static str {
+        match self {
+            EnemyKind::Brute => "boss.glb#Scene0",
+            EnemyKind::Stalker => "boss2.glb#Scene0",
+        }
+    }
+
+    /// Walk and idle clips
+    fn animation_paths(&self) -> (&'static str, &'static str) {
+        match self {
+            // Unnamed Mixamo clips; the short loop is the walk
+            EnemyKind::Brute => ("boss.glb#Animation0", "boss.glb#Animation1"),
+            EnemyKind::Stalker => ("boss2.glb#Animation7", "boss2.glb#Animation4"),
+        }
+    }
+
+    /// Patrol speed, chase speed and the distance the enemy notices the player from
+    fn movement(&self) -> (f32, f32, f32) {
+        match self {
+            EnemyKind::Brute => (1.2, 2.5, 5.0),
+            EnemyKind::Stalker => (2.0, 4.5, 8.0),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Enemy {
+    pub kind: EnemyKind,
+    /// Where the enemy stands at the start, and goes back to on a reset
+    pub home: Vec3,
+}
+
+/// How an enemy moves: back and forth along its patrol until the player
+/// comes close, then after them, never leaving the patrol range
+#[derive(Component)]
+pub struct EnemyAi {
+    pub state: EnemyState,
+    /// Leftmost and rightmost x the enemy walks to
+    pub patrol: (f32, f32),
+    pub patrol_speed: f32,
+    pub chase_speed: f32,
+    pub chase_radius: f32,
+    pub facing_left: bool,
+    pub is_moving: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EnemyState {
+    Patrol,
+    Chase,
+    /// Beaten; `timer` counts the squash down before the enemy disappears
+    Defeated { timer: f32 },
+}
+
+/// Marks an animation player inside an enemy's model, so the player's
+/// animations leave it alone
+#[derive(Component)]
+pub struct EnemyAnimator {
+    pub enemy: Entity,
+}
+
+#[derive(Resource)]
+struct EnemyAnimations {
+    brute: (Handle<AnimationClip>, Handle<AnimationClip>),
+    stalker: (Handle<AnimationClip>, Handle<AnimationClip>),
+}
+
+fn setup_enemies(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    // Enemies: kind, where they stand, and the x range they patrol
+    let enemy_configs = vec![
+        // On the ground past the trampoline
+        (EnemyKind::Brute, Vec3::new(14.0, 0.85, 0.0), (9.0, 22.0)),
+        // Wading through the mud
+        (EnemyKind::Stalker, Vec3::new(44.5, 0.85, 0.0), (42.0, 47.0)),
+        // Guarding the Section 3 convergence bridge
+        (EnemyKind::Stalker, Vec3::new(39.0, 24.1, 0.0), (37.5, 40.5)),
+        // On the final convergence
+        (EnemyKind::Brute, Vec3::new(40.0, 62.1, 0.0), (38.0, 42.0)),
+    ];
+
+    let load_clips = |kind: EnemyKind| {
+        let (walk, idle) = kind.animation_paths();
+        (asset_server.load(walk), asset_server.load(idle))
+    };
+    commands.insert_resource(EnemyAnimations {
+        brute: load_clips(EnemyKind::Brute),
+        stalker: load_clips(EnemyKind::Stalker),
+    });
+
+    for (kind, home, patrol) in enemy_configs {
+        let (patrol_speed, chase_speed, chase_radius) = kind.movement();
+        commands.spawn((
+            SpatialBundle::from_transform(Transform::from_translation(home)),
+            RigidBody::KinematicPositionBased,
+            // A sensor, so touching an enemy is up to `handle_enemy_contact`
+            Collider::capsule_y(0.4, 0.4),
+            Sensor,
+            Attackable,
+            Enemy { kind, home },
+            EnemyAi {
+                state: EnemyState::Patrol,
+                patrol,
+                patrol_speed,
+                chase_speed,
+                chase_radius,
+                facing_left: false,
+                is_moving: false,
+            },
+            Name::new(format!("Enemy_{:?}", kind)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                SceneBundle {
+                    scene: asset_server.load(kind.scene_path()),
+                    transform: Transform::from_xyz(0.0, -0.8, 0.0)
+                        .with_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2)),
+                    ..default()
+                },
+                Name::new("EnemyModel"),
+            ));
+        });
+    }
+
+    info!("Enemies setup complete");
+}
+
+fn update_enemy_ai(
+    mut enemy_query: Query<(&mut Transform, &mut EnemyAi), With<Enemy>>,
+    player_query: Query<(&Transform, &Player), Without<Enemy>>,
+    time: Res<Time>,
+) {
+    // Enemies only chase players on about the same level as them
+    const CHASE_HEIGHT: f32 = 2.0;
+
+    let player = player_query.get_single().ok()
+        .filter(|(_, player)| !player.is_dead)
+        .map(|(transform, _)| transform.translation);
+
+    for (mut transform, mut ai) in enemy_query.iter_mut() {
+        if matches!(ai.state, EnemyState::Defeated { .. }) {
+            continue;
+        }
+
+        let (min_x, max_x) = ai.patrol;
+        let noticed = player.filter(|player| {
+            (player.x - transform.translation.x).abs() <= ai.chase_radius
+                && (player.y - transform.translation.y).abs() <= CHASE_HEIGHT
+        });
+
+        let (target_x, speed) = match noticed {
+            Some(player) => {
+                ai.state = EnemyState::Chase;
+                (player.x.clamp(min_x, max_x), ai.chase_speed)
+            }
+            None => {
+                ai.state = EnemyState::Patrol;
+                // Turn round at either end of the patrol
+                if transform.translation.x <= min_x {
+                    ai.facing_left = false;
+                } else if transform.translation.x >= max_x {
+                    ai.facing_left = true;
+                }
+                (if ai.facing_left { min_x } else { max_x }, ai.patrol_speed)
+            }
+        };
+
+        let step = (target_x - transform.translation.x).clamp(-speed * time.delta_seconds(), speed * time.delta_seconds());
+        transform.translation.x += step;
+        ai.is_moving = step != 0.0;
+        if ai.is_moving {
+            ai.facing_left = step < 0.0;
+        }
+        transform.rotation = Quat::from_rotation_y(if ai.facing_left { std::f32::consts::PI } else { 0.0 });
+    }
+}
+
+/// Decides what happens when the player touches an enemy: a dive roll or a
+/// landing on its head beats it, anything else hurts the player and knocks them back
+fn handle_enemy_contact(
+    mut player_query: Query<(Entity, &Transform, &mut Velocity, &Player, &mut AbilityState, &Health)>,
+    mut enemy_query: Query<(&Transform, &mut EnemyAi), With<Enemy>>,
+    rapier_context: Res<RapierContext>,
+    mut hurt_events: EventWriter<PlayerHurtEvent>,
+    tuning: Res<MovementTuning>,
+) {
+    // A little larger than the player's capsule, as with hazards
+    const CONTACT_MARGIN: f32 = 0.05;
+    // Player centre this far above the enemy's counts as landing on its head
+    const STOMP_HEIGHT: f32 = 1.0;
+    const KNOCKBACK: Vec2 = Vec2::new(6.0, 5.0);
+
+    for (entity, transform, mut velocity, player, mut abilities, health) in player_query.iter_mut() {
+        if player.is_dead {
+            continue;
+        }
+
+        let mut touching = Vec::new();
+        let is_enemy = |collider: Entity| enemy_query.contains(collider);
+        rapier_context.intersections_with_shape(
+            transform.translation,
+            Quat::IDENTITY,
+            &Collider::capsule_y(0.4, 0.4 + CONTACT_MARGIN),
+            QueryFilter::default().exclude_rigid_body(entity).predicate(&is_enemy),
+            |collider| {
+                touching.push(collider);
+                true
+            },
+        );
+
+        for enemy in touching {
+            let Ok((enemy_transform, mut ai)) = enemy_query.get_mut(enemy) else {
+                continue;
+            };
+            if matches!(ai.state, EnemyState::Defeated { .. }) {
+                continue;
+            }
+
+            let offset = transform.translation - enemy_transform.translation;
+            let stomped = offset.y >= STOMP_HEIGHT && velocity.linvel.y <= 0.0;
+            if player.is_dive_rolling || stomped || abilities.is_ground_pounding {
+                ai.state = EnemyState::Defeated { timer: 0.0 };
+                if !player.is_dive_rolling {
+                    // Bounce off the head, with the air jumps back
+                    velocity.linvel.y = tuning.jump_velocity;
+                    abilities.is_ground_pounding = false;
+                    abilities.air_jumps_used = 0;
+                }
+                info!("Enemy {:?} defeated", enemy);
+            } else if !health.is_invulnerable() {
+                let side = if offset.x < 0.0 { -1.0 } else { 1.0 };
+                hurt_events.send(PlayerHurtEvent {
+                    entity,
+                    damage: 1,
+                    knockback: Vec3::new(side * KNOCKBACK.x, KNOCKBACK.y, 0.0),
+                    cause: DeathCause::Enemy,
+                });
+            }
+        }
+    }
+}
+
+/// Any landed attack beats an enemy
+fn handle_enemy_hits(
+    mut hit_events: EventReader<HitEvent>,
+    mut enemy_query: Query<&mut EnemyAi, With<Enemy>>,
+) {
+    for event in hit_events.read() {
+        let Ok(mut ai) = enemy_query.get_mut(event.target) else {
+            continue;
+        };
+        if !matches!(ai.state, EnemyState::Defeated { .. }) {
+            ai.state = EnemyState::Defeated { timer: 0.0 };
+            info!("Enemy {:?} defeated by {:?}", event.target, event.attack);
+        }
+    }
+}
+
+/// Squashes beaten enemies flat, then hides them until the next reset
+fn update_defeated_enemies(
+    mut commands: Commands,
+    mut enemy_query: Query<(Entity, &mut Transform, &mut EnemyAi, &mut Visibility), With<Enemy>>,
+    time: Res<Time>,
+) {
+    const SQUASH_TIME: f32 = 0.4;
+
+    for (entity, mut transform, mut ai, mut visibility) in enemy_query.iter_mut() {
+        let EnemyState::Defeated { timer } = ai.state else {
+            continue;
+        };
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+
+        let timer = timer + time.delta_seconds();
+        ai.state = EnemyState::Defeated { timer };
+        transform.scale = Vec3::new(1.0 + timer, (1.0 - timer / SQUASH_TIME).max(0.05), 1.0 + timer);
+        if timer >= SQUASH_TIME {
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).insert(ColliderDisabled);
+        }
+    }
+}
+
+/// Tags the animation player of each enemy's model once its scene has loaded
+fn link_enemy_animators(
+    mut commands: Commands,
+    animator_query: Query<Entity, Added<AnimationPlayer>>,
+    parent_query: Query<&Parent>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    for animator in animator_query.iter() {
+        if let Some(enemy) = parent_query.iter_ancestors(animator).find(|ancestor| enemy_query.contains(*ancestor)) {
+            commands.entity(animator).insert(EnemyAnimator { enemy });
+        }
+    }
+}
+
+fn animate_enemies(
+    mut animator_query: Query<(&mut AnimationPlayer, &EnemyAnimator)>,
+    enemy_query: Query<(&Enemy, &EnemyAi)>,
+    animations: Res<EnemyAnimations>,
+) {
+    for (mut animation_player, animator) in animator_query.iter_mut() {
+        let Ok((enemy, ai)) = enemy_query.get(animator.enemy) else {
+            continue;
+        };
+
+        let (walk, idle) = match enemy.kind {
+            EnemyKind::Brute => &animations.brute,
+            EnemyKind::Stalker => &animations.stalker,
+        };
+        // A chase held at the end of the patrol stands and waits
+        let walking = ai.is_moving && !matches!(ai.state, EnemyState::Defeated { .. });
+        animation_player.play(if walking { walk.clone() } else { idle.clone() }).repeat();
+    }
+}
+
+/// Puts every enemy back where it started when the player spawns or respawns
+fn reset_enemies_on_spawn(
+    mut commands: Commands,
+    mut spawn_events: EventReader<PlayerSpawnEvent>,
+    mut enemy_query: Query<(Entity, &Enemy, &mut Transform, &mut EnemyAi, &mut Visibility)>,
+) {
+    if spawn_events.read().count() == 0 {
+        return;
+    }
+
+    for (entity, enemy, mut transform, mut ai, mut visibility) in enemy_query.iter_mut() {
+        transform.translation = enemy.home;
+        transform.scale = Vec3::ONE;
+        ai.state = EnemyState::Patrol;
+        *visibility = Visibility::Inherited;
+        commands.entity(entity).remove::<ColliderDisabled>();
+    }
+}
@@ -0,0 +1,395 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use bevy_rapier3d::rapier::geometry::CollisionEventFlags;
+use crate::{
+    components::*,
+    events::*,
+    states::*,
+};
+
+pub struct HazardsPlugin;
+
+impl Plugin for HazardsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(PlayState::Setup), setup_hazards)
+            .add_systems(Update, (
+                update_lasers,
+                aim_and_fire_turrets,
+                update_projectiles,
+                check_hazard_contact.after(update_lasers),
+                clear_projectiles_on_spawn,
+            ).run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// Hurts the player on contact
+#[derive(Component)]
+pub struct Hazard {
+    pub cause: DeathCause,
+    /// Health lost on contact; `None` kills outright
+    pub damage: Option<u32>,
+}
+
+// Beam that switches on and off on a fixed cycle
+#[derive(Component)]
+pub struct Laser {
+    /// Seconds the beam is on in each cycle
+    pub on_time: f32,
+    /// Seconds the beam is off in each cycle
+    pub off_time: f32,
+    /// Seconds into the cycle at startup, so neighbouring lasers can alternate
+    pub offset: f32,
+    pub active: bool,
+}
+
+// Fires projectiles at the player while they're in range
+#[derive(Component)]
+pub struct Turret {
+    /// Seconds between shots
+    pub fire_interval: f32,
+    pub projectile_speed: f32,
+    pub range: f32,
+    pub cooldown: f32,
+}
+
+#[derive(Component)]
+pub struct Projectile {
+    /// Seconds left before the projectile is removed if it hasn't hit anything
+    pub lifetime: f32,
+}
+
+fn setup_hazards(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Kill planes: height, and the x range they cover. Each sits under a
+    // stretch of the course with nothing worth landing on below it, so a
+    // fall there ends straight away instead of at the bottom of the world
+    let kill_planes = vec![
+        // Below the Gauntlet's far stones
+        (33.0, 33.5, 45.0),
+        // Below the right path of Section 7
+        (38.0, 44.0, 56.0),
+        // Either side of the course, past the ground
+        (-2.0, 53.0, 80.0),
+        (-2.0, -80.0, -36.0),
+    ];
+
+    // Spike strips: centre of the strip's base, and its length along x
+    let spike_strips: Vec<(Vec3, f32)> = vec![
+        // Under the ferry near the start
+        (Vec3::new(-6.5, 0.0, 0.0), 3.0),
+        // At the end of the ice, to be jumped before sliding into it
+        (Vec3::new(33.0, 0.0, 0.0), 1.0),
+    ];
+
+    // Lasers: one end, the other end, and the on, off and offset times
+    let lasers = vec![
+        // Across the gap in the tutorial jumps
+        (Vec3::new(21.0, 3.5, 0.0), Vec3::new(21.0, 10.0, 0.0), 1.5, 1.5, 0.0),
+        // Across the wall climb
+        (Vec3::new(6.0, 41.0, 0.0), Vec3::new(19.0, 41.0, 0.0), 2.0, 2.0, 1.0),
+    ];
+
+    // Turrets: position, seconds between shots, projectile speed and range
+    let turrets = vec![
+        // On the emergency platform below the Gauntlet
+        (Vec3::new(30.0, 45.75, 0.0), 2.5, 10.0, 20.0),
+        // Covering the blinking platforms on the side path
+        (Vec3::new(-37.0, 13.0, 0.0), 3.0, 8.0, 18.0),
+    ];
+
+    for (height, min_x, max_x) in kill_planes {
+        commands.spawn((
+            TransformBundle::from_transform(Transform::from_xyz((min_x + max_x) * 0.5, height - 0.5, 0.0)),
+            Collider::cuboid((max_x - min_x) * 0.5, 0.5, 10.0),
+            Sensor,
+            Hazard { cause: DeathCause::KillPlane, damage: None },
+            Name::new("KillPlane"),
+        ));
+    }
+
+    let spike_mesh = meshes.add(Mesh::from(shape::Box::new(0.25, 0.25, 0.25)));
+    let spike_material = materials.add(StandardMaterial {
+        base_color: Color::rgb(0.75, 0.75, 0.8),
+        metallic: 0.8,
+        perceptual_roughness: 0.3,
+        ..default()
+    });
+
+    for (base, length) in spike_strips {
+        let count = (length / 0.4).round().max(1.0) as usize;
+        commands.spawn((
+            SpatialBundle::from_transform(Transform::from_translation(base + Vec3::Y * 0.15)),
+            Collider::cuboid(length * 0.5, 0.15, 1.5),
+            Sensor,
+            Hazard { cause: DeathCause::Spikes, damage: Some(1) },
+            Name::new("SpikeStrip"),
+        ))
+        .with_children(|parent| {
+            // Cubes stood on a corner read as spikes
+            for i in 0..count {
+                for z in [-1.0, 0.0, 1.0] {
+                    let x = (i as f32 + 0.5) * length / count as f32 - length * 0.5;
+                    parent.spawn(PbrBundle {
+                        mesh: spike_mesh.clone(),
+                        material: spike_material.clone(),
+                        transform: Transform::from_xyz(x, 0.0, z)
+                            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4) * Quat::from_rotation_x(std::f32::consts::FRAC_PI_4)),
+                        ..default()
+                    });
+                }
+            }
+        });
+    }
+
+    let laser_material = materials.add(StandardMaterial {
+        base_color: Color::rgb(1.0, 0.1, 0.1),
+        emissive: Color::rgb(4.0, 0.2, 0.2),
+        unlit: true,
+        ..default()
+    });
+
+    for (start, end, on_time, off_time, offset) in lasers {
+        let length = start.distance(end);
+        let rotation = Quat::from_rotation_arc(Vec3::X, (end - start).normalize());
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Mesh::from(shape::Box::new(length, 0.08, 0.08))),
+                material: laser_material.clone(),
+                transform: Transform::from_translation((start + end) * 0.5).with_rotation(rotation),
+                ..default()
+            },
+            Collider::cuboid(length * 0.5, 0.05, 1.0),
+            Sensor,
+            Hazard { cause: DeathCause::Laser, damage: Some(1) },
+            Laser {
+                on_time,
+                off_time,
+                offset,
+                active: true,
+            },
+            Name::new("Laser"),
+        ));
+    }
+
+    let turret_mesh = meshes.add(Mesh::from(shape::Box::new(1.0, 1.0, 1.0)));
+    let turret_material = materials.add(StandardMaterial {
+        base_color: Color::rgb(0.25, 0.25, 0.3),
+        emissive: Color::rgb(0.6, 0.1, 0.1),
+        ..default()
+    });
+
+    for (position, fire_interval, projectile_speed, range) in turrets {
+        commands.spawn((
+            PbrBundle {
+                mesh: turret_mesh.clone(),
+                material: turret_material.clone(),
+                transform: Transform::from_translation(position),
+                ..default()
+            },
+            RigidBody::Fixed,
+            Collider::cuboid(0.5, 0.5, 0.5),
+            Turret {
+                fire_interval,
+                projectile_speed,
+                range,
+                cooldown: fire_interval,
+            },
+            Name::new("Turret"),
+        ));
+    }
+
+    info!("Hazards setup complete");
+}
+
+/// Switches lasers on and off, taking their collider with them
+fn update_lasers(
+    mut commands: Commands,
+    mut laser_query: Query<(Entity, &mut Laser, &mut Visibility)>,
+    time: Res<Time>,
+) {
+    for (entity, mut laser, mut visibility) in laser_query.iter_mut() {
+        let cycle = laser.on_time + laser.off_time;
+        let active = (time.elapsed_seconds() + laser.offset).rem_euclid(cycle.max(0.01)) < laser.on_time;
+        if active == laser.active {
+            continue;
+        }
+
+        laser.active = active;
+        if active {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<ColliderDisabled>();
+        } else {
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).insert(ColliderDisabled);
+        }
+    }
+}
+
+fn aim_and_fire_turrets(
+    mut commands: Commands,
+    mut turret_query: Query<(&Transform, &mut Turret)>,
+    player_query: Query<(&Transform, &Player), Without<Turret>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut projectile_assets: Local<Option<(Handle<Mesh>, Handle<StandardMaterial>)>>,
+    time: Res<Time>,
+) {
+    // Far enough out to clear the turret's own collider
+    const MUZZLE_DISTANCE: f32 = 0.9;
+
+    let Ok((player_transform, player)) = player_query.get_single() else {
+        return;
+    };
+
+    for (transform, mut turret) in turret_query.iter_mut() {
+        turret.cooldown -= time.delta_seconds();
+
+        let to_player = player_transform.translation - transform.translation;
+        if player.is_dead || to_player.length() > turret.range || turret.cooldown > 0.0 {
+            continue;
+        }
+        turret.cooldown = turret.fire_interval;
+
+        let (mesh, material) = projectile_assets.get_or_insert_with(|| (
+            meshes.add(Mesh::from(shape::UVSphere { radius: 0.2, ..default() })),
+            materials.add(StandardMaterial {
+                base_color: Color::rgb(1.0, 0.4, 0.1),
+                emissive: Color::rgb(3.0, 1.0, 0.2),
+                ..default()
+            }),
+        ));
+
+        let direction = to_player.normalize_or_zero();
+        commands.spawn((
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(transform.translation + direction * MUZZLE_DISTANCE),
+                ..default()
+            },
+            RigidBody::Dynamic,
+            Collider::ball(0.2),
+            Velocity::linear(direction * turret.projectile_speed),
+            GravityScale(0.0),
+            Ccd::enabled(),
+            ActiveEvents::COLLISION_EVENTS,
+            Hazard { cause: DeathCause::Projectile, damage: Some(1) },
+            Projectile { lifetime: 5.0 },
+            Name::new("Projectile"),
+        ));
+    }
+}
+
+/// Removes projectiles when they hit something solid or run out of time
+fn update_projectiles(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut projectile_query: Query<(Entity, &mut Projectile)>,
+    time: Res<Time>,
+) {
+    for collision_event in collision_events.read() {
+        let CollisionEvent::Started(entity1, entity2, flags) = collision_event else {
+            continue;
+        };
+        // Flying through kill planes and lasers doesn't stop a projectile
+        if flags.contains(CollisionEventFlags::SENSOR) {
+            continue;
+        }
+        for entity in [entity1, entity2] {
+            if projectile_query.contains(*entity) {
+                commands.entity(*entity).despawn_recursive();
+            }
+        }
+    }
+
+    for (entity, mut projectile) in projectile_query.iter_mut() {
+        projectile.lifetime -= time.delta_seconds();
+        if projectile.lifetime <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Hurts the player the moment they touch a hazard, knocking them away from
+/// it, or kills them outright if it has no damage
+fn check_hazard_contact(
+    mut player_query: Query<(Entity, &Transform, &mut Player, &Health)>,
+    hazard_query: Query<(&Hazard, &GlobalTransform)>,
+    rapier_context: Res<RapierContext>,
+    mut death_events: EventWriter<PlayerDeathEvent>,
+    mut hurt_events: EventWriter<PlayerHurtEvent>,
+) {
+    // A little larger than the player's capsule, so solid hazards like
+    // projectiles register before they bounce off
+    const CONTACT_MARGIN: f32 = 0.05;
+    const KNOCKBACK: Vec2 = Vec2::new(4.0, 7.0);
+
+    for (entity, transform, mut player, health) in player_query.iter_mut() {
+        if player.is_dead {
+            continue;
+        }
+
+        let mut contact = None;
+        let is_hazard = |collider: Entity| hazard_query.contains(collider);
+        rapier_context.intersections_with_shape(
+            transform.translation,
+            Quat::IDENTITY,
+            &Collider::capsule_y(0.4, 0.4 + CONTACT_MARGIN),
+            QueryFilter::default().exclude_rigid_body(entity).predicate(&is_hazard),
+            |collider| {
+                contact = hazard_query.get(collider).ok();
+                // Keep looking past ones that only hurt, in case there's a
+                // deadly one too
+                contact.is_some_and(|(hazard, _)| hazard.damage.is_some())
+            },
+        );
+
+        let Some((hazard, hazard_transform)) = contact else {
+            continue;
+        };
+
+        match hazard.damage {
+            None => {
+                player.is_dead = true;
+                death_events.send(PlayerDeathEvent {
+                    entity,
+                    position: transform.translation,
+                    cause: hazard.cause,
+                });
+            }
+            Some(damage) if !health.is_invulnerable() => {
+                let side = if transform.translation.x < hazard_transform.translation().x { -1.0 } else { 1.0 };
+                hurt_events.send(PlayerHurtEvent {
+                    entity,
+                    damage,
+                    knockback: Vec3::new(side * KNOCKBACK.x, KNOCKBACK.y, 0.0),
+                    cause: hazard.cause,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// Clears shots still in flight when the player spawns or respawns
+fn clear_projectiles_on_spawn(
+    mut commands: Commands,
+    mut spawn_events: EventReader<PlayerSpawnEvent>,
+    projectile_query: Query<Entity, With<Projectile>>,
+    mut turret_query: Query<&mut Turret>,
+) {
+    if spawn_events.read().count() == 0 {
+        return;
+    }
+
+    for entity in projectile_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for mut turret in turret_query.iter_mut() {
+        turret.cooldown = turret.fire_interval;
+    }
+}
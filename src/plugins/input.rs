@@ -1,4 +1,7 @@
 use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
+use bevy::input::InputSystem;
+use bevy::utils::HashSet;
 use crate::{
     events::*,
     resources::*,
@@ -10,27 +13,104 @@ pub struct InputPlugin;
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
         app
+            .add_plugins(ActionMappingPlugin)
             .add_systems(Update, (
-                handle_debug_input,
+                handle_debug_input.run_if(super::controls_menu_closed),
                 handle_player_input.run_if(in_state(GameState::Playing)),
             ));
     }
 }
 
-fn handle_debug_input(
+/// Turns keyboard and gamepad input into `ActionState` before `Update` runs.
+/// Needs Bevy's input plugin, and nothing else, so it also runs headless.
+pub struct ActionMappingPlugin;
+
+impl Plugin for ActionMappingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<InputConfig>()
+            .init_resource::<GamepadConfig>()
+            .init_resource::<ActionState>()
+            .add_systems(PreUpdate, update_action_state.after(InputSystem));
+    }
+}
+
+pub fn update_action_state(
     keyboard: Res<Input<KeyCode>>,
-    mut config: ResMut<GameConfig>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
     input_config: Res<InputConfig>,
+    gamepad_config: Res<GamepadConfig>,
+    mut actions: ResMut<ActionState>,
+) {
+    let mut pressed: HashSet<InputAction> = InputAction::ALL.into_iter()
+        .filter(|action| keyboard.pressed(input_config.key(*action)))
+        .collect();
+
+    let mut left_stick = Vec2::ZERO;
+    let mut right_stick = Vec2::ZERO;
+    for gamepad in gamepads.iter() {
+        pressed.extend(InputAction::ALL.into_iter().filter(|action| {
+            gamepad_config.button(*action)
+                .is_some_and(|button_type| gamepad_buttons.pressed(GamepadButton::new(gamepad, button_type)))
+        }));
+
+        let axis = |axis_type| {
+            let raw = gamepad_axes.get(GamepadAxis::new(gamepad, axis_type)).unwrap_or(0.0);
+            gamepad_config.stick_value(raw)
+        };
+        left_stick.x += axis(GamepadAxisType::LeftStickX);
+        right_stick += Vec2::new(axis(GamepadAxisType::RightStickX), axis(GamepadAxisType::RightStickY));
+    }
+
+    // A pushed stick also counts as holding the direction, so menus can step with it
+    if left_stick.x < 0.0 {
+        pressed.insert(InputAction::MoveLeft);
+    }
+    if left_stick.x > 0.0 {
+        pressed.insert(InputAction::MoveRight);
+    }
+
+    let digital = |negative, positive| {
+        let mut value = 0.0;
+        if pressed.contains(&negative) {
+            value -= 1.0;
+        }
+        if pressed.contains(&positive) {
+            value += 1.0;
+        }
+        value
+    };
+
+    let movement = if left_stick.x != 0.0 {
+        left_stick.x.clamp(-1.0, 1.0)
+    } else {
+        digital(InputAction::MoveLeft, InputAction::MoveRight)
+    };
+
+    let camera = Vec3::new(
+        digital(InputAction::CameraLeft, InputAction::CameraRight) + right_stick.x,
+        digital(InputAction::CameraDown, InputAction::CameraUp),
+        digital(InputAction::CameraBackward, InputAction::CameraForward) + right_stick.y,
+    ).clamp(Vec3::NEG_ONE, Vec3::ONE);
+
+    actions.update(pressed, movement, camera);
+}
+
+fn handle_debug_input(
+    actions: Res<ActionState>,
+    mut config: ResMut<GameConfig>,
     mut debug_events: EventWriter<DebugEvent>,
 ) {
-    if keyboard.just_pressed(input_config.debug_toggle) {
+    if actions.just_pressed(InputAction::DebugToggle) {
         config.debug_mode = !config.debug_mode;
         debug_events.send(DebugEvent {
             message: format!("Debug mode: {}", config.debug_mode),
         });
     }
 
-    if keyboard.just_pressed(KeyCode::F4) {
+    if actions.just_pressed(InputAction::ToggleColliders) {
         config.show_colliders = !config.show_colliders;
         if config.show_colliders {
             debug_events.send(DebugEvent {
@@ -43,7 +123,7 @@ fn handle_debug_input(
         }
     }
 
-    if keyboard.just_pressed(KeyCode::F5) {
+    if actions.just_pressed(InputAction::TogglePhysicsDebug) {
         config.physics_debug = !config.physics_debug;
         debug_events.send(DebugEvent {
             message: format!("Physics debug: {}", config.physics_debug),
@@ -51,59 +131,82 @@ fn handle_debug_input(
     }
 }
 
+// The events player input is turned into
+#[derive(SystemParam)]
+struct PlayerActionEvents<'w> {
+    moves: EventWriter<'w, PlayerMoveEvent>,
+    jumps: EventWriter<'w, PlayerJumpEvent>,
+    jump_releases: EventWriter<'w, PlayerJumpReleaseEvent>,
+    flips: EventWriter<'w, PlayerFlipEvent>,
+    abilities: EventWriter<'w, PlayerAbilityEvent>,
+    attacks: EventWriter<'w, AttackEvent>,
+}
+
 fn handle_player_input(
-    keyboard: Res<Input<KeyCode>>,
-    input_config: Res<InputConfig>,
-    mut player_events: EventWriter<PlayerMoveEvent>,
-    mut jump_events: EventWriter<PlayerJumpEvent>,
-    mut flip_events: EventWriter<PlayerFlipEvent>,
+    actions: Res<ActionState>,
+    mut events: PlayerActionEvents,
     mut next_state: ResMut<NextState<crate::states::GameState>>,
     player_query: Query<Entity, With<crate::Player>>,
 ) {
-    // Check for restart key (i) - return to character selection
-    if keyboard.just_pressed(KeyCode::I) {
+    // Check for restart key - return to character selection
+    if actions.just_pressed(InputAction::Restart) {
         info!("Restart key pressed - returning to character selection");
         next_state.set(crate::states::GameState::CharacterSelection);
         return;
     }
 
     if let Ok(player_entity) = player_query.get_single() {
-        // Movement input
-        let mut direction = Vec3::ZERO;
+        // Movement input, sent every frame so the player slows down smoothly when idle
+        events.moves.send(PlayerMoveEvent {
+            entity: player_entity,
+            direction: Vec3::new(actions.movement, 0.0, 0.0),
+        });
 
-        if keyboard.pressed(input_config.move_left) {
-            direction.x -= 1.0;
+        // Jump input
+        if actions.just_pressed(InputAction::Jump) {
+            events.jumps.send(PlayerJumpEvent {
+                entity: player_entity,
+            });
         }
-        if keyboard.pressed(input_config.move_right) {
-            direction.x += 1.0;
+        if actions.just_released(InputAction::Jump) {
+            events.jump_releases.send(PlayerJumpReleaseEvent {
+                entity: player_entity,
+            });
         }
 
-        if direction != Vec3::ZERO {
-            player_events.send(PlayerMoveEvent {
+        // Flip input
+        if actions.just_pressed(InputAction::FrontFlip) {
+            events.flips.send(PlayerFlipEvent {
                 entity: player_entity,
-                direction,
+                flip_type: FlipType::Front,
             });
         }
 
-        // Jump input
-        if keyboard.just_pressed(input_config.jump) {
-            jump_events.send(PlayerJumpEvent {
+        if actions.just_pressed(InputAction::DiveRoll) {
+            events.flips.send(PlayerFlipEvent {
                 entity: player_entity,
+                flip_type: FlipType::Dive,
             });
         }
 
-        // Flip input
-        if keyboard.just_pressed(input_config.front_flip) {
-            flip_events.send(PlayerFlipEvent {
+        // Air moves; double jump comes from the jump button
+        if actions.just_pressed(InputAction::AirDash) {
+            events.abilities.send(PlayerAbilityEvent {
                 entity: player_entity,
-                flip_type: FlipType::Front,
+                ability: Ability::AirDash,
             });
         }
 
-        if keyboard.just_pressed(input_config.dive_roll) {
-            flip_events.send(PlayerFlipEvent {
+        if actions.just_pressed(InputAction::GroundPound) {
+            events.abilities.send(PlayerAbilityEvent {
+                entity: player_entity,
+                ability: Ability::GroundPound,
+            });
+        }
+
+        if actions.just_pressed(InputAction::Attack) {
+            events.attacks.send(AttackEvent {
                 entity: player_entity,
-                flip_type: FlipType::Dive,
             });
         }
     }
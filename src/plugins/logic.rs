@@ -0,0 +1,487 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_rapier3d::prelude::*;
+use crate::{
+    components::*,
+    events::*,
+    plugins::combat::Attackable,
+    plugins::physics::{MovingPlatform, Platform},
+    plugins::props::Prop,
+    states::*,
+};
+
+pub struct LogicPlugin;
+
+impl Plugin for LogicPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<LogicChannels>()
+            .add_systems(OnEnter(PlayState::Setup), setup_logic)
+            .add_systems(OnEnter(GameState::CharacterSelection), reset_logic_on_restart)
+            .add_systems(Update, (
+                link_platform_targets.run_if(resource_exists::<PendingPlatformLinks>()),
+                (update_trigger_volumes, update_pressure_plates, handle_switch_hits),
+                update_channels,
+                (operate_doors, operate_platform_targets, update_switch_lamps),
+            ).chain().run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// A numbered wire in the level's logic: inputs on the same channel switch it
+/// on, and every target on it follows
+pub type Channel = u32;
+
+/// Channels with at least one input on this frame
+#[derive(Resource, Default)]
+pub struct LogicChannels {
+    on: HashSet<Channel>,
+}
+
+impl LogicChannels {
+    pub fn is_on(&self, channel: Channel) -> bool {
+        self.on.contains(&channel)
+    }
+}
+
+/// Invisible volume that's on while the player is inside it, or for good
+/// once entered if `once` is set
+#[derive(Component)]
+pub struct TriggerVolume {
+    pub channel: Channel,
+    pub half_extents: Vec3,
+    pub once: bool,
+    pub active: bool,
+}
+
+/// Plate that's on while the player or a pushable crate stands on it
+#[derive(Component)]
+pub struct PressurePlate {
+    pub channel: Channel,
+    /// Position when not pressed
+    pub rest: Vec3,
+    pub pressed: bool,
+}
+
+/// Lever that flips on or off each time an attack hits it
+#[derive(Component)]
+pub struct Switch {
+    pub channel: Channel,
+    pub on: bool,
+}
+
+// The light on top of a switch, lit while it's on
+#[derive(Component)]
+struct SwitchLamp;
+
+/// Slab that slides out of the way while its channel is on
+#[derive(Component)]
+pub struct Door {
+    pub channel: Channel,
+    pub closed: Vec3,
+    /// Position when fully open
+    pub open: Vec3,
+    /// 0 closed to 1 open
+    pub progress: f32,
+}
+
+/// What a channel does to a platform it's wired to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlatformAction {
+    /// `Platform::is_active` follows the channel, so the platform is only
+    /// there while the channel is on
+    Activate,
+    /// The opposite: the platform goes away while the channel is on
+    Deactivate,
+    /// Sets a waiting moving platform off the first time the channel comes on
+    StartMoving,
+}
+
+/// A platform from the level data wired to a channel
+#[derive(Component)]
+pub struct PlatformTarget {
+    pub channel: Channel,
+    pub action: PlatformAction,
+}
+
+// Platform links from the level data, waiting for the platforms to be spawned
+#[derive(Resource)]
+struct PendingPlatformLinks(Vec<(Vec3, Channel, PlatformAction)>);
+
+// Channels used by the level
+const SECRET_STONES: Channel = 1;
+const ALCOVE_DOOR: Channel = 2;
+const FERRY_START: Channel = 3;
+
+const SWITCH_OFF_COLOR: Color = Color::rgb(0.6, 0.15, 0.1);
+const SWITCH_ON_COLOR: Color = Color::rgb(0.2, 0.9, 0.3);
+
+fn setup_logic(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Trigger volumes: centre, half extents, channel, and whether they stay on once entered
+    let trigger_volumes = vec![
+        // Walking up to the ferry past the first crate sets it off
+        (Vec3::new(-3.0, 1.5, 0.0), Vec3::new(0.5, 1.5, 2.5), FERRY_START, true),
+    ];
+
+    // Pressure plates: centre of the plate, and channel
+    let pressure_plates = vec![
+        // Under the carousel; the crate to its left can be pushed on to hold it down
+        (Vec3::new(-13.5, 0.05, 0.0), ALCOVE_DOOR),
+    ];
+
+    // Switches: base of the post, and channel
+    let switches = vec![
+        // Top of the wall climb, overlooking the secret stones
+        (Vec3::new(12.8, 44.25, 0.0), SECRET_STONES),
+    ];
+
+    // Doors: centre when closed, size, how far opening moves it, and channel
+    let doors = vec![
+        // Shuts off the alcove at the left end of the ground, sinking into it when open
+        (Vec3::new(-23.0, 4.0, 0.0), Vec3::new(0.4, 8.0, 5.0), Vec3::new(0.0, -7.9, 0.0), ALCOVE_DOOR),
+    ];
+
+    // Platform links: where the platform is in the level data, the channel
+    // and what it does to the platform
+    let platform_links = vec![
+        // The way into the secret area only appears once the switch is struck
+        (Vec3::new(0.0, 45.0, 0.0), SECRET_STONES, PlatformAction::Activate),
+        (Vec3::new(-6.0, 48.0, 0.0), SECRET_STONES, PlatformAction::Activate),
+        (Vec3::new(6.0, 48.0, 0.0), SECRET_STONES, PlatformAction::Activate),
+        // The ferry, by its first waypoint
+        (Vec3::new(-4.0, 2.5, 0.0), FERRY_START, PlatformAction::StartMoving),
+    ];
+
+    for (centre, half_extents, channel, once) in trigger_volumes {
+        commands.spawn((
+            TransformBundle::from_transform(Transform::from_translation(centre)),
+            Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+            Sensor,
+            TriggerVolume {
+                channel,
+                half_extents,
+                once,
+                active: false,
+            },
+            Name::new("TriggerVolume"),
+        ));
+    }
+
+    let plate_mesh = meshes.add(Mesh::from(shape::Box::new(1.6, 0.1, 1.6)));
+    let plate_material = materials.add(StandardMaterial {
+        base_color: Color::rgb(0.7, 0.55, 0.2),
+        metallic: 0.6,
+        perceptual_roughness: 0.4,
+        ..default()
+    });
+
+    for (centre, channel) in pressure_plates {
+        commands.spawn((
+            PbrBundle {
+                mesh: plate_mesh.clone(),
+                material: plate_material.clone(),
+                transform: Transform::from_translation(centre),
+                ..default()
+            },
+            PressurePlate {
+                channel,
+                rest: centre,
+                pressed: false,
+            },
+            Name::new("PressurePlate"),
+        ));
+    }
+
+    let post_mesh = meshes.add(Mesh::from(shape::Box::new(0.15, 1.2, 0.15)));
+    let post_material = materials.add(StandardMaterial {
+        base_color: Color::rgb(0.35, 0.35, 0.4),
+        metallic: 0.7,
+        ..default()
+    });
+    let lamp_mesh = meshes.add(Mesh::from(shape::UVSphere { radius: 0.2, ..default() }));
+
+    for (base, channel) in switches {
+        commands.spawn((
+            SpatialBundle::from_transform(Transform::from_translation(base + Vec3::Y * 0.6)),
+            // A sensor, so it's struck by attacks without getting in the way
+            Collider::cuboid(0.3, 0.6, 0.5),
+            Sensor,
+            Attackable,
+            Switch { channel, on: false },
+            Name::new("Switch"),
+        ))
+        .with_children(|parent| {
+            parent.spawn(PbrBundle {
+                mesh: post_mesh.clone(),
+                material: post_material.clone(),
+                ..default()
+            });
+            // Each lamp gets its own material so it can light up alone
+            parent.spawn((
+                PbrBundle {
+                    mesh: lamp_mesh.clone(),
+                    material: materials.add(StandardMaterial {
+                        base_color: SWITCH_OFF_COLOR,
+                        ..default()
+                    }),
+                    transform: Transform::from_xyz(0.0, 0.7, 0.0),
+                    ..default()
+                },
+                SwitchLamp,
+            ));
+        });
+    }
+
+    let door_material = materials.add(StandardMaterial {
+        base_color: Color::rgb(0.3, 0.32, 0.38),
+        metallic: 0.8,
+        perceptual_roughness: 0.35,
+        ..default()
+    });
+
+    for (closed, size, open_offset, channel) in doors {
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Mesh::from(shape::Box::new(size.x, size.y, size.z))),
+                material: door_material.clone(),
+                transform: Transform::from_translation(closed),
+                ..default()
+            },
+            RigidBody::KinematicPositionBased,
+            Collider::cuboid(size.x * 0.5, size.y * 0.5, size.z * 0.5),
+            Door {
+                channel,
+                closed,
+                open: closed + open_offset,
+                progress: 0.0,
+            },
+            Name::new("Door"),
+        ));
+    }
+
+    commands.insert_resource(PendingPlatformLinks(platform_links));
+
+    info!("Logic setup complete");
+}
+
+/// Finds the platforms named by position in the level's links once they've
+/// been spawned, and wires them up
+fn link_platform_targets(
+    mut commands: Commands,
+    links: Res<PendingPlatformLinks>,
+    platform_query: Query<(Entity, &Transform), With<Platform>>,
+) {
+    if platform_query.is_empty() {
+        return;
+    }
+
+    for (position, channel, action) in links.0.iter().copied() {
+        let platform = platform_query.iter()
+            .find(|(_, transform)| transform.translation.distance(position) < 0.01);
+        match platform {
+            Some((entity, _)) => {
+                commands.entity(entity).insert(PlatformTarget { channel, action });
+            }
+            None => warn!("No platform at {:?} to wire to channel {}", position, channel),
+        }
+    }
+
+    commands.remove_resource::<PendingPlatformLinks>();
+}
+
+// Platforms driven by a channel. Doors are never platforms, which lets both move in one system
+type WiredPlatform = (With<PlatformTarget>, Without<Door>);
+
+/// Puts every switch, trigger and door back when the player restarts, along
+/// with the platforms they set moving. Like crates, they're left as they are
+/// after a respawn or a pause.
+fn reset_logic_on_restart(
+    mut switch_query: Query<&mut Switch>,
+    mut trigger_query: Query<&mut TriggerVolume>,
+    mut door_query: Query<(&mut Door, &mut Transform)>,
+    mut platform_query: Query<(&mut MovingPlatform, &mut Transform), WiredPlatform>,
+) {
+    for mut switch in switch_query.iter_mut() {
+        switch.on = false;
+    }
+    for mut trigger in trigger_query.iter_mut() {
+        trigger.active = false;
+    }
+    for (mut door, mut transform) in door_query.iter_mut() {
+        door.progress = 0.0;
+        transform.translation = door.closed;
+    }
+    // The logic systems only run while playing, so the channels are worked out
+    // again from the reset inputs before a platform can move
+    for (mut platform, mut transform) in platform_query.iter_mut() {
+        platform.reset();
+        if let Some(start) = platform.start() {
+            transform.translation = start;
+        }
+    }
+}
+
+fn update_trigger_volumes(
+    mut trigger_query: Query<(&Transform, &mut TriggerVolume)>,
+    player_query: Query<(), With<Player>>,
+    rapier_context: Res<RapierContext>,
+) {
+    for (transform, mut trigger) in trigger_query.iter_mut() {
+        if trigger.once && trigger.active {
+            continue;
+        }
+
+        let mut inside = false;
+        let is_player = |collider: Entity| player_query.contains(collider);
+        let half_extents = trigger.half_extents;
+        rapier_context.intersections_with_shape(
+            transform.translation,
+            transform.rotation,
+            &Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+            QueryFilter::default().predicate(&is_player),
+            |_| {
+                inside = true;
+                false
+            },
+        );
+
+        if inside != trigger.active {
+            trigger.active = inside;
+            info!("Trigger on channel {} {}", trigger.channel, if inside { "entered" } else { "left" });
+        }
+    }
+}
+
+// What's heavy enough to hold a pressure plate down
+type PlateWeight = Or<(With<Player>, With<Prop>)>;
+
+/// Presses a plate while the player or a pushable crate is on it, sinking it a little
+fn update_pressure_plates(
+    mut plate_query: Query<(&mut Transform, &mut PressurePlate)>,
+    weight_query: Query<(), PlateWeight>,
+    rapier_context: Res<RapierContext>,
+) {
+    const SINK: f32 = 0.06;
+
+    for (mut transform, mut plate) in plate_query.iter_mut() {
+        let mut pressed = false;
+        let is_weight = |collider: Entity| weight_query.contains(collider);
+        // Just the space above the plate, so things beside it don't count
+        rapier_context.intersections_with_shape(
+            plate.rest + Vec3::Y * 0.3,
+            Quat::IDENTITY,
+            &Collider::cuboid(0.7, 0.25, 0.7),
+            QueryFilter::default().predicate(&is_weight),
+            |_| {
+                pressed = true;
+                false
+            },
+        );
+
+        if pressed != plate.pressed {
+            plate.pressed = pressed;
+            transform.translation = plate.rest - Vec3::Y * if pressed { SINK } else { 0.0 };
+            info!("Pressure plate on channel {} {}", plate.channel, if pressed { "pressed" } else { "released" });
+        }
+    }
+}
+
+fn handle_switch_hits(
+    mut hit_events: EventReader<HitEvent>,
+    mut switch_query: Query<&mut Switch>,
+) {
+    for event in hit_events.read() {
+        if let Ok(mut switch) = switch_query.get_mut(event.target) {
+            switch.on = !switch.on;
+            info!("Switch on channel {} turned {}", switch.channel, if switch.on { "on" } else { "off" });
+        }
+    }
+}
+
+fn update_channels(
+    mut channels: ResMut<LogicChannels>,
+    trigger_query: Query<&TriggerVolume>,
+    plate_query: Query<&PressurePlate>,
+    switch_query: Query<&Switch>,
+) {
+    let on: HashSet<Channel> = trigger_query.iter().filter(|trigger| trigger.active).map(|trigger| trigger.channel)
+        .chain(plate_query.iter().filter(|plate| plate.pressed).map(|plate| plate.channel))
+        .chain(switch_query.iter().filter(|switch| switch.on).map(|switch| switch.channel))
+        .collect();
+
+    if channels.on != on {
+        channels.on = on;
+    }
+}
+
+fn operate_doors(
+    mut door_query: Query<(&mut Door, &mut Transform)>,
+    channels: Res<LogicChannels>,
+    time: Res<Time>,
+) {
+    // Seconds to open or close fully
+    const DOOR_TIME: f32 = 0.6;
+
+    for (mut door, mut transform) in door_query.iter_mut() {
+        let target = if channels.is_on(door.channel) { 1.0 } else { 0.0 };
+        if door.progress == target {
+            continue;
+        }
+
+        let step = time.delta_seconds() / DOOR_TIME;
+        door.progress = if target > door.progress {
+            (door.progress + step).min(target)
+        } else {
+            (door.progress - step).max(target)
+        };
+        transform.translation = door.closed.lerp(door.open, door.progress * door.progress * (3.0 - 2.0 * door.progress));
+    }
+}
+
+fn operate_platform_targets(
+    mut target_query: Query<(&PlatformTarget, &mut Platform, Option<&mut MovingPlatform>)>,
+    channels: Res<LogicChannels>,
+) {
+    for (target, mut platform, moving) in target_query.iter_mut() {
+        let on = channels.is_on(target.channel);
+        match target.action {
+            PlatformAction::Activate | PlatformAction::Deactivate => {
+                let active = on == (target.action == PlatformAction::Activate);
+                // Only written when it changes, since physics reacts to every change
+                if platform.is_active != active {
+                    platform.is_active = active;
+                }
+            }
+            PlatformAction::StartMoving => {
+                if let Some(mut moving) = moving.filter(|moving| on && !moving.started) {
+                    moving.started = true;
+                    info!("Moving platform started by channel {}", target.channel);
+                }
+            }
+        }
+    }
+}
+
+fn update_switch_lamps(
+    switch_query: Query<(&Switch, &Children), Changed<Switch>>,
+    lamp_query: Query<&Handle<StandardMaterial>, With<SwitchLamp>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (switch, children) in switch_query.iter() {
+        for child in children.iter() {
+            let Some(material) = lamp_query.get(*child).ok().and_then(|handle| materials.get_mut(handle)) else {
+                continue;
+            };
+            if switch.on {
+                material.base_color = SWITCH_ON_COLOR;
+                material.emissive = SWITCH_ON_COLOR * 2.0;
+            } else {
+                material.base_color = SWITCH_OFF_COLOR;
+                material.emissive = Color::BLACK;
+            }
+        }
+    }
+}
@@ -5,24 +5,42 @@ pub mod core;
 pub mod player;
 pub mod camera;
 pub mod physics;
+pub mod hazards;
+pub mod checkpoints;
+pub mod enemies;
+pub mod combat;
+pub mod props;
+pub mod logic;
 pub mod rendering;
 pub mod input;
 pub mod audio;
 pub mod debug;
 pub mod character_selection;
 pub mod auth;
+pub mod sync;
+pub mod profiles;
+pub mod controls;
 
 // Re-export plugins
 pub use core::*;
 pub use player::*;
 pub use camera::*;
 pub use physics::*;
+pub use hazards::*;
+pub use checkpoints::*;
+pub use enemies::*;
+pub use combat::*;
+pub use props::*;
+pub use logic::*;
 pub use rendering::*;
 pub use input::*;
 pub use audio::*;
 pub use debug::*;
 pub use character_selection::*;
 pub use auth::*;
+pub use sync::*;
+pub use profiles::*;
+pub use controls::*;
 
 // Main game plugin that orchestrates everything
 pub struct GamePlugin;
@@ -39,12 +57,25 @@ impl Plugin for GamePlugin {
                 crate::events::EventsPlugin,
                 crate::resources::ResourcesPlugin,
                 CorePlugin,
+                ProfilePlugin,
+                ControlsPlugin,
                 AuthPlugin,
+                SyncPlugin,
                 CharacterSelectionPlugin,
                 InputPlugin,
+            ))
+
+            // Add gameplay plugins
+            .add_plugins((
                 PlayerPlugin,
                 CameraPlugin,
                 PhysicsPlugin,
+                HazardsPlugin,
+                CheckpointsPlugin,
+                EnemiesPlugin,
+                CombatPlugin,
+                PropsPlugin,
+                LogicPlugin,
                 RenderingPlugin,
                 AudioPlugin,
                 DebugPlugin,
@@ -7,11 +7,15 @@ use crate::{
     events::*,
 };
 
+// Declared with the components the player's ground state holds
+pub use crate::components::{PlatformType, SurfaceMaterial};
+
 pub struct PhysicsPlugin;
 
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
         app
+            .init_resource::<PlatformRhythm>()
             .add_systems(OnEnter(PlayState::Setup), (
                 setup_platforms,
                 setup_physics_world,
@@ -19,18 +23,33 @@ impl Plugin for PhysicsPlugin {
             ))
             .add_systems(Update, (
                 handle_platform_interactions,
-                handle_trampoline_collisions,
-                handle_trampoline_proximity,
+                handle_bouncy_surfaces,
                 update_physics_debug,
                 handle_coin_collection,
                 animate_coins,
                 setup_coins_delayed,
                 trigger_trampoline_animation,
                 update_trampoline_animation,
+                update_one_way_platforms,
+                move_platforms.after(reset_moving_platforms_on_spawn),
+                reset_moving_platforms_on_spawn,
+                reset_platforms_on_spawn,
+                update_crumbling_platforms.after(reset_platforms_on_spawn),
+                update_timed_platforms.after(reset_platforms_on_spawn),
+                apply_platform_activity,
             ).run_if(in_state(GameState::Playing)));
     }
 }
 
+/// Collision group of the player's colliders
+pub const PLAYER_GROUP: Group = Group::GROUP_1;
+
+/// Groups for the player's colliders and its own scene queries, so they skip passable platforms
+pub const PLAYER_COLLISION_GROUPS: CollisionGroups = CollisionGroups::new(PLAYER_GROUP, Group::ALL);
+
+// A one-way platform the player is currently below
+const PASSABLE_COLLISION_GROUPS: CollisionGroups = CollisionGroups::new(Group::ALL, Group::ALL.difference(PLAYER_GROUP));
+
 fn setup_platforms(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -140,6 +159,7 @@ fn setup_platforms(
             has_coin: false,
             has_lights: true, // Trampolines have lights
         },
+        SurfaceMaterial::Bouncy { strength: 12.0 },
         TrampolineAnimation {
             original_transform: trampoline_transform,
             ..default()
@@ -159,7 +179,6 @@ fn setup_platforms(
                 coefficient: 1.2, // Super bouncy!
                 combine_rule: CoefficientCombineRule::Max,
             },
-            TrampolineTopSurface, // Mark this as the bouncy surface
         ));
 
         // Side/bottom collider
@@ -235,7 +254,9 @@ fn setup_platforms(
         (Vec3::new(18.0, 32.0, 0.0), Vec3::new(4.0, 0.5, 4.0), PlatformType::Floating),
 
         // === SECTION 5: VERTICAL WALL CLIMB (Hard) ===
-        // Alternating platforms that require wall-jump-like movement
+        // Alternating platforms between two walls to slide down and jump off
+        (Vec3::new(5.5, 40.0, 0.0), Vec3::new(1.0, 10.0, 3.0), PlatformType::Wall),
+        (Vec3::new(19.5, 40.0, 0.0), Vec3::new(1.0, 10.0, 3.0), PlatformType::Wall),
         (Vec3::new(12.0, 34.0, 0.0), Vec3::new(2.5, 0.5, 2.5), PlatformType::Small),
         (Vec3::new(16.0, 36.0, 0.0), Vec3::new(2.5, 0.5, 2.5), PlatformType::Small),
         (Vec3::new(10.0, 38.0, 0.0), Vec3::new(2.5, 0.5, 2.5), PlatformType::Small),
@@ -295,10 +316,81 @@ fn setup_platforms(
         (Vec3::new(20.0, 25.0, 0.0), Vec3::new(3.0, 0.5, 3.0), PlatformType::Small),
         (Vec3::new(25.0, 35.0, 0.0), Vec3::new(3.0, 0.5, 3.0), PlatformType::Small),
         (Vec3::new(30.0, 45.0, 0.0), Vec3::new(3.0, 0.5, 3.0), PlatformType::Small),
+
+        // === SIDE PATH: FALLING FLOORS ===
+        // Off the carousel: stones that give way, then floors that blink in turn
+        (Vec3::new(-25.0, 9.0, 0.0), Vec3::new(2.5, 0.5, 2.5), PlatformType::Crumbling),
+        (Vec3::new(-29.0, 10.5, 0.0), Vec3::new(2.5, 0.5, 2.5), PlatformType::Crumbling),
+        (Vec3::new(-33.0, 12.0, 0.0), Vec3::new(2.5, 0.5, 2.5), PlatformType::Crumbling),
+        (Vec3::new(-29.0, 14.5, 0.0), Vec3::new(3.0, 0.5, 3.0), PlatformType::Timed),
+        (Vec3::new(-25.0, 16.0, 0.0), Vec3::new(3.0, 0.5, 3.0), PlatformType::Timed),
+        (Vec3::new(-21.0, 17.5, 0.0), Vec3::new(3.0, 0.5, 3.0), PlatformType::Timed),
+    ];
+
+    // Platforms with a special surface, as a run of floor past the right end of the ground
+    let surface_configs = vec![
+        (Vec3::new(29.5, -0.25, 0.0), Vec3::new(8.0, 0.5, 5.0), PlatformType::Ground, SurfaceMaterial::Ice),
+        // Pushes back towards the start
+        (Vec3::new(37.5, -0.25, 0.0), Vec3::new(7.0, 0.5, 5.0), PlatformType::Ground, SurfaceMaterial::Conveyor { speed: -3.0 }),
+        (Vec3::new(44.5, -0.25, 0.0), Vec3::new(6.0, 0.5, 5.0), PlatformType::Ground, SurfaceMaterial::Sticky),
+        // Launches up towards the right path of Section 3
+        (Vec3::new(50.0, -0.25, 0.0), Vec3::new(3.0, 0.5, 3.0), PlatformType::Floating, SurfaceMaterial::Bouncy { strength: 16.0 }),
+    ];
+
+    // Moving platforms: size and the path each one follows
+    let moving_platform_configs = vec![
+        // Ferry near the start, to practise riding; set off by the trigger
+        // volume past the first crate, or by stepping on
+        (Vec3::new(3.0, 0.5, 3.0), PlatformPath {
+            waypoints: vec![Vec3::new(-4.0, 2.5, 0.0), Vec3::new(-9.0, 2.5, 0.0)],
+            segment_time: 2.5,
+            pause_time: 0.8,
+            mode: PathMode::PingPong,
+            easing: Easing::Smooth,
+            triggered: true,
+        }),
+        // Carousel going round past the ferry's far end
+        (Vec3::new(3.0, 0.5, 3.0), PlatformPath {
+            waypoints: vec![
+                Vec3::new(-14.0, 3.0, 0.0),
+                Vec3::new(-14.0, 7.0, 0.0),
+                Vec3::new(-20.0, 7.0, 0.0),
+                Vec3::new(-20.0, 3.0, 0.0),
+            ],
+            segment_time: 2.0,
+            pause_time: 0.3,
+            mode: PathMode::Loop,
+            easing: Easing::Linear,
+            triggered: false,
+        }),
+        // Lift from the end of Section 4 up to the Gauntlet, waiting for the player to step on
+        (Vec3::new(2.0, 0.5, 2.0), PlatformPath {
+            waypoints: vec![Vec3::new(22.0, 32.5, 0.0), Vec3::new(22.0, 45.0, 0.0)],
+            segment_time: 5.0,
+            pause_time: 1.5,
+            mode: PathMode::PingPong,
+            easing: Easing::Smooth,
+            triggered: true,
+        }),
     ];
 
-    for (i, (position, size, platform_type)) in platform_configs.iter().enumerate() {
-        let color = match platform_type {
+    // Special surfaces and moving platforms are built like the rest, moving
+    // ones starting at their first waypoint
+    let platform_configs = platform_configs
+        .into_iter()
+        .map(|(position, size, platform_type)| (position, size, platform_type, SurfaceMaterial::Normal, None))
+        .chain(surface_configs.into_iter().map(|(position, size, platform_type, surface)| {
+            (position, size, platform_type, surface, None)
+        }))
+        .chain(moving_platform_configs.into_iter().map(|(size, path)| {
+            (path.waypoints[0], size, PlatformType::Moving, SurfaceMaterial::Normal, Some(path))
+        }));
+
+    // Neighbouring timed platforms take turns, half a beat apart
+    let mut timed_count = 0;
+
+    for (i, (position, size, platform_type, surface, path)) in platform_configs.enumerate() {
+        let color = surface.color().unwrap_or(match platform_type {
             PlatformType::Ground => Color::rgb(0.5, 0.5, 0.5),
             PlatformType::Floating => Color::rgb(0.5, 0.5, 0.5),
             PlatformType::Small => Color::rgb(0.6, 0.6, 0.6),
@@ -306,7 +398,10 @@ fn setup_platforms(
             PlatformType::Bridge => Color::rgb(0.7, 0.7, 0.7),
             PlatformType::Moving => Color::rgb(0.8, 0.4, 0.4),
             PlatformType::Trampoline => Color::rgb(0.2, 0.8, 0.2),
-        };
+            PlatformType::Wall => Color::rgb(0.45, 0.4, 0.55),
+            PlatformType::Crumbling => Color::rgb(0.65, 0.5, 0.35),
+            PlatformType::Timed => Color::rgb(0.3, 0.6, 0.7),
+        });
 
         // Determine if this platform should have lights
         let has_lights = match platform_type {
@@ -342,45 +437,83 @@ fn setup_platforms(
             })
         };
 
-        let platform_entity = commands.spawn((
+        let mut platform = commands.spawn((
             PbrBundle {
                 mesh: meshes.add(Mesh::from(RoundedBox {
-                    size: *size,
+                    size,
                     radius: 0.2,
                     subdivisions: 6,
                     options: BoxMeshOptions::DEFAULT,
                 })),
                 material,
-                transform: Transform::from_translation(*position),
+                transform: Transform::from_translation(position),
                 ..default()
             },
             RigidBody::Fixed,
             // No collider on the main entity
             Platform {
-                platform_type: platform_type.clone(),
+                platform_type,
                 is_active: true,
                 has_coin: false,
                 has_lights,
             },
+            surface,
             Name::new(format!("Platform_{}", i)),
-        ))
-        .with_children(|parent| {
+        ));
+        if let Some(path) = path {
+            platform.insert((RigidBody::KinematicPositionBased, MovingPlatform::new(path)));
+        }
+        match platform_type {
+            PlatformType::Crumbling => {
+                platform.insert((CrumblingPlatform::default(), PlatformWarning::new(position)));
+            }
+            PlatformType::Timed => {
+                platform.insert((TimedPlatform::new(timed_count as f32 * 0.5), PlatformWarning::new(position)));
+                timed_count += 1;
+            }
+            _ => {}
+        }
+
+        platform.with_children(|parent| {
+            if matches!(platform_type, PlatformType::Wall) {
+                // One slippery collider; wall sliding sets its own fall speed
+                parent.spawn((
+                    TransformBundle::default(),
+                    Collider::round_cuboid(size.x * 0.5 - 0.05, size.y * 0.5 - 0.05, size.z * 0.5 - 0.05, 0.05),
+                    Friction {
+                        coefficient: 0.0,
+                        combine_rule: CoefficientCombineRule::Min,
+                    },
+                    Restitution {
+                        coefficient: 0.0,
+                        combine_rule: CoefficientCombineRule::Min,
+                    },
+                    WallSurface,
+                ));
+                return;
+            }
+
+            // Bridges can be jumped up through from below
+            let top_height = size.y * 0.4 + 0.05;
+            let one_way = matches!(platform_type, PlatformType::Bridge);
+
             // Top surface with friction for walking
-            parent.spawn((
+            let mut top = parent.spawn((
                 TransformBundle::from_transform(Transform::from_xyz(0.0, size.y * 0.3, 0.0)),
                 Collider::round_cuboid(size.x * 0.5, size.y * 0.1, size.z * 0.5, 0.05),
-                Friction {
-                    coefficient: 0.8,
-                    combine_rule: CoefficientCombineRule::Max,
-                },
+                surface.friction(),
                 Restitution {
                     coefficient: 0.0,
                     combine_rule: CoefficientCombineRule::Min,
                 },
+                LedgeSurface,
             ));
+            if one_way {
+                top.insert((OneWayPlatform { top: top_height - size.y * 0.3 }, CollisionGroups::default()));
+            }
 
-            // Side/bottom collider without friction
-            parent.spawn((
+            // Side/bottom collider without friction, which the player can also wall jump off
+            let mut sides = parent.spawn((
                 TransformBundle::from_transform(Transform::from_xyz(0.0, -size.y * 0.2, 0.0)),
                 Collider::round_cuboid(size.x * 0.5, size.y * 0.5, size.z * 0.5, 0.1),
                 Friction {
@@ -391,7 +524,11 @@ fn setup_platforms(
                     coefficient: 0.0,
                     combine_rule: CoefficientCombineRule::Min,
                 },
+                WallSurface,
             ));
+            if one_way {
+                sides.insert((OneWayPlatform { top: top_height + size.y * 0.2 }, CollisionGroups::default()));
+            }
 
             // Add point light if platform has lights
             if has_lights {
@@ -441,80 +578,6 @@ fn setup_physics_world(mut commands: Commands) {
     info!("Physics world configured");
 }
 
-fn setup_coins(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    mut platform_query: Query<(Entity, &mut Platform, &Transform)>,
-) {
-    // Define which platforms should have coins (using indices for simplicity)
-    let coin_platform_indices = vec![
-        2, 5, 8, 12, 15, 18, 22, 25, 28, 32, 35, 38, 42, 45, 48, 52, 55, 58, 62, 65
-    ];
-
-    // Gold coin material
-    let coin_material = materials.add(StandardMaterial {
-        base_color: Color::rgb(1.0, 0.8, 0.0), // Gold color
-        metallic: 0.9,
-        perceptual_roughness: 0.1,
-        reflectance: 0.8,
-        emissive: Color::rgb(0.2, 0.15, 0.0), // Slight glow
-        ..default()
-    });
-
-    // Coin mesh - cylinder to look like a coin
-    let coin_mesh = meshes.add(Mesh::from(shape::Cylinder {
-        radius: 0.3,
-        height: 0.1,
-        resolution: 16,
-        segments: 1,
-    }));
-
-    let platform_count = platform_query.iter().count();
-    info!("Found {} platforms for coin setup", platform_count);
-
-    let mut platform_index = 0;
-    for (platform_entity, mut platform, platform_transform) in platform_query.iter_mut() {
-        // Skip ground platform (index 0)
-        if platform_index == 0 {
-            platform_index += 1;
-            continue;
-        }
-
-        // Check if this platform should have a coin
-        if coin_platform_indices.contains(&platform_index) {
-            platform.has_coin = true;
-
-                        // Spawn coin above the platform
-            let coin_base_position = platform_transform.translation + Vec3::new(0.0, 1.5, 0.0);
-
-            let _coin_entity = commands.spawn((
-                PbrBundle {
-                    mesh: coin_mesh.clone(),
-                    material: coin_material.clone(),
-                    transform: Transform::from_translation(coin_base_position)
-                        .with_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
-                    ..default()
-                },
-                RigidBody::KinematicPositionBased,
-                Collider::cylinder(0.05, 0.3), // Thin collider for coin
-                Coin {
-                    platform_entity: Some(platform_entity),
-                    float_height: coin_base_position.y, // Set the base floating height
-                    ..default()
-                },
-                Name::new(format!("Coin_{}", platform_index)),
-            )).id();
-
-            info!("Spawned coin on platform {}", platform_index);
-        }
-
-        platform_index += 1;
-    }
-
-    info!("Coins setup complete");
-}
-
 fn setup_coins_delayed(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -558,10 +621,9 @@ fn setup_coins_delayed(
     }));
 
     let mut coins_spawned = 0;
-    let mut platform_index = 0;
-    for (platform_entity, mut platform, platform_transform) in platform_query.iter_mut() {
-        // Check if this platform should have a coin
-        if coin_platform_indices.contains(&platform_index) {
+    for (platform_index, (platform_entity, mut platform, platform_transform)) in platform_query.iter_mut().enumerate() {
+        // Check if this platform should have a coin; walls have nowhere to put one and moving platforms would leave it behind
+        if coin_platform_indices.contains(&platform_index) && !matches!(platform.platform_type, PlatformType::Wall | PlatformType::Moving) {
             platform.has_coin = true;
 
             // Spawn coin above the platform
@@ -605,8 +667,6 @@ fn setup_coins_delayed(
             coins_spawned += 1;
             info!("Spawned coin on platform {}", platform_index);
         }
-
-        platform_index += 1;
     }
 
     info!("Coins setup complete! Spawned {} coins", coins_spawned);
@@ -650,10 +710,9 @@ fn setup_coins_immediately(
     }));
 
     let mut coins_spawned = 0;
-    let mut platform_index = 0;
-    for (platform_entity, mut platform, platform_transform) in platform_query.iter_mut() {
-        // Check if this platform should have a coin
-        if coin_platform_indices.contains(&platform_index) {
+    for (platform_index, (platform_entity, mut platform, platform_transform)) in platform_query.iter_mut().enumerate() {
+        // Check if this platform should have a coin; walls have nowhere to put one and moving platforms would leave it behind
+        if coin_platform_indices.contains(&platform_index) && !matches!(platform.platform_type, PlatformType::Wall | PlatformType::Moving) {
             platform.has_coin = true;
 
             // Spawn coin above the platform
@@ -697,8 +756,6 @@ fn setup_coins_immediately(
             coins_spawned += 1;
             info!("Spawned coin on platform {}", platform_index);
         }
-
-        platform_index += 1;
     }
 
     info!("Coins setup complete! Spawned {} coins", coins_spawned);
@@ -734,7 +791,7 @@ fn animate_coins(
 fn handle_coin_collection(
     mut commands: Commands,
     coin_query: Query<(Entity, &Transform, &Coin)>,
-    player_query: Query<(Entity, &Transform), (With<Player>, Without<Coin>)>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
     mut stats: ResMut<crate::resources::GameStats>,
 ) {
     // Check for player-coin collisions
@@ -762,7 +819,7 @@ fn handle_coin_collection(
 
 fn handle_platform_interactions(
     platform_query: Query<(Entity, &Platform, &Transform), With<Platform>>,
-    player_query: Query<(Entity, &Transform, &Player), (With<Player>, Without<Platform>)>,
+    player_query: Query<(Entity, &Transform, &Player)>,
     mut stats: ResMut<crate::resources::GameStats>,
 ) {
     if let Ok((_player_entity, player_transform, _player)) = player_query.get_single() {
@@ -780,57 +837,6 @@ fn handle_platform_interactions(
     }
 }
 
-fn handle_trampoline_collisions(
-    mut collision_events: EventReader<CollisionEvent>,
-    player_query: Query<Entity, With<Player>>,
-    trampoline_query: Query<Entity, With<TrampolineTopSurface>>,
-    mut trampoline_events: EventWriter<TrampolineBounceEvent>,
-    mut last_trampoline_time: Local<f32>,
-    time: Res<Time>,
-) {
-    let Ok(player_entity) = player_query.get_single() else {
-        return;
-    };
-
-    for collision_event in collision_events.read() {
-        let (entity1, entity2) = match collision_event {
-            CollisionEvent::Started(e1, e2, _) => {
-                info!("Collision started between {:?} and {:?}", e1, e2);
-                (e1, e2)
-            },
-            CollisionEvent::Stopped(_, _, _) => continue,
-        };
-
-        // Check if collision is between player and trampoline top surface
-        let is_player_trampoline_collision =
-            (entity1 == &player_entity && trampoline_query.get(*entity2).is_ok()) ||
-            (entity2 == &player_entity && trampoline_query.get(*entity1).is_ok());
-
-        if is_player_trampoline_collision {
-            let current_time = time.elapsed_seconds();
-            let time_since_last_bounce = current_time - *last_trampoline_time;
-
-            info!("Player collision with trampoline top surface detected! Time since last bounce: {}", time_since_last_bounce);
-
-            // Prevent spam bouncing with cooldown
-            if time_since_last_bounce > 0.2 {
-                let trampoline_entity = if entity1 == &player_entity { *entity2 } else { *entity1 };
-
-                trampoline_events.send(TrampolineBounceEvent {
-                    player_entity,
-                    bounce_force: 12.0, // Increased force
-                    platform_entity: trampoline_entity,
-                });
-
-                *last_trampoline_time = current_time;
-                info!("Player bounced on trampoline top surface! Boost applied with force 15.0");
-            } else {
-                info!("Trampoline bounce blocked by cooldown");
-            }
-        }
-    }
-}
-
 fn update_physics_debug(
     config: Res<crate::resources::GameConfig>,
 ) {
@@ -855,15 +861,247 @@ pub struct Platform {
     pub has_lights: bool,
 }
 
+// Route of a `PlatformType::Moving` platform, declared with the level data
 #[derive(Clone, Debug)]
-pub enum PlatformType {
-    Ground,
-    Floating,
-    Small,
-    SteppingStone,
-    Bridge,
-    Moving,
-    Trampoline,
+pub struct PlatformPath {
+    /// Positions the platform travels between; the first is where it spawns
+    pub waypoints: Vec<Vec3>,
+    /// Seconds to travel from one waypoint to the next
+    pub segment_time: f32,
+    /// Seconds to wait at each waypoint
+    pub pause_time: f32,
+    pub mode: PathMode,
+    pub easing: Easing,
+    /// Stay put until the player first stands on the platform
+    pub triggered: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathMode {
+    /// Back and forth along the waypoints
+    PingPong,
+    /// From the last waypoint straight back to the first
+    Loop,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    /// Eases in and out of every waypoint
+    Smooth,
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::Smooth => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+// Moving platform progress along its path
+#[derive(Component)]
+pub struct MovingPlatform {
+    pub path: PlatformPath,
+    pub started: bool,
+    /// Waypoint the current segment starts from
+    pub segment: usize,
+    /// Travelling towards later waypoints; ping-pong paths flip this at the ends
+    pub forward: bool,
+    /// Time spent on the current segment, including the pause
+    pub elapsed: f32,
+    /// How far the platform moved this frame, which a rider moves along with it
+    pub delta: Vec3,
+    pub velocity: Vec3,
+}
+
+impl MovingPlatform {
+    pub fn new(path: PlatformPath) -> Self {
+        Self {
+            started: !path.triggered,
+            path,
+            segment: 0,
+            forward: true,
+            elapsed: 0.0,
+            delta: Vec3::ZERO,
+            velocity: Vec3::ZERO,
+        }
+    }
+
+    /// Back to the start of the path, waiting for the player again if it's triggered
+    pub fn reset(&mut self) {
+        *self = Self::new(self.path.clone());
+    }
+
+    /// Where the platform spawns
+    pub fn start(&self) -> Option<Vec3> {
+        self.path.waypoints.first().copied()
+    }
+
+    fn next_waypoint(&self) -> usize {
+        let count = self.path.waypoints.len();
+        match (self.path.mode, self.forward) {
+            (PathMode::Loop, _) => (self.segment + 1) % count,
+            (PathMode::PingPong, true) => self.segment + 1,
+            (PathMode::PingPong, false) => self.segment - 1,
+        }
+    }
+
+    /// Moves on to the next segment once the platform reaches its waypoint
+    fn advance(&mut self) {
+        let count = self.path.waypoints.len();
+        self.segment = self.next_waypoint();
+        if self.path.mode == PathMode::PingPong && (self.segment == 0 || self.segment == count - 1) {
+            self.forward = self.segment == 0;
+        }
+    }
+
+    /// Where the platform should be after the current `elapsed`
+    fn position(&self) -> Vec3 {
+        let from = self.path.waypoints[self.segment];
+        let to = self.path.waypoints[self.next_waypoint()];
+        let travel = (self.elapsed - self.path.pause_time).max(0.0) / self.path.segment_time.max(0.01);
+        from.lerp(to, self.path.easing.apply(travel))
+    }
+}
+
+// Platform that falls a moment after the player lands on it
+#[derive(Component)]
+pub struct CrumblingPlatform {
+    pub state: CrumbleState,
+    /// Time spent in the current state
+    pub timer: f32,
+    /// Seconds between the player landing and the platform falling
+    pub crumble_delay: f32,
+    /// Seconds a fallen platform stays gone
+    pub respawn_delay: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrumbleState {
+    Solid,
+    /// Stood on and about to fall
+    Crumbling,
+    Fallen,
+}
+
+impl Default for CrumblingPlatform {
+    fn default() -> Self {
+        Self {
+            state: CrumbleState::Solid,
+            timer: 0.0,
+            crumble_delay: 0.8,
+            respawn_delay: 3.0,
+        }
+    }
+}
+
+impl CrumblingPlatform {
+    /// Back in place and waiting to be stood on
+    pub fn reset(&mut self) {
+        self.state = CrumbleState::Solid;
+        self.timer = 0.0;
+    }
+
+    /// Moves the platform on by `delta` seconds. Returns whether it should be
+    /// solid whenever that changes, so the caller can add or take away its collider.
+    pub fn advance(&mut self, delta: f32, stood_on: bool) -> Option<bool> {
+        self.timer += delta;
+
+        let (next, solid) = match self.state {
+            CrumbleState::Solid if stood_on => (CrumbleState::Crumbling, None),
+            CrumbleState::Crumbling if self.timer >= self.crumble_delay => (CrumbleState::Fallen, Some(false)),
+            CrumbleState::Fallen if self.timer >= self.respawn_delay => (CrumbleState::Solid, Some(true)),
+            _ => return None,
+        };
+        self.state = next;
+        self.timer = 0.0;
+        solid
+    }
+
+    pub fn is_shaking(&self) -> bool {
+        self.state == CrumbleState::Crumbling
+    }
+}
+
+// Platform that is only there for part of every `PlatformRhythm` beat
+#[derive(Component)]
+pub struct TimedPlatform {
+    /// Fraction of the beat this platform runs behind the rhythm
+    pub offset: f32,
+    pub solid: bool,
+}
+
+impl TimedPlatform {
+    pub fn new(offset: f32) -> Self {
+        Self { offset, solid: true }
+    }
+
+    pub fn reset(&mut self) {
+        self.solid = true;
+    }
+}
+
+/// Clock the timed platforms blink to, restarted whenever the player spawns
+#[derive(Resource)]
+pub struct PlatformRhythm {
+    pub elapsed: f32,
+    /// Seconds in one beat
+    pub period: f32,
+    /// Seconds of each beat a timed platform is solid
+    pub solid_time: f32,
+    /// Seconds of warning before a timed platform disappears
+    pub warning_time: f32,
+}
+
+impl Default for PlatformRhythm {
+    fn default() -> Self {
+        Self {
+            elapsed: 0.0,
+            period: 4.0,
+            solid_time: 2.5,
+            warning_time: 0.8,
+        }
+    }
+}
+
+impl PlatformRhythm {
+    /// Seconds into the current beat for a platform `offset` beats behind
+    fn beat(&self, offset: f32) -> f32 {
+        (self.elapsed - offset * self.period).rem_euclid(self.period)
+    }
+
+    pub fn is_solid(&self, offset: f32) -> bool {
+        self.beat(offset) < self.solid_time
+    }
+
+    /// Solid, but about to disappear
+    pub fn is_warning(&self, offset: f32) -> bool {
+        let beat = self.beat(offset);
+        beat < self.solid_time && beat >= self.solid_time - self.warning_time
+    }
+}
+
+// Shake and flicker shown before a platform disappears
+#[derive(Component)]
+pub struct PlatformWarning {
+    /// Where the platform sits when it isn't shaking
+    pub rest: Vec3,
+    /// Emissive colour to restore once the warning ends, read from the material on first use
+    pub base_emissive: Option<Color>,
+    pub active: bool,
+}
+
+impl PlatformWarning {
+    pub fn new(rest: Vec3) -> Self {
+        Self {
+            rest,
+            base_emissive: None,
+            active: false,
+        }
+    }
 }
 
 // Coin component
@@ -910,45 +1148,31 @@ impl Default for TrampolineAnimation {
     }
 }
 
-fn handle_trampoline_proximity(
-    platform_query: Query<(Entity, &Platform, &Transform), With<Platform>>,
-    player_query: Query<(Entity, &Transform, &Player, &Velocity), (With<Player>, Without<Platform>)>,
+/// Bounces the player off bouncy surfaces they land on
+fn handle_bouncy_surfaces(
+    player_query: Query<(Entity, &GroundState), With<Player>>,
     mut trampoline_events: EventWriter<TrampolineBounceEvent>,
-    mut last_proximity_bounce_time: Local<f32>,
+    mut last_bounce_time: Local<f32>,
     time: Res<Time>,
 ) {
-    let Ok((player_entity, player_transform, player, player_velocity)) = player_query.get_single() else {
+    let Ok((player_entity, ground)) = player_query.get_single() else {
+        return;
+    };
+    let (Some(platform_entity), SurfaceMaterial::Bouncy { strength }) = (ground.entity, ground.material) else {
         return;
     };
 
-    for (platform_entity, platform, platform_transform) in platform_query.iter() {
-        if !matches!(platform.platform_type, PlatformType::Trampoline) {
-            continue;
-        }
-
-        let distance = player_transform.translation.distance(platform_transform.translation);
-        let height_diff = player_transform.translation.y - platform_transform.translation.y;
-
-        // Check if player is above and close to the trampoline
-        if distance < 2.5 && height_diff > 0.5 && height_diff < 1.5 && player.is_grounded {
-            let current_time = time.elapsed_seconds();
-            let time_since_last_bounce = current_time - *last_proximity_bounce_time;
-
-            // Check if player is moving downward (just landed)
-            if player_velocity.linvel.y < 1.0 && time_since_last_bounce > 0.3 {
-                info!("Proximity-based trampoline bounce detected! Distance: {}, Height diff: {}, Velocity Y: {}",
-                      distance, height_diff, player_velocity.linvel.y);
-
-                trampoline_events.send(TrampolineBounceEvent {
-                    player_entity,
-                    bounce_force: 12.0,
-                    platform_entity,
-                });
+    // Still touching the surface just after a bounce shouldn't bounce again
+    let current_time = time.elapsed_seconds();
+    if current_time - *last_bounce_time > 0.3 {
+        trampoline_events.send(TrampolineBounceEvent {
+            player_entity,
+            bounce_force: strength,
+            platform_entity,
+        });
 
-                *last_proximity_bounce_time = current_time;
-                info!("Proximity trampoline bounce applied with force 15.0");
-            }
-        }
+        *last_bounce_time = current_time;
+        info!("Player bounced off {:?} with force {}", platform_entity, strength);
     }
 }
 
@@ -1024,3 +1248,211 @@ fn update_trampoline_animation(
     }
 }
 
+/// Makes each one-way platform solid only while the player is standing above it
+fn update_one_way_platforms(
+    player_query: Query<&Transform, With<Player>>,
+    mut platform_query: Query<(&GlobalTransform, &OneWayPlatform, &mut CollisionGroups)>,
+) {
+    // Capsule half height plus radius
+    const FEET_OFFSET: f32 = 0.8;
+    // Resting contact sits a little into the surface
+    const TOLERANCE: f32 = 0.1;
+
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let feet = player_transform.translation.y - FEET_OFFSET;
+
+    for (global_transform, one_way, mut groups) in platform_query.iter_mut() {
+        let solid = feet >= global_transform.translation().y + one_way.top - TOLERANCE;
+        let wanted = if solid { CollisionGroups::default() } else { PASSABLE_COLLISION_GROUPS };
+        if *groups != wanted {
+            *groups = wanted;
+        }
+    }
+}
+
+/// Steps every moving platform along its path
+pub fn move_platforms(
+    mut platform_query: Query<(Entity, &mut Transform, &mut MovingPlatform)>,
+    player_query: Query<&GroundState, With<Player>>,
+    time: Res<Time>,
+) {
+    let delta_seconds = time.delta_seconds();
+    let standing_on = player_query.get_single().ok().and_then(|ground| ground.entity);
+
+    for (entity, mut transform, mut platform) in platform_query.iter_mut() {
+        platform.delta = Vec3::ZERO;
+        platform.velocity = Vec3::ZERO;
+
+        if !platform.started {
+            if standing_on != Some(entity) {
+                continue;
+            }
+            platform.started = true;
+            info!("Moving platform {:?} triggered", entity);
+        }
+        if platform.path.waypoints.len() < 2 || delta_seconds == 0.0 {
+            continue;
+        }
+
+        platform.elapsed += delta_seconds;
+        let segment_length = platform.path.pause_time + platform.path.segment_time.max(0.01);
+        while platform.elapsed >= segment_length {
+            platform.elapsed -= segment_length;
+            platform.advance();
+        }
+
+        let position = platform.position();
+        platform.delta = position - transform.translation;
+        platform.velocity = platform.delta / delta_seconds;
+        transform.translation = position;
+    }
+}
+
+/// Sends every moving platform back to its first waypoint when the player
+/// spawns or respawns, so a triggered platform waits for the player again
+/// instead of being left wherever it stopped
+fn reset_moving_platforms_on_spawn(
+    mut spawn_events: EventReader<PlayerSpawnEvent>,
+    mut platform_query: Query<(&mut Transform, &mut MovingPlatform)>,
+) {
+    if spawn_events.read().count() == 0 {
+        return;
+    }
+
+    for (mut transform, mut platform) in platform_query.iter_mut() {
+        platform.reset();
+        if let Some(start) = platform.start() {
+            transform.translation = start;
+        }
+    }
+}
+
+/// Starts every crumbling and timed platform over when the player spawns or respawns
+fn reset_platforms_on_spawn(
+    mut spawn_events: EventReader<PlayerSpawnEvent>,
+    mut commands: Commands,
+    mut rhythm: ResMut<PlatformRhythm>,
+    mut platform_query: Query<(Entity, &mut Transform, &Handle<StandardMaterial>, &mut PlatformWarning)>,
+    mut crumbling_query: Query<&mut CrumblingPlatform>,
+    mut timed_query: Query<&mut TimedPlatform>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if spawn_events.read().count() == 0 {
+        return;
+    }
+
+    rhythm.elapsed = 0.0;
+    for mut crumbling in crumbling_query.iter_mut() {
+        crumbling.reset();
+    }
+    for mut timed in timed_query.iter_mut() {
+        timed.reset();
+    }
+    for (entity, mut transform, material, mut warning) in platform_query.iter_mut() {
+        set_platform_solid(&mut commands, entity, true);
+        show_warning(&mut transform, &mut warning, materials.get_mut(material), false, 0.0);
+    }
+}
+
+/// Crumbles platforms the player stands on and brings fallen ones back
+fn update_crumbling_platforms(
+    mut commands: Commands,
+    mut platform_query: Query<(Entity, &mut Transform, &Handle<StandardMaterial>, &mut CrumblingPlatform, &mut PlatformWarning)>,
+    player_query: Query<&GroundState, With<Player>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    let standing_on = player_query.get_single().ok().and_then(|ground| ground.entity);
+
+    for (entity, mut transform, material, mut crumbling, mut warning) in platform_query.iter_mut() {
+        if let Some(solid) = crumbling.advance(time.delta_seconds(), standing_on == Some(entity)) {
+            set_platform_solid(&mut commands, entity, solid);
+            if !solid {
+                info!("Platform {:?} crumbled", entity);
+            }
+        }
+
+        let shaking = crumbling.is_shaking();
+        if shaking || warning.active {
+            show_warning(&mut transform, &mut warning, materials.get_mut(material), shaking, time.elapsed_seconds());
+        }
+    }
+}
+
+/// Blinks timed platforms in and out with the shared rhythm
+fn update_timed_platforms(
+    mut commands: Commands,
+    mut platform_query: Query<(Entity, &mut Transform, &Handle<StandardMaterial>, &mut TimedPlatform, &mut PlatformWarning)>,
+    mut rhythm: ResMut<PlatformRhythm>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    rhythm.elapsed += time.delta_seconds();
+
+    for (entity, mut transform, material, mut timed, mut warning) in platform_query.iter_mut() {
+        let solid = rhythm.is_solid(timed.offset);
+        if solid != timed.solid {
+            timed.solid = solid;
+            set_platform_solid(&mut commands, entity, solid);
+        }
+
+        let warn = rhythm.is_warning(timed.offset);
+        if warn || warning.active {
+            show_warning(&mut transform, &mut warning, materials.get_mut(material), warn, time.elapsed_seconds());
+        }
+    }
+}
+
+/// Hides a platform and takes away its collider while it isn't active.
+/// Crumbling and timed platforms come and go on their own, so they're left alone.
+fn apply_platform_activity(
+    mut commands: Commands,
+    platform_query: Query<(Entity, &Platform), Changed<Platform>>,
+) {
+    for (entity, platform) in platform_query.iter() {
+        if !matches!(platform.platform_type, PlatformType::Crumbling | PlatformType::Timed) {
+            set_platform_solid(&mut commands, entity, platform.is_active);
+        }
+    }
+}
+
+/// Shows or hides a platform, turning its colliders on or off with it
+fn set_platform_solid(commands: &mut Commands, entity: Entity, solid: bool) {
+    let mut platform = commands.entity(entity);
+    if solid {
+        platform.remove::<RigidBodyDisabled>().insert(Visibility::Inherited);
+    } else {
+        platform.insert((RigidBodyDisabled, Visibility::Hidden));
+    }
+}
+
+/// Shakes the platform and flickers its glow while `active`, or puts both back
+fn show_warning(
+    transform: &mut Transform,
+    warning: &mut PlatformWarning,
+    material: Option<&mut StandardMaterial>,
+    active: bool,
+    elapsed: f32,
+) {
+    const SHAKE: f32 = 0.05;
+    const FLICKER_RATE: f32 = 20.0;
+    const WARNING_GLOW: Color = Color::rgb(2.0, 0.7, 0.1);
+
+    warning.active = active;
+    let Some(material) = material else {
+        return;
+    };
+    let base_emissive = *warning.base_emissive.get_or_insert(material.emissive);
+
+    if active {
+        transform.translation = warning.rest
+            + Vec3::new((elapsed * 53.0).sin(), 0.0, (elapsed * 41.0).cos()) * SHAKE;
+        let flicker = (elapsed * FLICKER_RATE).sin() * 0.5 + 0.5;
+        material.emissive = base_emissive * (1.0 - flicker) + WARNING_GLOW * flicker;
+    } else {
+        transform.translation = warning.rest;
+        material.emissive = base_emissive;
+    }
+}
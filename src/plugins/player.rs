@@ -3,7 +3,10 @@ use bevy_rapier3d::prelude::*;
 use crate::{
     components::*,
     events::*,
-    resources::{GameStats, PlayerAnimations, SelectedCharacter, PreloadedCharacterModels, CharacterType},
+    plugins::checkpoints::{ActiveCheckpoint, PLAYER_START},
+    plugins::enemies::EnemyAnimator,
+    plugins::physics::{move_platforms, MovingPlatform, Platform, SurfaceMaterial, PLAYER_COLLISION_GROUPS},
+    resources::{Ability, ControllerMode, GameStats, MovementTuning, PlayerAnimations, SelectedCharacter, PreloadedCharacterModels, CharacterType, UserData},
     states::*,
 };
 
@@ -14,24 +17,42 @@ impl Plugin for PlayerPlugin {
         app
             .add_systems(Update, (
                 handle_player_movement,
+                check_player_grounded.before(update_wall_contact),
+                carry_player_on_platforms.after(check_player_grounded).after(move_platforms),
+                update_wall_contact.before(handle_player_jump),
                 handle_player_jump,
+                handle_player_abilities.after(handle_player_jump).before(detect_ledge_grab),
+                detect_ledge_grab.after(handle_player_jump),
+                handle_ledge_hang.after(detect_ledge_grab),
                 handle_player_flip,
                 handle_trampoline_bounce,
+                handle_player_hurt.before(handle_player_movement),
                 update_player_state,
                 handle_player_animation,
-                check_player_grounded,
                 manage_dive_roll_hitbox,
                 spawn_player_when_ready,
+                switch_player_controller.run_if(resource_changed::<MovementTuning>()),
+            ).run_if(in_state(GameState::Playing)))
+            .add_systems(PostUpdate, (
+                drive_kinematic_player.before(PhysicsSet::SyncBackend),
+                sync_kinematic_velocity.after(PhysicsSet::Writeback),
             ).run_if(in_state(GameState::Playing)))
             .add_systems(Update, (
                 check_player_fall,
-                handle_player_fall,
+                handle_player_death,
                 update_death_vignette,
+                update_damage_flash,
                 handle_player_respawn,
             ).run_if(in_state(GameState::Playing)));
     }
 }
 
+/// Hits the player can take before dying
+const PLAYER_MAX_HEALTH: u32 = 3;
+
+/// Time after letting go of a ledge before the same ledge can catch the player again
+const REGRAB_DELAY: f32 = 0.3;
+
 fn spawn_player_when_ready(
     mut commands: Commands,
     mut spawn_events: EventWriter<PlayerSpawnEvent>,
@@ -39,13 +60,17 @@ fn spawn_player_when_ready(
     player_query: Query<&Player>,
     animations: Option<Res<PlayerAnimations>>,
     preloaded_models: Option<Res<PreloadedCharacterModels>>,
+    tuning: Res<MovementTuning>,
 ) {
     // Only spawn if no player exists, animations are loaded, and models are preloaded
-    if player_query.is_empty() && animations.is_some() && preloaded_models.is_some() {
+    let Some(preloaded_models) = preloaded_models else {
+        return;
+    };
+
+    if player_query.is_empty() && animations.is_some() {
         info!("=== SPAWNING PLAYER ===");
         info!("Selected character: {:?}", selected_character.character_type);
 
-        let preloaded_models = preloaded_models.unwrap();
         let scene = match selected_character.character_type {
             CharacterType::Boss3 => {
                 info!("Using preloaded Boss3 model");
@@ -57,14 +82,14 @@ fn spawn_player_when_ready(
             }
         };
 
-        let spawn_position = Vec3::new(0.0, 7.0, 0.0);
+        let spawn_position = PLAYER_START;
 
-        commands.spawn((
+        let player_entity = commands.spawn((
             Transform::from_translation(spawn_position),
             GlobalTransform::default(),
-            RigidBody::Dynamic,
             Collider::capsule_y(0.4, 0.4),
             MainCollider,
+            PLAYER_COLLISION_GROUPS,
             Velocity::default(),
             GravityScale(1.0),
             LockedAxes::ROTATION_LOCKED,
@@ -88,12 +113,15 @@ fn spawn_player_when_ready(
                 is_dive_rolling: false,
                 flip_direction: Vec3::ZERO,
                 facing_left: false,
-                is_falling: false,
+                is_dead: false,
+                is_hanging: false,
             },
             InheritedVisibility::default(),
             ViewVisibility::default(),
             Name::new("Player"),
-        )).with_children(|parent| {
+        ))
+        .insert((JumpState::default(), WallState::default(), LedgeState::default(), AbilityState::default(), GroundState::default(), HurtState::default(), Health::new(PLAYER_MAX_HEALTH), AttackState::default()))
+        .with_children(|parent| {
             parent.spawn((
                 SceneBundle {
                     scene,
@@ -104,23 +132,117 @@ fn spawn_player_when_ready(
                 },
                 Name::new("PlayerModel"),
             ));
-        });
+        })
+        .id();
+
+        let mode = tuning.controller_for(selected_character.character_type);
+        set_controller_mode(&mut commands.entity(player_entity), mode, &tuning);
 
         spawn_events.send(PlayerSpawnEvent {
             position: spawn_position,
         });
 
-        info!("Player spawned with character: {:?} using preloaded model", selected_character.character_type);
+        info!("Player spawned with character: {:?} using preloaded model and {} controller", selected_character.character_type, mode.label());
         info!("=== END PLAYER SPAWN ===");
     }
 }
 
+/// Gives the player the rigid body for `mode`
+fn set_controller_mode(entity: &mut bevy::ecs::system::EntityCommands, mode: ControllerMode, tuning: &MovementTuning) {
+    match mode {
+        ControllerMode::Dynamic => {
+            entity
+                .remove::<(KinematicCharacterController, KinematicCharacterControllerOutput)>()
+                .insert((RigidBody::Dynamic, mode));
+        }
+        ControllerMode::Kinematic => {
+            entity.insert((RigidBody::KinematicPositionBased, kinematic_controller(tuning), mode));
+        }
+    }
+}
+
+fn kinematic_controller(tuning: &MovementTuning) -> KinematicCharacterController {
+    let max_slope = tuning.max_slope_angle.to_radians();
+    KinematicCharacterController {
+        offset: CharacterLength::Absolute(0.01),
+        autostep: Some(CharacterAutostep {
+            max_height: CharacterLength::Absolute(tuning.step_height),
+            min_width: CharacterLength::Absolute(0.2),
+            include_dynamic_bodies: false,
+        }),
+        max_slope_climb_angle: max_slope,
+        min_slope_slide_angle: max_slope,
+        snap_to_ground: Some(CharacterLength::Absolute(tuning.snap_to_ground)),
+        filter_flags: QueryFilterFlags::EXCLUDE_SENSORS,
+        filter_groups: Some(PLAYER_COLLISION_GROUPS),
+        ..default()
+    }
+}
+
+/// Swaps the player's body when the controller setting changes, and keeps
+/// the kinematic controller in step with the tuning
+fn switch_player_controller(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &ControllerMode, Option<&mut KinematicCharacterController>), With<Player>>,
+    selected_character: Res<SelectedCharacter>,
+    tuning: Res<MovementTuning>,
+) {
+    for (entity, mode, controller) in player_query.iter_mut() {
+        let wanted = tuning.controller_for(selected_character.character_type);
+        if wanted != *mode {
+            set_controller_mode(&mut commands.entity(entity), wanted, &tuning);
+            info!("Switched player to the {} controller", wanted.label());
+        } else if let Some(mut controller) = controller {
+            // Keep the dive roll shape, if one is set
+            let custom_shape = controller.custom_shape.take();
+            *controller = KinematicCharacterController {
+                custom_shape,
+                ..kinematic_controller(&tuning)
+            };
+        }
+    }
+}
+
+/// Hands a kinematic player's velocity, plus gravity, to the character controller
+fn drive_kinematic_player(
+    mut player_query: Query<(&mut Velocity, &GravityScale, &mut KinematicCharacterController), With<Player>>,
+    rapier_config: Res<RapierConfiguration>,
+    time: Res<Time>,
+) {
+    let delta_seconds = time.delta_seconds();
+    for (mut velocity, gravity, mut controller) in player_query.iter_mut() {
+        velocity.linvel += rapier_config.gravity * gravity.0 * delta_seconds;
+        controller.translation = Some(velocity.linvel * delta_seconds);
+    }
+}
+
+/// Rapier's velocity for a kinematic body includes teleports and ground
+/// snapping, so use the motion the controller actually made instead
+fn sync_kinematic_velocity(
+    mut player_query: Query<(&mut Velocity, &KinematicCharacterControllerOutput), With<Player>>,
+    time: Res<Time>,
+) {
+    let delta_seconds = time.delta_seconds();
+    if delta_seconds == 0.0 {
+        return;
+    }
+
+    for (mut velocity, output) in player_query.iter_mut() {
+        let mut linvel = output.effective_translation / delta_seconds;
+        if output.grounded {
+            linvel.y = linvel.y.max(0.0);
+        }
+        velocity.linvel = linvel;
+        velocity.angvel = Vec3::ZERO;
+    }
+}
+
 fn manage_dive_roll_hitbox(
     mut commands: Commands,
-    mut player_query: Query<(Entity, &Player, &mut Collider), With<MainCollider>>,
+    mut player_query: Query<(Entity, &Player, &mut Collider, Option<&mut KinematicCharacterController>), With<MainCollider>>,
     dive_roll_query: Query<Entity, With<DiveRollCollider>>,
 ) {
-    if let Ok((player_entity, player, mut main_collider)) = player_query.get_single_mut() {
+    if let Ok((player_entity, player, mut main_collider, controller)) = player_query.get_single_mut() {
         let has_dive_roll_collider = !dive_roll_query.is_empty();
 
         if player.is_dive_rolling && !has_dive_roll_collider {
@@ -133,10 +255,16 @@ fn manage_dive_roll_hitbox(
                     TransformBundle::from_transform(Transform::from_xyz(0.0, -0.5, 0.0)),
                     Collider::capsule_y(0.15, 0.2), // Smaller capsule: radius 0.15, height 0.2
                     DiveRollCollider,
+                    PLAYER_COLLISION_GROUPS,
                     Name::new("DiveRollCollider"),
                 ));
             });
 
+            // The kinematic controller only moves its own entity's shape, so hand it the same capsule
+            if let Some(mut controller) = controller {
+                controller.custom_shape = Some((Collider::capsule_y(0.15, 0.2), Vec3::new(0.0, -0.5, 0.0), Quat::IDENTITY));
+            }
+
             info!("Dive roll hitbox activated - smaller collider at feet");
 
         } else if !player.is_dive_rolling && has_dive_roll_collider {
@@ -147,19 +275,40 @@ fn manage_dive_roll_hitbox(
 
             // Restore main collider
             *main_collider = Collider::capsule_y(0.4, 0.4);
+            if let Some(mut controller) = controller {
+                controller.custom_shape = None;
+            }
 
             info!("Dive roll hitbox deactivated - main collider restored");
         }
     }
 }
 
+// Player state a movement step reads
+type MovementContext = (&'static WallState, &'static AbilityState, &'static GroundState, &'static HurtState, &'static AttackState);
+
 fn handle_player_movement(
     mut move_events: EventReader<PlayerMoveEvent>,
-    mut player_query: Query<(&mut Transform, &mut Velocity, &mut Player)>,
+    mut player_query: Query<(&mut Transform, &mut Velocity, &mut Player, MovementContext)>,
+    tuning: Res<MovementTuning>,
+    time: Res<Time>,
 ) {
     for event in move_events.read() {
-        if let Ok((mut transform, mut velocity, mut player)) = player_query.get_mut(event.entity) {
-            if player.is_front_flipping || player.is_dive_rolling {
+        if let Ok((mut transform, mut velocity, mut player, (wall, abilities, ground, hurt, attack))) = player_query.get_mut(event.entity) {
+            if player.is_front_flipping || player.is_dive_rolling || player.is_hanging || player.is_dead {
+                continue;
+            }
+
+            // Dashes, ground pounds and swings on the ground are committed to once started
+            if abilities.dash_timer > 0.0 || abilities.is_ground_pounding {
+                continue;
+            }
+            if attack.current.is_some_and(|current| !current.is_air()) {
+                continue;
+            }
+
+            // A wall jump or knockback briefly keeps its own momentum
+            if wall.lockout_timer > 0.0 || hurt.stun_timer > 0.0 {
                 continue;
             }
 
@@ -174,32 +323,372 @@ fn handle_player_movement(
                 transform.rotation = Quat::from_rotation_y(0.0);
             }
 
-            velocity.linvel.x = direction * player.speed;
+            // Ice stretches out speed changes, mud lowers the top speed and
+            // conveyors move the speed the player settles at
+            let (speed_multiplier, traction, belt_speed) = match ground.material {
+                SurfaceMaterial::Ice => (1.0, tuning.ice_traction, 0.0),
+                SurfaceMaterial::Sticky => (tuning.sticky_speed_multiplier, 1.0, 0.0),
+                SurfaceMaterial::Conveyor { speed } => (1.0, 1.0, speed),
+                _ => (1.0, 1.0, 0.0),
+            };
 
-            if direction == 0.0 {
-                // Apply strong friction to stop horizontal movement immediately
-                if player.is_grounded {
-                    velocity.linvel.x *= 0.07; // Much stronger friction when grounded
-                } else {
-                    velocity.linvel.x *= 0.95; // Lighter friction when in air
-                }
-            }
+            let target = tuning.target_speed(direction, player.speed * speed_multiplier) + belt_speed;
+            velocity.linvel.x = tuning.next_velocity(velocity.linvel.x, target, player.is_grounded, time.delta_seconds() * traction);
         }
     }
 }
 
 fn handle_player_jump(
     mut jump_events: EventReader<PlayerJumpEvent>,
-    mut player_query: Query<(&mut Velocity, &Player)>,
+    mut release_events: EventReader<PlayerJumpReleaseEvent>,
+    mut player_query: Query<(&mut Transform, &mut Velocity, &mut Player, &mut JumpState, &mut WallState, &mut AbilityState)>,
+    tuning: Res<MovementTuning>,
+    time: Res<Time>,
+    user_data: Res<UserData>,
     mut stats: ResMut<GameStats>,
 ) {
+    let can_double_jump = user_data.player_stats.has_ability(Ability::DoubleJump);
+
     for event in jump_events.read() {
-        if let Ok((mut velocity, player)) = player_query.get_mut(event.entity) {
+        if let Ok((_, _, _, mut jump, _, _)) = player_query.get_mut(event.entity) {
+            jump.buffer_timer = tuning.jump_buffer_time;
+        }
+    }
+
+    for event in release_events.read() {
+        if let Ok((_, mut velocity, _, mut jump, _, _)) = player_query.get_mut(event.entity) {
+            // Letting go early gives a shorter hop
+            if jump.is_jumping && velocity.linvel.y > 0.0 {
+                velocity.linvel.y *= tuning.jump_cut_multiplier;
+            }
+            jump.is_jumping = false;
+        }
+    }
+
+    let delta_seconds = time.delta_seconds();
+    for (mut transform, mut velocity, mut player, mut jump, mut wall, mut abilities) in player_query.iter_mut() {
+        // Jump climbs up while hanging, which the ledge system handles, and
+        // does nothing while dead
+        if player.is_hanging || player.is_dead {
+            jump.buffer_timer = 0.0;
+            jump.coyote_timer = 0.0;
+            continue;
+        }
+
+        if jump.is_jumping && velocity.linvel.y <= 0.0 {
+            jump.is_jumping = false;
+        }
+
+        // The ground probe still reaches the floor for a moment after takeoff,
+        // which mustn't count as being back on the ground
+        if player.is_grounded && !jump.is_jumping {
+            jump.coyote_timer = tuning.coyote_time;
+        }
+
+        if jump.buffer_timer > 0.0 && jump.coyote_timer > 0.0 {
+            velocity.linvel.y = tuning.jump_velocity;
+            jump.buffer_timer = 0.0;
+            jump.coyote_timer = 0.0;
+            jump.is_jumping = true;
+            stats.jump_count += 1;
+            info!("Player jumped! Total jumps: {}", stats.jump_count);
+        } else if let (true, Some(side)) = (jump.buffer_timer > 0.0, wall.contact_side) {
+            // Kick off away from the wall
+            velocity.linvel.x = -side * tuning.wall_jump_horizontal;
+            velocity.linvel.y = tuning.wall_jump_vertical;
+            jump.buffer_timer = 0.0;
+            jump.is_jumping = true;
+            wall.lockout_timer = tuning.wall_jump_lockout;
+            wall.is_sliding = false;
+
+            player.facing_left = side > 0.0;
+            transform.rotation = if player.facing_left {
+                Quat::from_rotation_y(-std::f32::consts::PI)
+            } else {
+                Quat::from_rotation_y(0.0)
+            };
+
+            stats.jump_count += 1;
+            info!("Player wall jumped! Total jumps: {}", stats.jump_count);
+        } else if jump.buffer_timer > 0.0
+            && can_double_jump
+            && !player.is_grounded
+            && !abilities.is_ground_pounding
+            && abilities.air_jumps_used < tuning.air_jumps
+        {
+            velocity.linvel.y = tuning.double_jump_velocity;
+            jump.buffer_timer = 0.0;
+            jump.is_jumping = true;
+            abilities.air_jumps_used += 1;
+            stats.jump_count += 1;
+            info!("Player double jumped! Total jumps: {}", stats.jump_count);
+        }
+
+        jump.buffer_timer = (jump.buffer_timer - delta_seconds).max(0.0);
+        jump.coyote_timer = (jump.coyote_timer - delta_seconds).max(0.0);
+    }
+}
+
+/// Finds a wall beside the airborne player and slows their fall while they push into it
+fn update_wall_contact(
+    mut player_query: Query<(Entity, &Transform, &mut Velocity, &Player, &mut WallState)>,
+    wall_query: Query<(), With<WallSurface>>,
+    rapier_context: Res<RapierContext>,
+    tuning: Res<MovementTuning>,
+    time: Res<Time>,
+) {
+    // Capsule radius plus a little slack
+    const WALL_PROBE_DISTANCE: f32 = 0.55;
+    // Probe at the chest and near the feet
+    const WALL_PROBE_HEIGHTS: [f32; 2] = [0.2, -0.5];
+
+    for (entity, transform, mut velocity, player, mut wall) in player_query.iter_mut() {
+        wall.lockout_timer = (wall.lockout_timer - time.delta_seconds()).max(0.0);
+
+        let is_wall = |collider: Entity| wall_query.contains(collider);
+        let filter = QueryFilter::default()
+            .exclude_rigid_body(entity)
+            .exclude_sensors()
+            .groups(PLAYER_COLLISION_GROUPS)
+            .predicate(&is_wall);
+        let touches = |side: f32| {
+            WALL_PROBE_HEIGHTS.iter().any(|height| {
+                let origin = transform.translation + Vec3::Y * *height;
+                rapier_context
+                    .cast_ray(origin, Vec3::X * side, WALL_PROBE_DISTANCE, true, filter)
+                    .is_some()
+            })
+        };
+
+        wall.contact_side = if player.is_grounded || player.is_hanging {
+            None
+        } else if touches(-1.0) {
+            Some(-1.0)
+        } else if touches(1.0) {
+            Some(1.0)
+        } else {
+            None
+        };
+
+        let pushing_into_wall = wall.contact_side.is_some_and(|side| {
+            player.is_moving && player.facing_left == (side < 0.0)
+        });
+        wall.is_sliding = pushing_into_wall && velocity.linvel.y < 0.0 && wall.lockout_timer == 0.0;
+
+        if wall.is_sliding {
+            velocity.linvel.y = velocity.linvel.y.max(-tuning.wall_slide_speed);
+        }
+    }
+}
+
+/// Air dash and ground pound, and giving air moves back on landing
+fn handle_player_abilities(
+    mut ability_events: EventReader<PlayerAbilityEvent>,
+    mut player_query: Query<(&mut Velocity, &mut GravityScale, &Player, &mut AbilityState)>,
+    user_data: Res<UserData>,
+    tuning: Res<MovementTuning>,
+    time: Res<Time>,
+) {
+    for event in ability_events.read() {
+        let Ok((mut velocity, mut gravity, player, mut abilities)) = player_query.get_mut(event.entity) else {
+            continue;
+        };
+
+        let airborne = !player.is_grounded
+            && !player.is_dead
+            && !player.is_hanging
+            && !player.is_front_flipping
+            && !player.is_dive_rolling
+            && !abilities.is_ground_pounding;
+        if !airborne || !user_data.player_stats.has_ability(event.ability) {
+            continue;
+        }
+
+        match event.ability {
+            Ability::AirDash if abilities.air_dashes_used < tuning.air_dashes => {
+                let side = if player.facing_left { -1.0 } else { 1.0 };
+                velocity.linvel = Vec3::new(side * tuning.air_dash_speed, 0.0, 0.0);
+                gravity.0 = 0.0;
+                abilities.dash_timer = tuning.air_dash_time;
+                abilities.air_dashes_used += 1;
+                info!("Player air dashed");
+            }
+            Ability::GroundPound => {
+                abilities.dash_timer = 0.0;
+                abilities.is_ground_pounding = true;
+                gravity.0 = 1.0;
+                info!("Player started a ground pound");
+            }
+            _ => {}
+        }
+    }
+
+    for (mut velocity, mut gravity, player, mut abilities) in player_query.iter_mut() {
+        // A ledge grab takes over gravity and velocity
+        if player.is_hanging {
+            abilities.dash_timer = 0.0;
+            abilities.is_ground_pounding = false;
+            continue;
+        }
+
+        if abilities.dash_timer > 0.0 {
+            abilities.dash_timer = (abilities.dash_timer - time.delta_seconds()).max(0.0);
+            velocity.linvel.y = 0.0;
+            if abilities.dash_timer == 0.0 {
+                gravity.0 = 1.0;
+            }
+        }
+
+        if abilities.is_ground_pounding {
+            velocity.linvel = Vec3::new(0.0, -tuning.ground_pound_speed, 0.0);
             if player.is_grounded {
-                velocity.linvel.y = 8.0;
-                stats.jump_count += 1;
-                info!("Player jumped! Total jumps: {}", stats.jump_count);
+                abilities.is_ground_pounding = false;
+                info!("Ground pound landed");
+            }
+        }
+
+        if player.is_grounded && abilities.dash_timer == 0.0 {
+            abilities.air_jumps_used = 0;
+            abilities.air_dashes_used = 0;
+        }
+    }
+}
+
+/// Catches the top of a platform as the player falls past its edge
+fn detect_ledge_grab(
+    mut player_query: Query<(Entity, &mut Transform, &mut Velocity, &mut GravityScale, &mut Player, &mut LedgeState)>,
+    ledge_query: Query<(), With<LedgeSurface>>,
+    rapier_context: Res<RapierContext>,
+    time: Res<Time>,
+) {
+    // Hands relative to the player's centre
+    const HAND_HEIGHT: f32 = 0.6;
+    const HAND_REACH: f32 = 0.55;
+    // A small ball swept down past the hands finds the ledge top
+    const PROBE_RADIUS: f32 = 0.1;
+    const PROBE_START: f32 = 0.3;
+    const PROBE_DEPTH: f32 = 0.45;
+    // Capsule radius plus a little gap, so the player hangs just off the side face
+    const HANG_DISTANCE: f32 = 0.42;
+    // Capsule half height plus radius, plus a little gap above the ledge
+    const STAND_HEIGHT: f32 = 0.85;
+
+    for (entity, mut transform, mut velocity, mut gravity, mut player, mut ledge) in player_query.iter_mut() {
+        ledge.regrab_timer = (ledge.regrab_timer - time.delta_seconds()).max(0.0);
+
+        let can_grab = !player.is_hanging
+            && !player.is_grounded
+            && !player.is_front_flipping
+            && !player.is_dive_rolling
+            && velocity.linvel.y <= 0.0
+            && ledge.regrab_timer == 0.0;
+        if !can_grab {
+            continue;
+        }
+
+        let side = if player.facing_left { -1.0 } else { 1.0 };
+        let is_ledge = |collider: Entity| ledge_query.contains(collider);
+        let ledge_filter = QueryFilter::default()
+            .exclude_rigid_body(entity)
+            .exclude_sensors()
+            .predicate(&is_ledge);
+
+        let probe_origin = transform.translation + Vec3::new(side * HAND_REACH, HAND_HEIGHT + PROBE_START, 0.0);
+        let hit = rapier_context.cast_shape(
+            probe_origin,
+            Quat::IDENTITY,
+            Vec3::NEG_Y,
+            &Collider::ball(PROBE_RADIUS),
+            PROBE_DEPTH,
+            false,
+            ledge_filter,
+        );
+        // A hit at zero means the probe started inside the platform, so the
+        // hands are already below its top
+        let Some((_, hit)) = hit.filter(|(_, hit)| hit.toi > 0.0) else {
+            continue;
+        };
+        let ledge_top = probe_origin.y - hit.toi - PROBE_RADIUS;
+
+        // Find the side face so the player hangs flush against it
+        let edge_probe = Vec3::new(transform.translation.x, ledge_top - 0.05, transform.translation.z);
+        let solid_filter = QueryFilter::default().exclude_rigid_body(entity).exclude_sensors();
+        let edge_x = rapier_context
+            .cast_ray(edge_probe, Vec3::X * side, HAND_REACH + PROBE_RADIUS, true, solid_filter)
+            .map(|(_, toi)| edge_probe.x + side * toi)
+            .unwrap_or(probe_origin.x - side * PROBE_RADIUS);
+
+        ledge.hang_position = Vec3::new(edge_x - side * HANG_DISTANCE, ledge_top - HAND_HEIGHT, transform.translation.z);
+        ledge.climb_target = Vec3::new(edge_x + side * HANG_DISTANCE, ledge_top + STAND_HEIGHT, transform.translation.z);
+        ledge.climb_progress = None;
+
+        player.is_hanging = true;
+        player.is_moving = false;
+        transform.translation = ledge.hang_position;
+        velocity.linvel = Vec3::ZERO;
+        gravity.0 = 0.0;
+        info!("Player grabbed a ledge at {:.2}", ledge_top);
+    }
+}
+
+/// Holds the player on the ledge until they climb up or let go
+fn handle_ledge_hang(
+    mut jump_events: EventReader<PlayerJumpEvent>,
+    mut flip_events: EventReader<PlayerFlipEvent>,
+    mut move_events: EventReader<PlayerMoveEvent>,
+    mut player_query: Query<(&mut Transform, &mut Velocity, &mut GravityScale, &mut Player, &mut LedgeState)>,
+    tuning: Res<MovementTuning>,
+    time: Res<Time>,
+) {
+    // Share of the climb spent rising before moving onto the ledge
+    const RISE_SHARE: f32 = 0.6;
+
+    let mut climb = jump_events.read().count() > 0;
+    let mut drop = false;
+    for event in flip_events.read() {
+        match event.flip_type {
+            FlipType::Front => climb = true,
+            FlipType::Dive => drop = true,
+        }
+    }
+    let movement = move_events.read().last().map(|event| event.direction.x).unwrap_or(0.0);
+
+    for (mut transform, mut velocity, mut gravity, mut player, mut ledge) in player_query.iter_mut() {
+        if !player.is_hanging {
+            continue;
+        }
+        velocity.linvel = Vec3::ZERO;
+
+        let Some(progress) = ledge.climb_progress else {
+            let side = if player.facing_left { -1.0 } else { 1.0 };
+            if climb {
+                ledge.climb_progress = Some(0.0);
+            } else if drop || movement * side < -0.5 {
+                player.is_hanging = false;
+                gravity.0 = 1.0;
+                ledge.regrab_timer = REGRAB_DELAY;
+                info!("Player dropped from ledge");
+            } else {
+                transform.translation = ledge.hang_position;
             }
+            continue;
+        };
+
+        // Straight up first, then over the lip, so the body stays clear of the corner
+        let progress = (progress + time.delta_seconds() / tuning.ledge_climb_time.max(0.01)).min(1.0);
+        let corner = Vec3::new(ledge.hang_position.x, ledge.climb_target.y, ledge.hang_position.z);
+        transform.translation = if progress < RISE_SHARE {
+            ledge.hang_position.lerp(corner, progress / RISE_SHARE)
+        } else {
+            corner.lerp(ledge.climb_target, (progress - RISE_SHARE) / (1.0 - RISE_SHARE))
+        };
+        ledge.climb_progress = Some(progress);
+
+        if progress >= 1.0 {
+            player.is_hanging = false;
+            ledge.climb_progress = None;
+            gravity.0 = 1.0;
+            info!("Player climbed up a ledge");
         }
     }
 }
@@ -245,6 +734,89 @@ fn handle_player_flip(
     }
 }
 
+// Player state a hit changes
+type HitState = (&'static mut AbilityState, &'static mut LedgeState, &'static mut HurtState, &'static mut Health);
+
+/// Takes health when something hurts the player and knocks them back, with a
+/// short spell of invulnerability afterwards; the last of their health kills them
+fn handle_player_hurt(
+    mut commands: Commands,
+    mut hurt_events: EventReader<PlayerHurtEvent>,
+    mut player_query: Query<(&Transform, &mut Velocity, &mut GravityScale, &mut Player, HitState)>,
+    mut death_events: EventWriter<PlayerDeathEvent>,
+    time: Res<Time>,
+) {
+    // How long the knockback keeps control from movement input
+    const STUN_TIME: f32 = 0.35;
+    // How long after a hit further hits are ignored
+    const INVULNERABLE_TIME: f32 = 1.0;
+
+    for (_, _, _, _, (_, _, mut hurt, mut health)) in player_query.iter_mut() {
+        hurt.stun_timer = (hurt.stun_timer - time.delta_seconds()).max(0.0);
+        if health.is_invulnerable() {
+            health.invulnerable_timer = (health.invulnerable_timer - time.delta_seconds()).max(0.0);
+        }
+    }
+
+    for event in hurt_events.read() {
+        let Ok((transform, mut velocity, mut gravity, mut player, (mut abilities, mut ledge, mut hurt, mut health))) = player_query.get_mut(event.entity) else {
+            continue;
+        };
+        if player.is_dead || health.is_invulnerable() {
+            continue;
+        }
+
+        health.current = health.current.saturating_sub(event.damage);
+        if health.current == 0 {
+            player.is_dead = true;
+            death_events.send(PlayerDeathEvent {
+                entity: event.entity,
+                position: transform.translation,
+                cause: event.cause,
+            });
+            continue;
+        }
+
+        // Knockback knocks the player off a ledge, or the hang would hold them in place
+        if player.is_hanging {
+            player.is_hanging = false;
+            ledge.climb_progress = None;
+            ledge.regrab_timer = REGRAB_DELAY;
+        }
+
+        // Knockback interrupts air moves
+        abilities.dash_timer = 0.0;
+        abilities.is_ground_pounding = false;
+        gravity.0 = 1.0;
+
+        velocity.linvel = event.knockback;
+        hurt.stun_timer = STUN_TIME;
+        health.invulnerable_timer = INVULNERABLE_TIME;
+        info!("Player hurt by {:?}, {} of {} health left", event.cause, health.current, health.max);
+
+        commands.spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.0),
+                    top: Val::Px(0.0),
+                    ..default()
+                },
+                background_color: Color::rgba(1.0, 0.0, 0.0, 0.0).into(),
+                z_index: ZIndex::Global(999),
+                ..default()
+            },
+            DamageFlash {
+                start_time: time.elapsed_seconds(),
+                ..default()
+            },
+            Name::new("DamageFlash"),
+        ));
+    }
+}
+
 fn handle_trampoline_bounce(
     mut trampoline_events: EventReader<TrampolineBounceEvent>,
     mut player_query: Query<&mut Velocity>,
@@ -270,16 +842,35 @@ fn update_player_state(
     }
 }
 
-fn handle_player_animation(
-    mut player_query: Query<(&mut Player, &Velocity)>,
-    mut animation_players: Query<&mut AnimationPlayer>,
+pub fn handle_player_animation(
+    mut player_query: Query<(&mut Player, &Velocity, &AttackState)>,
+    mut animation_players: Query<&mut AnimationPlayer, Without<EnemyAnimator>>,
     animations: Res<PlayerAnimations>,
+    clips: Res<Assets<AnimationClip>>,
 ) {
-    for (mut player, velocity) in player_query.iter_mut() {
+    for (mut player, velocity, attack) in player_query.iter_mut() {
         // Use both the is_moving flag and velocity to determine if actually moving
         let is_actually_moving = player.is_moving && velocity.linvel.x.abs() > 0.1;
 
         for mut animation_player in animation_players.iter_mut() {
+            if let Some(current) = attack.current {
+                let clip = match current {
+                    AttackMove::Slash | AttackMove::Finisher => &animations.attack[0],
+                    AttackMove::Backhand => &animations.attack[1],
+                    AttackMove::AirSlash => &animations.air_attack,
+                };
+                // Stretch the clip over the move so its frames line up with the hitbox
+                let speed = clips.get(clip).map_or(1.0, |clip| clip.duration() / current.duration());
+                if attack.elapsed == 0.0 {
+                    animation_player.play(clip.clone()).replay();
+                } else {
+                    animation_player.play(clip.clone());
+                }
+                animation_player.set_speed(speed);
+                continue;
+            }
+            animation_player.set_speed(1.0);
+
             if player.is_front_flipping {
                 animation_player.play(animations.front_flip.clone());
                 if animation_player.is_finished() {
@@ -290,6 +881,8 @@ fn handle_player_animation(
                 if animation_player.is_finished() {
                     player.is_dive_rolling = false;
                 }
+            } else if player.is_hanging {
+                animation_player.play(animations.hang.clone()).repeat();
             } else if !player.is_grounded {
                 animation_player.play(animations.air.clone()).repeat();
             } else if is_actually_moving {
@@ -301,60 +894,139 @@ fn handle_player_animation(
     }
 }
 
+/// Sweeps a ball down from the lower half of the capsule to find walkable ground
 fn check_player_grounded(
-    mut player_query: Query<(Entity, &Transform, &mut Player)>,
+    mut player_query: Query<(Entity, &Transform, &Velocity, &mut Player, &mut GroundState)>,
+    parent_query: Query<&Parent>,
+    platform_query: Query<(&Platform, Option<&SurfaceMaterial>)>,
     rapier_context: Res<RapierContext>,
+    mut land_events: EventWriter<PlayerLandEvent>,
+    tuning: Res<MovementTuning>,
 ) {
-    for (entity, transform, mut player) in player_query.iter_mut() {
-        let ray_pos = transform.translation;
-        let ray_dir = Vec3::new(0.0, -1.0, 0.0);
-        let max_distance = 1.0;
-
-        let ground_hit = rapier_context.cast_ray(
-            ray_pos,
-            ray_dir,
-            max_distance,
-            true,
-            QueryFilter::default().exclude_rigid_body(entity),
+    // Centre of the capsule's lower hemisphere
+    const PROBE_OFFSET: f32 = 0.4;
+    // Narrower than the capsule so walls beside the player aren't hit
+    const PROBE_RADIUS: f32 = 0.35;
+    // Reaches a little below the capsule, and below the dive roll hitbox
+    const PROBE_DEPTH: f32 = 0.15;
+    // Rising faster than this is a jump or bounce leaving the ground, not standing on it
+    const MAX_GROUNDED_RISE: f32 = 1.0;
+
+    for (entity, transform, velocity, mut player, mut ground) in player_query.iter_mut() {
+        // The probe has its own shape, so the dive roll's shrunken collider doesn't matter
+        let probe_origin = transform.translation - Vec3::Y * PROBE_OFFSET;
+        let hit = rapier_context.cast_shape(
+            probe_origin,
+            Quat::IDENTITY,
+            Vec3::NEG_Y,
+            &Collider::ball(PROBE_RADIUS),
+            PROBE_DEPTH,
+            false,
+            QueryFilter::default().exclude_rigid_body(entity).exclude_sensors().groups(PLAYER_COLLISION_GROUPS),
         );
 
+        // Rounded platform edges give a slanted normal, so hanging off an
+        // edge doesn't count as standing on it
+        let contact = hit.and_then(|(collider, toi)| {
+            let normal = toi.details.map_or(Vec3::Y, |details| details.normal1);
+            (normal.y >= tuning.min_ground_normal_y()).then_some((collider, normal))
+        }).filter(|_| velocity.linvel.y <= MAX_GROUNDED_RISE);
+
         let was_grounded = player.is_grounded;
-        player.is_grounded = ground_hit.is_some();
+        player.is_grounded = contact.is_some();
+
+        match contact {
+            Some((collider, normal)) => {
+                // Most platform colliders are children of the entity holding `Platform`
+                let platform = std::iter::once(collider)
+                    .chain(parent_query.get(collider).ok().map(Parent::get))
+                    .find(|candidate| platform_query.contains(*candidate));
+                ground.entity = Some(platform.unwrap_or(collider));
+                let (surface, material) = platform
+                    .and_then(|platform| platform_query.get(platform).ok())
+                    .map_or((None, None), |(platform, material)| (Some(platform.platform_type), material));
+                ground.surface = surface;
+                ground.material = material.copied().unwrap_or_default();
+                ground.normal = normal;
+            }
+            None => {
+                ground.entity = None;
+                ground.surface = None;
+                ground.material = SurfaceMaterial::Normal;
+                ground.normal = Vec3::Y;
+                ground.air_velocity = velocity.linvel;
+            }
+        }
 
-        // Track landing events
         if !was_grounded && player.is_grounded {
-            // Player just landed
-            info!("Player landed!");
+            land_events.send(PlayerLandEvent {
+                entity,
+                impact_velocity: ground.air_velocity,
+            });
+            info!("Player landed on {:?} at {:.1} m/s", ground.surface, -ground.air_velocity.y);
+        }
+    }
+}
+
+/// Moves the player along with the platform under them, and keeps the
+/// platform's velocity when they jump or walk off
+fn carry_player_on_platforms(
+    mut player_query: Query<(&mut Transform, &mut Velocity, &mut GroundState), With<Player>>,
+    platform_query: Query<&MovingPlatform>,
+) {
+    for (mut transform, mut velocity, mut ground) in player_query.iter_mut() {
+        match ground.entity.map(|entity| platform_query.get(entity)) {
+            Some(Ok(platform)) => {
+                transform.translation += platform.delta;
+                ground.platform_velocity = platform.velocity;
+            }
+            Some(Err(_)) => ground.platform_velocity = Vec3::ZERO,
+            None => {
+                if ground.platform_velocity != Vec3::ZERO {
+                    velocity.linvel += ground.platform_velocity;
+                    ground.platform_velocity = Vec3::ZERO;
+                }
+            }
         }
     }
 }
 
 fn check_player_fall(
     mut player_query: Query<(Entity, &Transform, &mut Player)>,
-    mut fall_events: EventWriter<PlayerFallEvent>,
-    mut stats: ResMut<GameStats>,
+    mut death_events: EventWriter<PlayerDeathEvent>,
 ) {
     const FALL_THRESHOLD: f32 = -10.0; // Y position below which player is considered fallen
 
     for (entity, transform, mut player) in player_query.iter_mut() {
-        if transform.translation.y < FALL_THRESHOLD && !player.is_falling {
-            player.is_falling = true;
-            fall_events.send(PlayerFallEvent {
+        if transform.translation.y < FALL_THRESHOLD && !player.is_dead {
+            player.is_dead = true;
+            death_events.send(PlayerDeathEvent {
                 entity,
                 position: transform.translation,
+                cause: DeathCause::Fall,
             });
-            stats.fall_count += 1;
-            info!("Player fell off platform! Total falls: {}", stats.fall_count);
         }
     }
 }
 
-fn handle_player_fall(
+fn handle_player_death(
     mut commands: Commands,
-    mut fall_events: EventReader<PlayerFallEvent>,
+    mut death_events: EventReader<PlayerDeathEvent>,
+    mut player_query: Query<(&mut Velocity, &mut GravityScale)>,
+    mut stats: ResMut<GameStats>,
     time: Res<Time>,
 ) {
-    for _event in fall_events.read() {
+    for event in death_events.read() {
+        stats.death_count += 1;
+        if matches!(event.cause, DeathCause::Fall | DeathCause::KillPlane) {
+            stats.fall_count += 1;
+        } else if let Ok((mut velocity, mut gravity)) = player_query.get_mut(event.entity) {
+            // Stop dead on the hazard rather than carrying on through it
+            velocity.linvel = Vec3::ZERO;
+            gravity.0 = 0.0;
+        }
+        info!("Player died from {:?} at {:?}! Total deaths: {}", event.cause, event.position, stats.death_count);
+
         // Create the death vignette effect - overlay that covers the entire screen
         commands.spawn((
             NodeBundle {
@@ -425,26 +1097,58 @@ fn update_death_vignette(
     }
 }
 
+/// Fades the damage flash out from its strongest at the moment of the hit
+fn update_damage_flash(
+    mut commands: Commands,
+    mut flash_query: Query<(Entity, &mut BackgroundColor, &DamageFlash)>,
+    time: Res<Time>,
+) {
+    for (entity, mut background_color, flash) in flash_query.iter_mut() {
+        let progress = (time.elapsed_seconds() - flash.start_time) / flash.duration;
+        if progress >= 1.0 {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            background_color.0 = Color::rgba(1.0, 0.0, 0.0, flash.max_intensity * (1.0 - progress));
+        }
+    }
+}
+
+// Player state cleared on respawning, so nothing the player was doing carries over
+type RespawnReset = (&'static mut AbilityState, &'static mut AttackState, &'static mut LedgeState, &'static mut HurtState, &'static mut GroundState);
+
 fn handle_player_respawn(
-    mut player_query: Query<(&mut Transform, &mut Velocity, &mut Player)>,
-    vignette_query: Query<&DeathVignette>,
+    mut player_query: Query<(&mut Transform, &mut Velocity, &mut GravityScale, &mut Player, &mut Health, RespawnReset)>,
+    mut vignette_query: Query<&mut DeathVignette>,
     mut spawn_events: EventWriter<PlayerSpawnEvent>,
+    checkpoint: Res<ActiveCheckpoint>,
     time: Res<Time>,
 ) {
     // Check if there's an active vignette effect that's near completion
-    for vignette in vignette_query.iter() {
+    for mut vignette in vignette_query.iter_mut() {
         let elapsed = time.elapsed_seconds() - vignette.start_time;
         let progress = elapsed / vignette.duration;
 
-        if progress >= 0.8 { // Start respawn near end of vignette
-            let spawn_position = Vec3::new(0.0, 7.0, 0.0);
+        // Start respawn near end of vignette, once per death
+        if progress >= 0.8 && !vignette.respawned {
+            vignette.respawned = true;
+            let spawn_position = checkpoint.position;
 
             // Reset player position and velocity
-            if let Ok((mut transform, mut velocity, mut player)) = player_query.get_single_mut() {
+            if let Ok((mut transform, mut velocity, mut gravity, mut player, mut health, reset)) = player_query.get_single_mut() {
+                let (mut abilities, mut attack, mut ledge, mut hurt, mut ground) = reset;
                 transform.translation = spawn_position;
                 velocity.linvel = Vec3::ZERO;
                 velocity.angvel = Vec3::ZERO;
-                player.is_falling = false; // Reset the falling flag
+                player.is_dead = false;
+                // Otherwise the ledge hang pulls the player straight back to where they died
+                player.is_hanging = false;
+                *abilities = AbilityState::default();
+                *attack = AttackState::default();
+                *ledge = LedgeState::default();
+                *hurt = HurtState::default();
+                ground.platform_velocity = Vec3::ZERO;
+                health.refill();
+                gravity.0 = 1.0;
 
                 spawn_events.send(PlayerSpawnEvent {
                     position: spawn_position,
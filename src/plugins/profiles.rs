@@ -0,0 +1,587 @@
+use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
+use bevy_egui::{egui, EguiContexts};
+use std::path::PathBuf;
+use crate::api;
+use crate::events::SyncRequestEvent;
+use crate::resources::{
+    validate_profile_name, ActiveProfile, ApiRuntime, AuthConfig, AuthSession, DataExport, LocalSave,
+    Profile, StoredSession, SyncRecord, UserData,
+};
+use crate::states::{AuthState, GameState};
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(GameState::ProfileSelection), setup_profile_screen)
+            .add_systems(OnExit(GameState::ProfileSelection), cleanup_profile_screen)
+            .add_systems(Update, (
+                profile_ui_system,
+                poll_profile_task,
+            ).run_if(in_state(GameState::ProfileSelection)))
+            .add_systems(Update, sync_active_profile);
+    }
+}
+
+// State of the profile picker
+#[derive(Resource, Default)]
+struct ProfileScreen {
+    profiles: Vec<Profile>,
+    new_name: String,
+    /// Profile whose management options are open
+    managing: Option<String>,
+    /// Profile id and the name being typed
+    renaming: Option<(String, String)>,
+    confirm_delete: bool,
+    confirm_delete_account: bool,
+    delete_account_password: String,
+    error_message: Option<String>,
+    info_message: Option<String>,
+}
+
+// What the player clicked this frame, applied once the UI is drawn
+enum ProfileAction {
+    Play(String),
+    Add,
+    Manage(String),
+    StartRename,
+    SaveRename,
+    CancelRename,
+    Delete,
+    ConfirmDelete,
+    CancelDelete,
+    SignOut,
+    ExportData,
+    DeleteAccount,
+    ConfirmDeleteAccount,
+    CancelDeleteAccount,
+}
+
+// Export or account deletion running on the API runtime
+#[derive(Resource)]
+struct PendingProfileTask(tokio::task::JoinHandle<ProfileTaskResult>);
+
+enum ProfileTaskResult {
+    Exported(Result<PathBuf, String>),
+    AccountDeleted { profile_id: String, result: Result<(), String> },
+}
+
+fn setup_profile_screen(
+    mut commands: Commands,
+    auth_config: Res<AuthConfig>,
+    mut auth_session: ResMut<AuthSession>,
+) {
+    // Nobody is logged in until a profile is picked
+    *auth_session = AuthSession::default();
+    commands.insert_resource(UserData::default());
+    commands.insert_resource(SyncRecord::default());
+    commands.remove_resource::<ActiveProfile>();
+
+    let mut profiles = Profile::load_all(&auth_config.profiles_directory);
+    if profiles.is_empty() {
+        let profile = Profile::new("Player 1");
+        if let Err(e) = profile.write(&auth_config.profiles_directory) {
+            warn!("Failed to create default profile: {}", e);
+        }
+        profiles.push(profile);
+    }
+
+    info!("Profile selection with {} profile(s)", profiles.len());
+    commands.insert_resource(ProfileScreen { profiles, ..default() });
+}
+
+fn cleanup_profile_screen(mut commands: Commands) {
+    commands.remove_resource::<ProfileScreen>();
+}
+
+// Everything switching to a profile changes
+#[derive(SystemParam)]
+struct ProfileSwitch<'w> {
+    auth_config: ResMut<'w, AuthConfig>,
+    auth_session: ResMut<'w, AuthSession>,
+    game_state: ResMut<'w, NextState<GameState>>,
+    auth_state: ResMut<'w, NextState<AuthState>>,
+    sync_requests: EventWriter<'w, SyncRequestEvent>,
+}
+
+fn profile_ui_system(
+    mut contexts: EguiContexts,
+    mut screen: ResMut<ProfileScreen>,
+    switch: ProfileSwitch,
+    runtime: Res<ApiRuntime>,
+    pending_task: Option<Res<PendingProfileTask>>,
+    mut commands: Commands,
+) {
+    let ProfileSwitch { mut auth_config, mut auth_session, mut game_state, mut auth_state, mut sync_requests } = switch;
+    let mut action = None;
+    let busy = pending_task.is_some();
+
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+            ui.add_space(100.0);
+
+            // Title
+            ui.heading("Who's Playing?");
+            ui.add_space(40.0);
+
+            // Error and status messages
+            if let Some(error) = &screen.error_message {
+                ui.colored_label(egui::Color32::RED, error);
+                ui.add_space(10.0);
+            }
+            if let Some(info) = &screen.info_message {
+                ui.colored_label(egui::Color32::LIGHT_GREEN, info);
+                ui.add_space(10.0);
+            }
+
+            let screen = &mut *screen;
+            for profile in &screen.profiles {
+                let session = profile.session.as_ref().filter(|session| !session.is_expired());
+
+                ui.horizontal(|ui| {
+                    ui.add_sized([200.0, 20.0], egui::Label::new(egui::RichText::new(&profile.name).size(18.0)));
+
+                    if ui.button("Play").clicked() {
+                        action = Some(ProfileAction::Play(profile.id.clone()));
+                    }
+                    if ui.button("Manage").clicked() {
+                        action = Some(ProfileAction::Manage(profile.id.clone()));
+                    }
+
+                    if let Some(session) = session {
+                        ui.label(format!("Signed in as {}", session.username.as_deref().unwrap_or("player")));
+                    }
+                });
+
+                if screen.managing.as_deref() == Some(profile.id.as_str()) {
+                    ui.add_space(5.0);
+                    ui.add_enabled_ui(!busy, |ui| {
+                        let fields = ManageFields {
+                            renaming: &mut screen.renaming,
+                            confirm_delete: &mut screen.confirm_delete,
+                            confirm_delete_account: &mut screen.confirm_delete_account,
+                            delete_account_password: &mut screen.delete_account_password,
+                        };
+                        manage_profile_ui(ui, fields, profile, session.is_some(), &mut action);
+                    });
+                }
+                ui.add_space(10.0);
+            }
+
+            ui.add_space(30.0);
+
+            ui.horizontal(|ui| {
+                ui.add_sized([200.0, 20.0], egui::TextEdit::singleline(&mut screen.new_name)
+                    .hint_text("New profile name"));
+                if ui.button("Add Profile").clicked() {
+                    action = Some(ProfileAction::Add);
+                }
+            });
+
+            if busy {
+                ui.add_space(20.0);
+                ui.label("Please wait...");
+            }
+        });
+    });
+
+    let Some(action) = action else {
+        return;
+    };
+    screen.error_message = None;
+    screen.info_message = None;
+    let profiles_directory = auth_config.profiles_directory.clone();
+    let managed_profile = screen.managing.as_ref()
+        .and_then(|id| screen.profiles.iter().find(|profile| profile.id == *id))
+        .cloned();
+
+    match action {
+        ProfileAction::Play(id) => {
+            if let Some(profile) = screen.profiles.iter().find(|profile| profile.id == id).cloned() {
+                activate_profile(
+                    profile,
+                    &mut auth_config,
+                    &mut auth_session,
+                    &mut game_state,
+                    &mut auth_state,
+                    &mut sync_requests,
+                    &mut commands,
+                );
+            }
+        }
+        ProfileAction::Add => {
+            if let Err(error) = validate_profile_name(&screen.new_name, &screen.profiles, None) {
+                screen.error_message = Some(error);
+                return;
+            }
+
+            let profile = Profile::new(&screen.new_name);
+            match profile.write(&profiles_directory) {
+                Ok(()) => {
+                    info!("Created profile {}", profile.name);
+                    screen.new_name.clear();
+                    screen.profiles = Profile::load_all(&profiles_directory);
+                }
+                Err(e) => screen.error_message = Some(format!("Failed to create profile: {}", e)),
+            }
+        }
+        ProfileAction::Manage(id) => {
+            let already_open = screen.managing.as_deref() == Some(id.as_str());
+            screen.managing = (!already_open).then_some(id);
+            screen.renaming = None;
+            screen.confirm_delete = false;
+            screen.confirm_delete_account = false;
+            screen.delete_account_password.clear();
+        }
+        ProfileAction::StartRename => {
+            if let Some(profile) = managed_profile {
+                screen.renaming = Some((profile.id, profile.name));
+            }
+        }
+        ProfileAction::SaveRename => {
+            let Some((id, name)) = screen.renaming.clone() else {
+                return;
+            };
+            if let Err(error) = validate_profile_name(&name, &screen.profiles, Some(&id)) {
+                screen.error_message = Some(error);
+                return;
+            }
+
+            if let Some(profile) = screen.profiles.iter_mut().find(|profile| profile.id == id) {
+                profile.name = name.trim().to_string();
+                if let Err(e) = profile.write(&profiles_directory) {
+                    screen.error_message = Some(format!("Failed to rename profile: {}", e));
+                    return;
+                }
+            }
+            screen.renaming = None;
+            screen.profiles = Profile::load_all(&profiles_directory);
+        }
+        ProfileAction::CancelRename => {
+            screen.renaming = None;
+        }
+        ProfileAction::Delete => {
+            screen.confirm_delete = true;
+        }
+        ProfileAction::ConfirmDelete => {
+            screen.confirm_delete = false;
+            let Some(profile) = managed_profile else {
+                return;
+            };
+            match Profile::delete(&profiles_directory, &profile.id) {
+                Ok(()) => {
+                    info!("Deleted profile {}", profile.name);
+                    screen.managing = None;
+                    screen.profiles.retain(|other| other.id != profile.id);
+                }
+                Err(e) => screen.error_message = Some(format!("Failed to delete profile: {}", e)),
+            }
+        }
+        ProfileAction::CancelDelete => {
+            screen.confirm_delete = false;
+        }
+        ProfileAction::SignOut => {
+            let Some(id) = managed_profile.map(|profile| profile.id) else {
+                return;
+            };
+            if let Some(profile) = screen.profiles.iter_mut().find(|profile| profile.id == id) {
+                profile.session = None;
+                if let Err(e) = profile.write(&profiles_directory) {
+                    screen.error_message = Some(format!("Failed to sign out: {}", e));
+                }
+            }
+        }
+        ProfileAction::ExportData => {
+            let Some(profile) = managed_profile else {
+                return;
+            };
+            let save_directory = Profile::directory(&profiles_directory, &profile.id);
+            let export_directory = auth_config.export_directory.clone();
+            let api_base_url = auth_config.api_base_url.clone();
+
+            let handle = runtime.0.spawn(async move {
+                let result = export_profile_data(api_base_url, save_directory, export_directory, profile).await;
+                ProfileTaskResult::Exported(result)
+            });
+            commands.insert_resource(PendingProfileTask(handle));
+        }
+        ProfileAction::DeleteAccount => {
+            screen.confirm_delete_account = true;
+        }
+        ProfileAction::ConfirmDeleteAccount => {
+            let Some(profile) = managed_profile else {
+                return;
+            };
+            let Some(session) = profile.session.clone() else {
+                return;
+            };
+            let password = std::mem::take(&mut screen.delete_account_password);
+            let save_directory = Profile::directory(&profiles_directory, &profile.id);
+            let api_base_url = auth_config.api_base_url.clone();
+            screen.confirm_delete_account = false;
+
+            let handle = runtime.0.spawn(async move {
+                let result = delete_account(api_base_url, save_directory, session, password).await;
+                ProfileTaskResult::AccountDeleted { profile_id: profile.id, result }
+            });
+            commands.insert_resource(PendingProfileTask(handle));
+        }
+        ProfileAction::CancelDeleteAccount => {
+            screen.confirm_delete_account = false;
+            screen.delete_account_password.clear();
+        }
+    }
+}
+
+// The parts of the profile screen the manage panel edits
+struct ManageFields<'a> {
+    renaming: &'a mut Option<(String, String)>,
+    confirm_delete: &'a mut bool,
+    confirm_delete_account: &'a mut bool,
+    delete_account_password: &'a mut String,
+}
+
+fn manage_profile_ui(
+    ui: &mut egui::Ui,
+    fields: ManageFields,
+    profile: &Profile,
+    signed_in: bool,
+    action: &mut Option<ProfileAction>,
+) {
+    ui.horizontal(|ui| {
+        match fields.renaming {
+            Some((_, name)) => {
+                ui.add_sized([200.0, 20.0], egui::TextEdit::singleline(name));
+                if ui.button("Save").clicked() {
+                    *action = Some(ProfileAction::SaveRename);
+                }
+                if ui.button("Cancel").clicked() {
+                    *action = Some(ProfileAction::CancelRename);
+                }
+            }
+            None => {
+                if ui.button("Rename").clicked() {
+                    *action = Some(ProfileAction::StartRename);
+                }
+                if ui.button("Export My Data").clicked() {
+                    *action = Some(ProfileAction::ExportData);
+                }
+                if ui.button("Delete Profile").clicked() {
+                    *action = Some(ProfileAction::Delete);
+                }
+                if signed_in {
+                    if ui.button("Sign Out").clicked() {
+                        *action = Some(ProfileAction::SignOut);
+                    }
+                    if ui.button("Delete Account").clicked() {
+                        *action = Some(ProfileAction::DeleteAccount);
+                    }
+                }
+            }
+        }
+    });
+
+    if *fields.confirm_delete {
+        ui.horizontal(|ui| {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!("Delete {} and all of its saves on this machine?", profile.name),
+            );
+            if ui.button("Delete").clicked() {
+                *action = Some(ProfileAction::ConfirmDelete);
+            }
+            if ui.button("Cancel").clicked() {
+                *action = Some(ProfileAction::CancelDelete);
+            }
+        });
+    }
+
+    if *fields.confirm_delete_account {
+        ui.colored_label(
+            egui::Color32::YELLOW,
+            "This permanently deletes your account and its progress on the server and this machine.",
+        );
+        ui.horizontal(|ui| {
+            ui.add_sized([200.0, 20.0], egui::TextEdit::singleline(fields.delete_account_password)
+                .password(true)
+                .hint_text("Confirm Password"));
+            let can_delete = !fields.delete_account_password.is_empty();
+            if ui.add_enabled(can_delete, egui::Button::new("Delete Account")).clicked() {
+                *action = Some(ProfileAction::ConfirmDeleteAccount);
+            }
+            if ui.button("Cancel").clicked() {
+                *action = Some(ProfileAction::CancelDeleteAccount);
+            }
+        });
+    }
+}
+
+fn poll_profile_task(
+    mut commands: Commands,
+    runtime: Res<ApiRuntime>,
+    pending: Option<ResMut<PendingProfileTask>>,
+    mut screen: ResMut<ProfileScreen>,
+    auth_config: Res<AuthConfig>,
+) {
+    let Some(mut pending) = pending else {
+        return;
+    };
+    if !pending.0.is_finished() {
+        return;
+    }
+
+    commands.remove_resource::<PendingProfileTask>();
+    let Ok(result) = runtime.0.block_on(&mut pending.0) else {
+        screen.error_message = Some("Request failed unexpectedly".to_string());
+        return;
+    };
+
+    match result {
+        ProfileTaskResult::Exported(Ok(path)) => {
+            info!("Exported player data to {}", path.display());
+            screen.info_message = Some(format!("Data exported to {}", path.display()));
+        }
+        ProfileTaskResult::Exported(Err(e)) => {
+            screen.error_message = Some(format!("Export failed: {}", e));
+        }
+        ProfileTaskResult::AccountDeleted { profile_id, result: Ok(()) } => {
+            // The local saves went with the account, so forget the login too
+            if let Some(profile) = screen.profiles.iter_mut().find(|profile| profile.id == profile_id) {
+                profile.session = None;
+                if let Err(e) = profile.write(&auth_config.profiles_directory) {
+                    warn!("Failed to clear session for profile {}: {}", profile.name, e);
+                }
+            }
+            info!("Account deleted");
+            screen.info_message = Some("Your account has been deleted".to_string());
+        }
+        ProfileTaskResult::AccountDeleted { result: Err(e), .. } => {
+            screen.error_message = Some(format!("Couldn't delete account: {}", e));
+        }
+    }
+}
+
+async fn export_profile_data(
+    api_base_url: String,
+    save_directory: PathBuf,
+    export_directory: PathBuf,
+    profile: Profile,
+) -> Result<PathBuf, String> {
+    let mut export = DataExport::new(&profile, LocalSave::load_all(&save_directory));
+
+    // Include the server copy too, which may hold progress from other machines
+    if let Some(session) = profile.session.as_ref().filter(|session| !session.is_expired()) {
+        match api::fetch_user_data(&api_base_url, &session.session_token).await {
+            Ok(remote) => export.attach_server_copy(remote.user_data, remote.revision),
+            Err(e) => warn!("Exporting local data only, server copy unavailable: {}", e),
+        }
+    }
+
+    export.write(&export_directory).map_err(|e| e.to_string())
+}
+
+async fn delete_account(
+    api_base_url: String,
+    save_directory: PathBuf,
+    session: StoredSession,
+    password: String,
+) -> Result<(), String> {
+    let response = api::delete_account(&api_base_url, &session.session_token, password).await
+        .map_err(|e| e.to_string())?;
+    if !response.success {
+        return Err(response.message);
+    }
+
+    LocalSave::remove(&save_directory, &session.user_id)
+        .map_err(|e| format!("Account deleted, but the local save couldn't be removed: {}", e))
+}
+
+// Points saves at the profile, resuming its last login if it's still valid
+fn activate_profile(
+    profile: Profile,
+    auth_config: &mut AuthConfig,
+    auth_session: &mut AuthSession,
+    game_state: &mut NextState<GameState>,
+    auth_state: &mut NextState<AuthState>,
+    sync_requests: &mut EventWriter<SyncRequestEvent>,
+    commands: &mut Commands,
+) {
+    info!("Playing as profile {}", profile.name);
+    auth_config.save_directory = Profile::directory(&auth_config.profiles_directory, &profile.id);
+
+    let resumed = profile.session.as_ref()
+        .filter(|session| !session.is_expired())
+        .and_then(|session| {
+            LocalSave::load(&auth_config.save_directory, &session.user_id).map(|save| (session, save))
+        });
+
+    match resumed {
+        Some((session, save)) => {
+            auth_session.is_authenticated = true;
+            auth_session.session_token = Some(session.session_token.clone());
+            auth_session.expires_at = Some(session.expires_at());
+            auth_session.offline = false;
+
+            commands.insert_resource(save.user_data);
+            commands.insert_resource(save.sync);
+            sync_requests.send(SyncRequestEvent);
+
+            info!("Resumed session for {:?}", session.username);
+            game_state.set(GameState::CharacterSelection);
+        }
+        None => {
+            auth_state.set(AuthState::Login);
+            game_state.set(GameState::Authentication);
+        }
+    }
+
+    commands.insert_resource(ActiveProfile(profile));
+}
+
+/// Keeps the active profile's preferences and login up to date on disk
+fn sync_active_profile(
+    active_profile: Option<ResMut<ActiveProfile>>,
+    mut user_data: ResMut<UserData>,
+    auth_session: Res<AuthSession>,
+    auth_config: Res<AuthConfig>,
+    mut last_user_id: Local<Option<String>>,
+) {
+    let Some(mut active_profile) = active_profile else {
+        *last_user_id = None;
+        return;
+    };
+    let profile = &mut active_profile.0;
+    let mut changed = false;
+
+    // Preferences belong to the device, so whoever logs in picks up the profile's
+    if user_data.user_id != *last_user_id {
+        *last_user_id = user_data.user_id.clone();
+        if user_data.user_id.is_some() && user_data.preferences != profile.preferences {
+            user_data.preferences = profile.preferences.clone();
+        }
+    } else if user_data.preferences != profile.preferences {
+        profile.preferences = user_data.preferences.clone();
+        changed = true;
+    }
+
+    if auth_session.is_valid() && !auth_session.is_demo() {
+        let logged_in_elsewhere = profile.session.as_ref()
+            .map(|session| Some(&session.session_token) != auth_session.session_token.as_ref())
+            .unwrap_or(true);
+
+        if logged_in_elsewhere {
+            if let Some(user_id) = user_data.user_id.as_deref() {
+                profile.session = StoredSession::from_auth_session(&auth_session, user_id, user_data.username.clone());
+                changed = true;
+            }
+        }
+    }
+
+    if changed {
+        if let Err(e) = profile.write(&auth_config.profiles_directory) {
+            warn!("Failed to save profile {}: {}", profile.name, e);
+        }
+    }
+}
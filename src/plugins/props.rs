@@ -0,0 +1,345 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use crate::{
+    components::*,
+    events::*,
+    plugins::combat::Attackable,
+    plugins::physics::{Coin, PLAYER_GROUP},
+    states::*,
+};
+
+pub struct PropsPlugin;
+
+impl Plugin for PropsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(PlayState::Setup), setup_props)
+            .add_systems(OnEnter(GameState::CharacterSelection), reset_props_on_restart)
+            .add_systems(Update, (
+                handle_prop_hits,
+                break_crates_on_dive_roll,
+                update_debris,
+                return_fallen_crates,
+            ).run_if(in_state(GameState::Playing)));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PropKind {
+    /// Slides along the ground when walked into, and can be stood on
+    PushableCrate,
+    /// Shatters when dive rolled into or attacked, dropping its coins
+    BreakableCrate { coins: u32 },
+}
+
+/// A crate from the level data, with where it goes back to on a new run
+#[derive(Component)]
+pub struct Prop {
+    pub kind: PropKind,
+    pub home: Vec3,
+}
+
+/// Set once a breakable crate has shattered, until the next run
+#[derive(Component, Default)]
+pub struct Breakable {
+    pub broken: bool,
+}
+
+// A shard of a shattered crate, removed once it runs out of time
+#[derive(Component)]
+struct Debris {
+    lifetime: f32,
+}
+
+// Coin dropped by a crate, cleared with the crates on a restart
+#[derive(Component)]
+struct DroppedCoin;
+
+#[derive(Resource)]
+struct PropAssets {
+    debris_mesh: Handle<Mesh>,
+    debris_material: Handle<StandardMaterial>,
+    coin_mesh: Handle<Mesh>,
+    coin_material: Handle<StandardMaterial>,
+}
+
+// Shards fly through the player rather than tripping them up
+const DEBRIS_COLLISION_GROUPS: CollisionGroups = CollisionGroups::new(Group::ALL, Group::ALL.difference(PLAYER_GROUP));
+
+fn setup_props(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Props: kind, centre and edge length of the crate
+    let prop_configs = vec![
+        // Next to the trampoline; pushed under the first floating platform it makes a step up
+        (PropKind::PushableCrate, Vec3::new(2.5, 0.6, 0.0), 1.2),
+        // Under the carousel, to be pushed onto the pressure plate that opens the alcove
+        (PropKind::PushableCrate, Vec3::new(-16.5, 0.6, 0.0), 1.2),
+        // Loose on the ice, where it slides a long way
+        (PropKind::PushableCrate, Vec3::new(27.0, 0.6, 0.0), 1.2),
+        // Near the start, to show what breaks
+        (PropKind::BreakableCrate { coins: 1 }, Vec3::new(-2.0, 0.5, 0.0), 1.0),
+        // At the end of the ground before the surface strips
+        (PropKind::BreakableCrate { coins: 2 }, Vec3::new(24.0, 0.5, 0.0), 1.0),
+        // Behind the alcove door at the left end of the ground
+        (PropKind::BreakableCrate { coins: 3 }, Vec3::new(-24.2, 0.5, 0.0), 1.0),
+        // On the Section 3 convergence bridge, behind its Stalker
+        (PropKind::BreakableCrate { coins: 1 }, Vec3::new(40.5, 23.65, 0.0), 0.8),
+        // Either side of the victory bridge
+        (PropKind::BreakableCrate { coins: 3 }, Vec3::new(37.5, 72.75, 0.0), 1.0),
+        (PropKind::BreakableCrate { coins: 3 }, Vec3::new(42.5, 72.75, 0.0), 1.0),
+    ];
+
+    let pushable_material = materials.add(StandardMaterial {
+        base_color: Color::rgb(0.55, 0.4, 0.25),
+        perceptual_roughness: 0.8,
+        ..default()
+    });
+    let breakable_material = materials.add(StandardMaterial {
+        base_color: Color::rgb(0.8, 0.6, 0.35),
+        emissive: Color::rgb(0.15, 0.1, 0.02),
+        perceptual_roughness: 0.7,
+        ..default()
+    });
+
+    for (kind, home, size) in prop_configs {
+        let mut prop = commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Mesh::from(shape::Cube { size })),
+                transform: Transform::from_translation(home),
+                ..default()
+            },
+            Collider::cuboid(size * 0.5, size * 0.5, size * 0.5),
+            Attackable,
+            Prop { kind, home },
+        ));
+
+        match kind {
+            PropKind::PushableCrate => {
+                prop.insert((
+                    pushable_material.clone(),
+                    RigidBody::Dynamic,
+                    // Stays square and on the course's plane however it's shoved
+                    LockedAxes::ROTATION_LOCKED | LockedAxes::TRANSLATION_LOCKED_Z,
+                    Velocity::default(),
+                    Friction::coefficient(0.4),
+                    ColliderMassProperties::Density(2.0),
+                    Name::new("PushableCrate"),
+                ));
+            }
+            PropKind::BreakableCrate { .. } => {
+                prop.insert((
+                    breakable_material.clone(),
+                    RigidBody::Fixed,
+                    Breakable::default(),
+                    Name::new("BreakableCrate"),
+                ));
+            }
+        }
+    }
+
+    commands.insert_resource(PropAssets {
+        debris_mesh: meshes.add(Mesh::from(shape::Cube { size: 0.25 })),
+        debris_material: breakable_material,
+        coin_mesh: meshes.add(Mesh::from(shape::Cylinder {
+            radius: 0.3,
+            height: 0.1,
+            resolution: 16,
+            segments: 1,
+        })),
+        coin_material: materials.add(StandardMaterial {
+            base_color: Color::rgb(1.0, 0.8, 0.0),
+            metallic: 0.7,
+            perceptual_roughness: 0.1,
+            reflectance: 0.9,
+            emissive: Color::rgb(1.5, 1.2, 0.3),
+            ..default()
+        }),
+    });
+
+    info!("Props setup complete");
+}
+
+/// Hides a crate, sends its shards flying and leaves its coins floating where it stood
+fn shatter_crate(
+    commands: &mut Commands,
+    assets: &PropAssets,
+    entity: Entity,
+    position: Vec3,
+    coins: u32,
+    visibility: &mut Visibility,
+    breakable: &mut Breakable,
+) {
+    breakable.broken = true;
+    *visibility = Visibility::Hidden;
+    commands.entity(entity).insert(ColliderDisabled);
+
+    for i in 0..8 {
+        let angle = i as f32 * std::f32::consts::TAU / 8.0;
+        let direction = Vec3::new(angle.cos(), 0.8 + (i % 3) as f32 * 0.4, angle.sin() * 0.3);
+        commands.spawn((
+            PbrBundle {
+                mesh: assets.debris_mesh.clone(),
+                material: assets.debris_material.clone(),
+                transform: Transform::from_translation(position + direction * 0.2),
+                ..default()
+            },
+            RigidBody::Dynamic,
+            Collider::cuboid(0.125, 0.125, 0.125),
+            DEBRIS_COLLISION_GROUPS,
+            Velocity {
+                linvel: direction * 4.0,
+                angvel: Vec3::new(direction.z, 1.0, -direction.x) * 8.0,
+            },
+            Debris { lifetime: 1.2 },
+            Name::new("CrateDebris"),
+        ));
+    }
+
+    for i in 0..coins {
+        // Spread side by side, centred on where the crate was
+        let x = (i as f32 - (coins - 1) as f32 * 0.5) * 0.7;
+        let coin_position = position + Vec3::new(x, 0.5, 0.0);
+        commands.spawn((
+            PbrBundle {
+                mesh: assets.coin_mesh.clone(),
+                material: assets.coin_material.clone(),
+                transform: Transform::from_translation(coin_position)
+                    .with_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+                ..default()
+            },
+            Coin {
+                float_height: coin_position.y,
+                ..default()
+            },
+            DroppedCoin,
+            Name::new("DroppedCoin"),
+        ));
+    }
+
+    info!("Crate {:?} broken, dropping {} coins", entity, coins);
+}
+
+/// Attacks shatter breakable crates and shove pushable ones
+fn handle_prop_hits(
+    mut commands: Commands,
+    mut hit_events: EventReader<HitEvent>,
+    mut crate_query: Query<(&Prop, &Transform, &mut Visibility, &mut Breakable)>,
+    mut pushable_query: Query<&mut Velocity, With<Prop>>,
+    assets: Res<PropAssets>,
+) {
+    for event in hit_events.read() {
+        if let Ok((prop, transform, mut visibility, mut breakable)) = crate_query.get_mut(event.target) {
+            if let PropKind::BreakableCrate { coins } = prop.kind {
+                if !breakable.broken {
+                    shatter_crate(&mut commands, &assets, event.target, transform.translation, coins, &mut visibility, &mut breakable);
+                }
+            }
+        } else if let Ok(mut velocity) = pushable_query.get_mut(event.target) {
+            velocity.linvel.x = event.knockback.x;
+        }
+    }
+}
+
+fn break_crates_on_dive_roll(
+    mut commands: Commands,
+    player_query: Query<(Entity, &Transform, &Player)>,
+    mut crate_query: Query<(&Prop, &Transform, &mut Visibility, &mut Breakable), Without<Player>>,
+    rapier_context: Res<RapierContext>,
+    assets: Res<PropAssets>,
+) {
+    // A little larger than the player's capsule, since the crate is solid
+    // and stops the roll just short of overlapping it
+    const CONTACT_MARGIN: f32 = 0.1;
+
+    for (entity, transform, player) in player_query.iter() {
+        if !player.is_dive_rolling || player.is_dead {
+            continue;
+        }
+
+        let mut touching = Vec::new();
+        let is_breakable = |collider: Entity| crate_query.contains(collider);
+        rapier_context.intersections_with_shape(
+            transform.translation,
+            Quat::IDENTITY,
+            &Collider::capsule_y(0.4, 0.4 + CONTACT_MARGIN),
+            QueryFilter::default().exclude_rigid_body(entity).predicate(&is_breakable),
+            |collider| {
+                touching.push(collider);
+                true
+            },
+        );
+
+        for crate_entity in touching {
+            let Ok((prop, crate_transform, mut visibility, mut breakable)) = crate_query.get_mut(crate_entity) else {
+                continue;
+            };
+            if let PropKind::BreakableCrate { coins } = prop.kind {
+                if !breakable.broken {
+                    shatter_crate(&mut commands, &assets, crate_entity, crate_transform.translation, coins, &mut visibility, &mut breakable);
+                }
+            }
+        }
+    }
+}
+
+fn update_debris(
+    mut commands: Commands,
+    mut debris_query: Query<(Entity, &mut Debris, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (entity, mut debris, mut transform) in debris_query.iter_mut() {
+        debris.lifetime -= time.delta_seconds();
+        if debris.lifetime <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        } else if debris.lifetime < 0.3 {
+            // Shrink away rather than popping out
+            transform.scale = Vec3::splat(debris.lifetime / 0.3);
+        }
+    }
+}
+
+/// Puts a pushable crate back where it started if it's shoved off the course
+fn return_fallen_crates(
+    mut crate_query: Query<(&Prop, &mut Transform, &mut Velocity)>,
+) {
+    // Same depth the player counts as fallen at
+    const FALL_THRESHOLD: f32 = -10.0;
+
+    for (prop, mut transform, mut velocity) in crate_query.iter_mut() {
+        if transform.translation.y < FALL_THRESHOLD {
+            transform.translation = prop.home;
+            *velocity = Velocity::zero();
+        }
+    }
+}
+
+/// Rebuilds broken crates and returns pushed ones when the player restarts.
+/// Respawning after a death or pausing leaves them as they are, so a crate's
+/// coins can only be had once per run.
+fn reset_props_on_restart(
+    mut commands: Commands,
+    mut prop_query: Query<(&Prop, &mut Transform, Option<&mut Velocity>)>,
+    mut breakable_query: Query<(Entity, &mut Breakable, &mut Visibility)>,
+    coin_query: Query<Entity, With<DroppedCoin>>,
+    debris_query: Query<Entity, With<Debris>>,
+) {
+    for (prop, mut transform, velocity) in prop_query.iter_mut() {
+        transform.translation = prop.home;
+        if let Some(mut velocity) = velocity {
+            *velocity = Velocity::zero();
+        }
+    }
+
+    for (entity, mut breakable, mut visibility) in breakable_query.iter_mut() {
+        breakable.broken = false;
+        *visibility = Visibility::Inherited;
+        commands.entity(entity).remove::<ColliderDisabled>();
+    }
+
+    for entity in coin_query.iter().chain(debris_query.iter()) {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,306 @@
+use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
+use crate::api::{self, ApiError, PutOutcome};
+use crate::events::SyncRequestEvent;
+use crate::resources::{
+    ApiRuntime, AuthConfig, AuthSession, GameStats, LocalSave, RunRecord, SelectedCharacter, SyncRecord,
+    SyncState, SyncStatus, UserData, merge_user_data,
+};
+use crate::states::{GameState, StateCleanup};
+
+// How many times to re-merge when another device writes between our fetch and store
+const MAX_SYNC_ATTEMPTS: usize = 3;
+
+pub struct SyncPlugin;
+
+impl Plugin for SyncPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(GameState::Playing), setup_sync_status_ui)
+            .add_systems(OnExit(GameState::Playing), (record_finished_run, save_player_data_locally).chain())
+            .add_systems(Update, (
+                track_player_progress,
+                auto_save_player_data,
+                update_sync_status_display,
+            ).run_if(in_state(GameState::Playing)))
+            .add_systems(Update, (
+                start_sync,
+                finish_sync,
+            ));
+    }
+}
+
+// In-flight sync, along with the local data it was started from
+#[derive(Resource)]
+struct PendingSync {
+    handle: tokio::task::JoinHandle<Result<SyncResult, ApiError>>,
+    sent: UserData,
+}
+
+struct SyncResult {
+    user_data: UserData,
+    revision: u64,
+}
+
+#[derive(Component)]
+struct SyncStatusText;
+
+fn track_player_progress(
+    time: Res<Time>,
+    stats: Res<GameStats>,
+    mut user_data: ResMut<UserData>,
+) {
+    user_data.player_stats.total_play_time += time.delta_seconds();
+
+    if stats.coins_collected > user_data.player_stats.high_score {
+        user_data.player_stats.high_score = stats.coins_collected;
+    }
+
+    for ability in user_data.player_stats.unlock_abilities() {
+        info!("Unlocked {} with a high score of {}", ability.label(), user_data.player_stats.high_score);
+    }
+}
+
+fn auto_save_player_data(
+    time: Res<Time>,
+    mut last_save: Local<f32>,
+    user_data: Res<UserData>,
+    sync_record: Res<SyncRecord>,
+    auth_config: Res<AuthConfig>,
+    auth_session: Res<AuthSession>,
+    mut sync_requests: EventWriter<SyncRequestEvent>,
+) {
+    // Guests and expired sessions still save locally; start_sync decides whether to upload
+    if !auth_session.is_authenticated {
+        return;
+    }
+
+    *last_save += time.delta_seconds();
+
+    if *last_save >= auth_config.auto_save_interval.as_secs_f32() {
+        *last_save = 0.0;
+
+        // Always save locally first so nothing is lost if the backend is down
+        info!("Auto-saving player data for user: {:?}", user_data.user_id);
+        if let Err(e) = LocalSave::store_progress(&auth_config.save_directory, &user_data, &sync_record) {
+            warn!("Failed to write local save: {}", e);
+        }
+
+        sync_requests.send(SyncRequestEvent);
+    }
+}
+
+// Adds the run to the player's history when they leave it, but not when they pause
+fn record_finished_run(
+    state: Res<State<GameState>>,
+    stats: Res<GameStats>,
+    selected_character: Res<SelectedCharacter>,
+    user_data: Res<UserData>,
+    auth_config: Res<AuthConfig>,
+    auth_session: Res<AuthSession>,
+) {
+    if !auth_session.is_authenticated || *state.get() == GameState::Paused || stats.play_time <= 0.0 {
+        return;
+    }
+
+    let run = RunRecord::finished_now(selected_character.character_type, &stats);
+    if let Err(e) = LocalSave::store_run(&auth_config.save_directory, &user_data, run) {
+        warn!("Failed to record run: {}", e);
+    }
+}
+
+fn save_player_data_locally(
+    user_data: Res<UserData>,
+    sync_record: Res<SyncRecord>,
+    auth_config: Res<AuthConfig>,
+    auth_session: Res<AuthSession>,
+) {
+    if !auth_session.is_authenticated {
+        return;
+    }
+
+    if let Err(e) = LocalSave::store_progress(&auth_config.save_directory, &user_data, &sync_record) {
+        warn!("Failed to write local save: {}", e);
+    }
+}
+
+// The user's progress and what's known about its server copy
+#[derive(SystemParam)]
+struct SyncedProgress<'w> {
+    user_data: ResMut<'w, UserData>,
+    sync_record: ResMut<'w, SyncRecord>,
+    sync_status: ResMut<'w, SyncStatus>,
+}
+
+fn start_sync(
+    mut commands: Commands,
+    mut sync_requests: EventReader<SyncRequestEvent>,
+    runtime: Res<ApiRuntime>,
+    auth_config: Res<AuthConfig>,
+    auth_session: Res<AuthSession>,
+    progress: SyncedProgress,
+    pending: Option<Res<PendingSync>>,
+) {
+    let SyncedProgress { user_data, sync_record, mut sync_status } = progress;
+    if sync_requests.is_empty() {
+        return;
+    }
+    sync_requests.clear();
+
+    if pending.is_some() || !auth_session.is_valid() || auth_session.is_demo() {
+        return;
+    }
+    let Some(session_token) = auth_session.session_token.clone() else {
+        return;
+    };
+
+    let api_base_url = auth_config.api_base_url.clone();
+    let local = user_data.clone();
+    let base = sync_record.base.clone();
+    let last_synced_revision = sync_record.last_synced_revision;
+
+    let handle = runtime.0.spawn(async move {
+        sync_user_data(api_base_url, session_token, local, base, last_synced_revision).await
+    });
+
+    commands.insert_resource(PendingSync {
+        handle,
+        sent: user_data.clone(),
+    });
+    sync_status.state = SyncState::Syncing;
+}
+
+fn finish_sync(
+    mut commands: Commands,
+    runtime: Res<ApiRuntime>,
+    pending: Option<ResMut<PendingSync>>,
+    auth_config: Res<AuthConfig>,
+    mut auth_session: ResMut<AuthSession>,
+    progress: SyncedProgress,
+) {
+    let SyncedProgress { mut user_data, mut sync_record, mut sync_status } = progress;
+    let Some(mut pending) = pending else {
+        return;
+    };
+    if !pending.handle.is_finished() {
+        return;
+    }
+
+    commands.remove_resource::<PendingSync>();
+    let result = runtime.0.block_on(&mut pending.handle)
+        .unwrap_or_else(|e| Err(ApiError::InvalidResponse(e.to_string())));
+
+    match result {
+        // The player switched profile or account while the request was in flight
+        Ok(synced) if synced.user_data.user_id != pending.sent.user_id || user_data.user_id != pending.sent.user_id => {
+            info!("Discarding sync result for a user who is no longer playing");
+        }
+        Ok(synced) => {
+            // Keep any progress made while the request was in flight
+            *user_data = merge_user_data(&user_data, &synced.user_data, Some(&pending.sent));
+            sync_record.base = Some(synced.user_data);
+            sync_record.last_synced_revision = synced.revision;
+
+            auth_session.offline = false;
+            sync_status.state = SyncState::Synced;
+            sync_status.last_error = None;
+            info!("Player data synced at revision {}", synced.revision);
+
+            if let Err(e) = LocalSave::store_progress(&auth_config.save_directory, &user_data, &sync_record) {
+                warn!("Failed to write local save: {}", e);
+            }
+        }
+        Err(e) if e.is_offline() => {
+            auth_session.offline = true;
+            sync_status.state = SyncState::Offline;
+            sync_status.last_error = Some(e.to_string());
+            info!("Backend unreachable, progress kept locally: {}", e);
+        }
+        Err(e) if e.is_unauthorized() => {
+            // Treat the session as expired so we stop sending it; the next login starts a new one
+            auth_session.expires_at = None;
+            sync_status.state = SyncState::SignedOut;
+            sync_status.last_error = Some(e.to_string());
+            warn!("Backend rejected the session, progress kept locally: {}", e);
+        }
+        Err(e) => {
+            sync_status.state = SyncState::Failed;
+            sync_status.last_error = Some(e.to_string());
+            warn!("Player data sync failed, will retry on the next auto-save: {}", e);
+        }
+    }
+}
+
+async fn sync_user_data(
+    api_base_url: String,
+    session_token: String,
+    local: UserData,
+    base: Option<UserData>,
+    last_synced_revision: u64,
+) -> Result<SyncResult, ApiError> {
+    for _ in 0..MAX_SYNC_ATTEMPTS {
+        let remote = api::fetch_user_data(&api_base_url, &session_token).await?;
+
+        // If nobody else has written since our last sync, the local copy is already up to date
+        let merged = if remote.revision == last_synced_revision && base.is_some() {
+            local.clone()
+        } else {
+            merge_user_data(&local, &remote.user_data, base.as_ref())
+        };
+
+        if merged == remote.user_data {
+            return Ok(SyncResult { user_data: merged, revision: remote.revision });
+        }
+
+        match api::put_user_data(&api_base_url, &session_token, &merged, remote.revision).await? {
+            PutOutcome::Stored { revision } => return Ok(SyncResult { user_data: merged, revision }),
+            PutOutcome::Conflict => continue,
+        }
+    }
+
+    Err(ApiError::Conflict)
+}
+
+fn setup_sync_status_ui(mut commands: Commands) {
+    // Sync status in the top left, below the coin counter and health
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(15.0),
+                top: Val::Px(80.0),
+                ..default()
+            },
+            ..default()
+        },
+        StateCleanup,
+    )).with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: default(),
+                    font_size: 16.0,
+                    color: Color::rgb(1.0, 0.6, 0.3),
+                },
+            ),
+            SyncStatusText,
+        ));
+    });
+}
+
+fn update_sync_status_display(
+    sync_status: Res<SyncStatus>,
+    mut query: Query<&mut Text, With<SyncStatusText>>,
+) {
+    let message = match sync_status.state {
+        SyncState::Offline => "Offline - progress saved locally",
+        SyncState::SignedOut => "Signed out - log in again to sync",
+        SyncState::Failed => "Sync failed - will retry",
+        _ => "",
+    };
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = message.to_string();
+    }
+}
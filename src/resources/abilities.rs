@@ -0,0 +1,51 @@
+/// Air moves the player earns by collecting coins. Unlocks are saved in
+/// `PlayerStats::unlocked_abilities` under `Ability::id`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Ability {
+    DoubleJump,
+    AirDash,
+    GroundPound,
+}
+
+impl Ability {
+    pub const ALL: [Ability; 3] = [
+        Ability::DoubleJump,
+        Ability::AirDash,
+        Ability::GroundPound,
+    ];
+
+    /// Name stored in the save file; never change an existing one
+    pub fn id(&self) -> &'static str {
+        match self {
+            Ability::DoubleJump => "double_jump",
+            Ability::AirDash => "air_dash",
+            Ability::GroundPound => "ground_pound",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Ability::DoubleJump => "Double Jump",
+            Ability::AirDash => "Air Dash",
+            Ability::GroundPound => "Ground Pound",
+        }
+    }
+
+    /// Short tag shown on the HUD icon
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Ability::DoubleJump => "2J",
+            Ability::AirDash => "DA",
+            Ability::GroundPound => "GP",
+        }
+    }
+
+    /// Best coin count that unlocks this ability
+    pub fn unlock_score(&self) -> u32 {
+        match self {
+            Ability::DoubleJump => 3,
+            Ability::AirDash => 8,
+            Ability::GroundPound => 15,
+        }
+    }
+}
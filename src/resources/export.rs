@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use super::{InputConfig, LocalSave, Profile, RunRecord, UserData, UserPreferences};
+
+const EXPORT_FORMAT: &str = "sidequest-data-export";
+
+/// "Export my data" archive for one profile: its settings plus the data of
+/// every account that has played under it. Credentials are left out.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DataExport {
+    pub format: String,
+    /// Seconds since the Unix epoch
+    pub exported_at: u64,
+    pub profile: ExportedProfile,
+    pub accounts: Vec<ExportedAccount>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExportedProfile {
+    pub id: String,
+    pub name: String,
+    pub preferences: UserPreferences,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExportedAccount {
+    /// Copy saved on this machine
+    pub user_data: Option<UserData>,
+    pub last_synced_revision: u64,
+    pub input: Option<InputConfig>,
+    /// Runs finished on this machine; the game doesn't record replays
+    pub runs: Vec<RunRecord>,
+    /// Copy held by the backend, when it could be reached
+    pub server_user_data: Option<UserData>,
+    pub server_revision: Option<u64>,
+}
+
+impl DataExport {
+    pub fn new(profile: &Profile, saves: Vec<LocalSave>) -> Self {
+        Self {
+            format: EXPORT_FORMAT.to_string(),
+            exported_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            profile: ExportedProfile {
+                id: profile.id.clone(),
+                name: profile.name.clone(),
+                preferences: profile.preferences.clone(),
+            },
+            accounts: saves.into_iter()
+                .map(|save| ExportedAccount {
+                    user_data: Some(save.user_data),
+                    last_synced_revision: save.sync.last_synced_revision,
+                    input: save.input,
+                    runs: save.runs,
+                    server_user_data: None,
+                    server_revision: None,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn attach_server_copy(&mut self, user_data: UserData, revision: u64) {
+        let existing = self.accounts.iter_mut().find(|account| {
+            account.user_data.as_ref().is_some_and(|local| local.user_id == user_data.user_id)
+        });
+
+        match existing {
+            Some(account) => {
+                account.server_user_data = Some(user_data);
+                account.server_revision = Some(revision);
+            }
+            None => self.accounts.push(ExportedAccount {
+                user_data: None,
+                last_synced_revision: 0,
+                input: None,
+                runs: Vec::new(),
+                server_user_data: Some(user_data),
+                server_revision: Some(revision),
+            }),
+        }
+    }
+
+    /// Writes the archive as a single JSON file and returns its path
+    pub fn write(&self, export_directory: &Path) -> io::Result<PathBuf> {
+        let name: String = self.profile.name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        let path = export_directory.join(format!("sidequest-export-{}-{}.json", name, self.exported_at));
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        fs::create_dir_all(export_directory)?;
+        fs::write(&path, contents)?;
+        Ok(path)
+    }
+}
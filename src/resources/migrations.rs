@@ -0,0 +1,106 @@
+use serde_json::{Map, Value};
+use std::fmt;
+use super::{PlayerStats, UserData, UserPreferences};
+
+/// Current version of the `UserData` JSON schema, which also covers the
+/// nested `PlayerStats` and `UserPreferences`.
+///
+/// Version 0 is everything written before `schema_version` existed.
+pub const USER_DATA_SCHEMA_VERSION: u32 = 2;
+
+type Migration = fn(&mut Map<String, Value>);
+
+// MIGRATIONS[n] upgrades version n to n + 1
+const MIGRATIONS: [Migration; USER_DATA_SCHEMA_VERSION as usize] = [
+    migrate_v0_to_v1,
+    migrate_v1_to_v2,
+];
+
+// Errors from upgrading stored or received user data
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The user data isn't a JSON object
+    NotAnObject,
+    /// Written by a newer version of the game; refuse rather than drop fields
+    UnsupportedVersion(u64),
+    /// Migrated JSON still doesn't match the current structs
+    Invalid(serde_json::Error),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::NotAnObject => write!(f, "user data is not a JSON object"),
+            MigrationError::UnsupportedVersion(version) => write!(
+                f,
+                "user data schema version {} is newer than supported version {}",
+                version, USER_DATA_SCHEMA_VERSION
+            ),
+            MigrationError::Invalid(e) => write!(f, "invalid user data: {}", e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for MigrationError {
+    fn from(error: serde_json::Error) -> Self {
+        MigrationError::Invalid(error)
+    }
+}
+
+/// Upgrades a `UserData` JSON object in place to the current schema version
+pub fn migrate_user_data(value: &mut Value) -> Result<(), MigrationError> {
+    let Value::Object(data) = value else {
+        return Err(MigrationError::NotAnObject);
+    };
+
+    let version = data.get("schema_version").and_then(Value::as_u64).unwrap_or(0);
+    if version > USER_DATA_SCHEMA_VERSION as u64 {
+        return Err(MigrationError::UnsupportedVersion(version));
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(data);
+        data.insert("schema_version".to_string(), Value::from(from + 1));
+    }
+    Ok(())
+}
+
+/// Parses `UserData` from JSON of any supported schema version
+pub fn user_data_from_value(mut value: Value) -> Result<UserData, MigrationError> {
+    migrate_user_data(&mut value)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Upgrades the `user_data` field of a backend response, if it has one
+pub fn migrate_response(value: &mut Value) -> Result<(), MigrationError> {
+    match value.get_mut("user_data") {
+        Some(Value::Null) | None => Ok(()),
+        Some(user_data) => migrate_user_data(user_data),
+    }
+}
+
+/// Upgrades the user data embedded in a local save file
+pub fn migrate_save(value: &mut Value) -> Result<(), MigrationError> {
+    migrate_response(value)?;
+    match value.pointer_mut("/sync/base") {
+        Some(Value::Null) | None => Ok(()),
+        Some(base) => migrate_user_data(base),
+    }
+}
+
+// v0 -> v1: adds `schema_version`. Accounts created by the first backend
+// have no stats or preferences yet, so fill in the defaults.
+fn migrate_v0_to_v1(data: &mut Map<String, Value>) {
+    data.entry("player_stats")
+        .or_insert_with(|| serde_json::to_value(PlayerStats::default()).unwrap_or_default());
+    data.entry("preferences")
+        .or_insert_with(|| serde_json::to_value(UserPreferences::default()).unwrap_or_default());
+}
+
+// v1 -> v2: adds `player_stats.unlocked_abilities`. Nothing was unlocked
+// before abilities existed; the saved high score unlocks them next run.
+fn migrate_v1_to_v2(data: &mut Map<String, Value>) {
+    if let Some(Value::Object(stats)) = data.get_mut("player_stats") {
+        stats.entry("unlocked_abilities").or_insert_with(|| Value::Array(Vec::new()));
+    }
+}
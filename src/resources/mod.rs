@@ -1,6 +1,21 @@
 use bevy::prelude::*;
+use bevy::utils::HashSet;
 use serde::{Deserialize, Serialize};
 
+mod abilities;
+mod export;
+mod migrations;
+mod profile;
+mod save;
+mod tuning;
+
+pub use abilities::*;
+pub use export::*;
+pub use migrations::*;
+pub use profile::*;
+pub use save::*;
+pub use tuning::*;
+
 // Game Configuration
 #[derive(Resource)]
 pub struct GameConfig {
@@ -28,12 +43,13 @@ impl Default for GameConfig {
 }
 
 // Game Statistics
-#[derive(Resource, Default)]
+#[derive(Resource, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct GameStats {
     pub play_time: f32,
     pub jump_count: u32,
     pub flip_count: u32,
     pub fall_count: u32,
+    pub death_count: u32,
     pub platform_touches: u32,
     pub max_height: f32,
     pub total_distance: f32,
@@ -50,7 +66,7 @@ pub struct PerformanceMetrics {
 }
 
 // Asset Management
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct GameAssets {
     pub player_scene: Handle<Scene>,
     pub player_animations: PlayerAnimations,
@@ -60,19 +76,6 @@ pub struct GameAssets {
     pub audio_flip: Handle<AudioSource>,
 }
 
-impl Default for GameAssets {
-    fn default() -> Self {
-        Self {
-            player_scene: Handle::default(),
-            player_animations: PlayerAnimations::default(),
-            skybox_texture: Handle::default(),
-            audio_jump: Handle::default(),
-            audio_land: Handle::default(),
-            audio_flip: Handle::default(),
-        }
-    }
-}
-
 #[derive(Resource, Default, Clone)]
 pub struct PlayerAnimations {
     pub walk: Handle<AnimationClip>,
@@ -80,50 +83,45 @@ pub struct PlayerAnimations {
     pub idle: Handle<AnimationClip>,
     pub front_flip: Handle<AnimationClip>,
     pub dive_roll: Handle<AnimationClip>,
+    pub hang: Handle<AnimationClip>,
+    /// Swings of the ground combo, alternated from one step to the next
+    pub attack: [Handle<AnimationClip>; 2],
+    pub air_attack: Handle<AnimationClip>,
 }
 
 // Preloaded animations for both characters
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct PreloadedAnimations {
     pub boss3: PlayerAnimations,
     pub sword_hero: PlayerAnimations,
 }
 
-impl Default for PreloadedAnimations {
-    fn default() -> Self {
-        Self {
-            boss3: PlayerAnimations::default(),
-            sword_hero: PlayerAnimations::default(),
-        }
-    }
-}
-
 // Preloaded character models
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct PreloadedCharacterModels {
     pub boss3: Handle<Scene>,
     pub sword_hero: Handle<Scene>,
 }
 
-impl Default for PreloadedCharacterModels {
-    fn default() -> Self {
-        Self {
-            boss3: Handle::default(),
-            sword_hero: Handle::default(),
-        }
-    }
-}
-
 // Input Configuration
-#[derive(Resource, Clone)]
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct InputConfig {
     pub move_left: KeyCode,
     pub move_right: KeyCode,
     pub jump: KeyCode,
     pub front_flip: KeyCode,
     pub dive_roll: KeyCode,
+    pub air_dash: KeyCode,
+    pub ground_pound: KeyCode,
+    pub attack: KeyCode,
     pub pause: KeyCode,
+    pub restart: KeyCode,
+    pub confirm: KeyCode,
     pub debug_toggle: KeyCode,
+    pub toggle_colliders: KeyCode,
+    pub toggle_physics_debug: KeyCode,
+    pub reset_stats: KeyCode,
     pub camera_toggle: KeyCode,
     pub camera_forward: KeyCode,
     pub camera_backward: KeyCode,
@@ -141,8 +139,16 @@ impl Default for InputConfig {
             jump: KeyCode::Space,
             front_flip: KeyCode::W,
             dive_roll: KeyCode::S,
+            air_dash: KeyCode::ShiftLeft,
+            ground_pound: KeyCode::ControlLeft,
+            attack: KeyCode::F,
             pause: KeyCode::Escape,
+            restart: KeyCode::R,
+            confirm: KeyCode::Return,
             debug_toggle: KeyCode::F3,
+            toggle_colliders: KeyCode::F4,
+            toggle_physics_debug: KeyCode::F5,
+            reset_stats: KeyCode::F6,
             camera_toggle: KeyCode::C,
             camera_forward: KeyCode::I,
             camera_backward: KeyCode::K,
@@ -154,6 +160,300 @@ impl Default for InputConfig {
     }
 }
 
+/// Every rebindable action, in the order the controls screen lists them
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    MoveLeft,
+    MoveRight,
+    Jump,
+    FrontFlip,
+    DiveRoll,
+    AirDash,
+    GroundPound,
+    Attack,
+    Pause,
+    Restart,
+    Confirm,
+    CameraToggle,
+    CameraForward,
+    CameraBackward,
+    CameraLeft,
+    CameraRight,
+    CameraUp,
+    CameraDown,
+    DebugToggle,
+    ToggleColliders,
+    TogglePhysicsDebug,
+    ResetStats,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 22] = [
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+        InputAction::Jump,
+        InputAction::FrontFlip,
+        InputAction::DiveRoll,
+        InputAction::AirDash,
+        InputAction::GroundPound,
+        InputAction::Attack,
+        InputAction::Pause,
+        InputAction::Restart,
+        InputAction::Confirm,
+        InputAction::CameraToggle,
+        InputAction::CameraForward,
+        InputAction::CameraBackward,
+        InputAction::CameraLeft,
+        InputAction::CameraRight,
+        InputAction::CameraUp,
+        InputAction::CameraDown,
+        InputAction::DebugToggle,
+        InputAction::ToggleColliders,
+        InputAction::TogglePhysicsDebug,
+        InputAction::ResetStats,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputAction::MoveLeft => "Move Left",
+            InputAction::MoveRight => "Move Right",
+            InputAction::Jump => "Jump",
+            InputAction::FrontFlip => "Front Flip",
+            InputAction::DiveRoll => "Dive Roll",
+            InputAction::AirDash => "Air Dash",
+            InputAction::GroundPound => "Ground Pound",
+            InputAction::Attack => "Attack",
+            InputAction::Pause => "Pause",
+            InputAction::Restart => "Restart",
+            InputAction::Confirm => "Confirm",
+            InputAction::CameraToggle => "Toggle Free Camera",
+            InputAction::CameraForward => "Camera Forward",
+            InputAction::CameraBackward => "Camera Backward",
+            InputAction::CameraLeft => "Camera Left",
+            InputAction::CameraRight => "Camera Right",
+            InputAction::CameraUp => "Camera Up",
+            InputAction::CameraDown => "Camera Down",
+            InputAction::DebugToggle => "Toggle Debug Mode",
+            InputAction::ToggleColliders => "Toggle Colliders",
+            InputAction::TogglePhysicsDebug => "Toggle Physics Debug",
+            InputAction::ResetStats => "Reset Stats",
+        }
+    }
+}
+
+impl InputConfig {
+    pub fn key(&self, action: InputAction) -> KeyCode {
+        match action {
+            InputAction::MoveLeft => self.move_left,
+            InputAction::MoveRight => self.move_right,
+            InputAction::Jump => self.jump,
+            InputAction::FrontFlip => self.front_flip,
+            InputAction::DiveRoll => self.dive_roll,
+            InputAction::AirDash => self.air_dash,
+            InputAction::GroundPound => self.ground_pound,
+            InputAction::Attack => self.attack,
+            InputAction::Pause => self.pause,
+            InputAction::Restart => self.restart,
+            InputAction::Confirm => self.confirm,
+            InputAction::CameraToggle => self.camera_toggle,
+            InputAction::CameraForward => self.camera_forward,
+            InputAction::CameraBackward => self.camera_backward,
+            InputAction::CameraLeft => self.camera_left,
+            InputAction::CameraRight => self.camera_right,
+            InputAction::CameraUp => self.camera_up,
+            InputAction::CameraDown => self.camera_down,
+            InputAction::DebugToggle => self.debug_toggle,
+            InputAction::ToggleColliders => self.toggle_colliders,
+            InputAction::TogglePhysicsDebug => self.toggle_physics_debug,
+            InputAction::ResetStats => self.reset_stats,
+        }
+    }
+
+    fn key_mut(&mut self, action: InputAction) -> &mut KeyCode {
+        match action {
+            InputAction::MoveLeft => &mut self.move_left,
+            InputAction::MoveRight => &mut self.move_right,
+            InputAction::Jump => &mut self.jump,
+            InputAction::FrontFlip => &mut self.front_flip,
+            InputAction::DiveRoll => &mut self.dive_roll,
+            InputAction::AirDash => &mut self.air_dash,
+            InputAction::GroundPound => &mut self.ground_pound,
+            InputAction::Attack => &mut self.attack,
+            InputAction::Pause => &mut self.pause,
+            InputAction::Restart => &mut self.restart,
+            InputAction::Confirm => &mut self.confirm,
+            InputAction::CameraToggle => &mut self.camera_toggle,
+            InputAction::CameraForward => &mut self.camera_forward,
+            InputAction::CameraBackward => &mut self.camera_backward,
+            InputAction::CameraLeft => &mut self.camera_left,
+            InputAction::CameraRight => &mut self.camera_right,
+            InputAction::CameraUp => &mut self.camera_up,
+            InputAction::CameraDown => &mut self.camera_down,
+            InputAction::DebugToggle => &mut self.debug_toggle,
+            InputAction::ToggleColliders => &mut self.toggle_colliders,
+            InputAction::TogglePhysicsDebug => &mut self.toggle_physics_debug,
+            InputAction::ResetStats => &mut self.reset_stats,
+        }
+    }
+
+    /// The other action already using `key`, if any
+    pub fn conflict(&self, action: InputAction, key: KeyCode) -> Option<InputAction> {
+        InputAction::ALL.into_iter()
+            .find(|other| *other != action && self.key(*other) == key)
+    }
+
+    /// Binds `key` to `action`. An action that already used `key` takes over
+    /// the old key, so no two actions ever share one; it is returned so the
+    /// caller can say what moved.
+    pub fn rebind(&mut self, action: InputAction, key: KeyCode) -> Option<InputAction> {
+        let old_key = self.key(action);
+        let conflict = self.conflict(action, key);
+        if let Some(other) = conflict {
+            *self.key_mut(other) = old_key;
+        }
+        *self.key_mut(action) = key;
+        conflict
+    }
+}
+
+// Gamepad Configuration
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct GamepadConfig {
+    pub jump: GamepadButtonType,
+    pub front_flip: GamepadButtonType,
+    pub dive_roll: GamepadButtonType,
+    pub air_dash: GamepadButtonType,
+    pub ground_pound: GamepadButtonType,
+    pub attack: GamepadButtonType,
+    pub pause: GamepadButtonType,
+    pub restart: GamepadButtonType,
+    pub confirm: GamepadButtonType,
+    pub camera_toggle: GamepadButtonType,
+    pub camera_up: GamepadButtonType,
+    pub camera_down: GamepadButtonType,
+    /// Stick deflection below this is ignored; the rest of the range is rescaled to 0-1
+    pub stick_deadzone: f32,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self {
+            jump: GamepadButtonType::South,
+            front_flip: GamepadButtonType::West,
+            dive_roll: GamepadButtonType::East,
+            air_dash: GamepadButtonType::RightTrigger2,
+            ground_pound: GamepadButtonType::LeftTrigger2,
+            attack: GamepadButtonType::North,
+            pause: GamepadButtonType::Start,
+            restart: GamepadButtonType::Select,
+            confirm: GamepadButtonType::South,
+            camera_toggle: GamepadButtonType::RightThumb,
+            camera_up: GamepadButtonType::RightTrigger,
+            camera_down: GamepadButtonType::LeftTrigger,
+            stick_deadzone: 0.2,
+        }
+    }
+}
+
+impl GamepadConfig {
+    /// The button bound to `action`, if it has one. Movement and free camera
+    /// directions come from the sticks and d-pad instead.
+    pub fn button(&self, action: InputAction) -> Option<GamepadButtonType> {
+        match action {
+            InputAction::MoveLeft => Some(GamepadButtonType::DPadLeft),
+            InputAction::MoveRight => Some(GamepadButtonType::DPadRight),
+            InputAction::Jump => Some(self.jump),
+            InputAction::FrontFlip => Some(self.front_flip),
+            InputAction::DiveRoll => Some(self.dive_roll),
+            InputAction::AirDash => Some(self.air_dash),
+            InputAction::GroundPound => Some(self.ground_pound),
+            InputAction::Attack => Some(self.attack),
+            InputAction::Pause => Some(self.pause),
+            InputAction::Restart => Some(self.restart),
+            InputAction::Confirm => Some(self.confirm),
+            InputAction::CameraToggle => Some(self.camera_toggle),
+            InputAction::CameraUp => Some(self.camera_up),
+            InputAction::CameraDown => Some(self.camera_down),
+            _ => None,
+        }
+    }
+
+    /// Applies the deadzone to a raw stick axis value
+    pub fn stick_value(&self, raw: f32) -> f32 {
+        let magnitude = raw.abs();
+        if magnitude <= self.stick_deadzone {
+            return 0.0;
+        }
+        let scaled = (magnitude - self.stick_deadzone) / (1.0 - self.stick_deadzone);
+        scaled.min(1.0) * raw.signum()
+    }
+}
+
+/// This frame's actions from the keyboard and every connected gamepad.
+/// Gameplay and menus read this instead of raw device input.
+#[derive(Resource, Default, Debug)]
+pub struct ActionState {
+    pressed: HashSet<InputAction>,
+    just_pressed: HashSet<InputAction>,
+    just_released: HashSet<InputAction>,
+    /// Horizontal movement from -1 to 1; anywhere in between with a stick
+    pub movement: f32,
+    /// Free camera movement: x right, y up, z forward, each from -1 to 1
+    pub camera: Vec3,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    pub fn just_released(&self, action: InputAction) -> bool {
+        self.just_released.contains(&action)
+    }
+
+    /// Replaces the held actions, working out which of them started or ended this frame
+    pub fn update(&mut self, pressed: HashSet<InputAction>, movement: f32, camera: Vec3) {
+        self.just_pressed = pressed.difference(&self.pressed).copied().collect();
+        self.just_released = self.pressed.difference(&pressed).copied().collect();
+        self.pressed = pressed;
+        self.movement = movement;
+        self.camera = camera;
+    }
+}
+
+/// Name shown for a key in prompts and on the controls screen
+pub fn key_name(key: KeyCode) -> String {
+    let name = match key {
+        KeyCode::Key0 => "0",
+        KeyCode::Key1 => "1",
+        KeyCode::Key2 => "2",
+        KeyCode::Key3 => "3",
+        KeyCode::Key4 => "4",
+        KeyCode::Key5 => "5",
+        KeyCode::Key6 => "6",
+        KeyCode::Key7 => "7",
+        KeyCode::Key8 => "8",
+        KeyCode::Key9 => "9",
+        KeyCode::Return => "Enter",
+        KeyCode::Escape => "Esc",
+        KeyCode::Back => "Backspace",
+        KeyCode::Left => "Left Arrow",
+        KeyCode::Right => "Right Arrow",
+        KeyCode::Up => "Up Arrow",
+        KeyCode::Down => "Down Arrow",
+        KeyCode::ShiftLeft => "Left Shift",
+        KeyCode::ShiftRight => "Right Shift",
+        KeyCode::ControlLeft => "Left Ctrl",
+        KeyCode::ControlRight => "Right Ctrl",
+        other => return format!("{:?}", other),
+    };
+    name.to_string()
+}
+
 // Loading Progress
 #[derive(Resource, Default)]
 pub struct LoadingProgress {
@@ -180,6 +480,16 @@ pub enum CharacterType {
 }
 
 impl CharacterType {
+    pub const ALL: [CharacterType; 2] = [CharacterType::Boss3, CharacterType::SwordHero];
+
+    /// Name used in saves and settings
+    pub fn id(&self) -> &'static str {
+        match self {
+            CharacterType::Boss3 => "Boss3",
+            CharacterType::SwordHero => "SwordHero",
+        }
+    }
+
     pub fn model_path(&self) -> &'static str {
         match self {
             CharacterType::Boss3 => "boss3.glb#Scene0",
@@ -209,8 +519,10 @@ impl Default for SelectedCharacter {
 }
 
 // Authentication Resources
-#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+// Always parse through `user_data_from_value` so older JSON gets migrated first
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct UserData {
+    pub schema_version: u32,
     pub user_id: Option<String>,
     pub email: Option<String>,
     pub username: Option<String>,
@@ -218,13 +530,55 @@ pub struct UserData {
     pub preferences: UserPreferences,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+impl UserData {
+    /// A fresh guest profile for Demo Mode
+    pub fn guest() -> Self {
+        Self {
+            user_id: Some(DEMO_USER_ID.to_string()),
+            email: Some("demo@example.com".to_string()),
+            username: Some("DemoPlayer".to_string()),
+            ..default()
+        }
+    }
+}
+
+impl Default for UserData {
+    fn default() -> Self {
+        Self {
+            schema_version: USER_DATA_SCHEMA_VERSION,
+            user_id: None,
+            email: None,
+            username: None,
+            player_stats: PlayerStats::default(),
+            preferences: UserPreferences::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PlayerStats {
     pub level: u32,
     pub total_play_time: f32,
     pub high_score: u32,
     pub achievements: Vec<String>,
     pub unlocked_characters: Vec<String>,
+    /// `Ability::id` of every ability earned so far
+    pub unlocked_abilities: Vec<String>,
+}
+
+impl PlayerStats {
+    pub fn has_ability(&self, ability: Ability) -> bool {
+        self.unlocked_abilities.iter().any(|id| id == ability.id())
+    }
+
+    /// Unlocks every ability the high score has reached, returning the new ones
+    pub fn unlock_abilities(&mut self) -> Vec<Ability> {
+        let earned: Vec<Ability> = Ability::ALL.into_iter()
+            .filter(|ability| !self.has_ability(*ability) && self.high_score >= ability.unlock_score())
+            .collect();
+        self.unlocked_abilities.extend(earned.iter().map(|ability| ability.id().to_string()));
+        earned
+    }
 }
 
 impl Default for PlayerStats {
@@ -235,11 +589,12 @@ impl Default for PlayerStats {
             high_score: 0,
             achievements: Vec::new(),
             unlocked_characters: vec!["Boss3".to_string()], // Default character
+            unlocked_abilities: Vec::new(),
         }
     }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct UserPreferences {
     pub master_volume: f32,
     pub sfx_volume: f32,
@@ -258,18 +613,71 @@ impl Default for UserPreferences {
     }
 }
 
+/// Session token used by "Skip (Demo Mode)"; never sent to the backend
+pub const DEMO_SESSION_TOKEN: &str = "demo_token";
+
+/// User id of the local guest profile that Demo Mode progress is saved under
+pub const DEMO_USER_ID: &str = "demo_user";
+
 #[derive(Resource, Default)]
 pub struct AuthSession {
     pub is_authenticated: bool,
     pub session_token: Option<String>,
     pub expires_at: Option<std::time::SystemTime>,
+    /// True while the backend is unreachable and progress is only saved locally
+    pub offline: bool,
 }
 
 impl AuthSession {
     pub fn is_valid(&self) -> bool {
         self.is_authenticated &&
         self.session_token.is_some() &&
-        self.expires_at.map_or(false, |exp| exp > std::time::SystemTime::now())
+        self.expires_at.is_some_and(|exp| exp > std::time::SystemTime::now())
+    }
+
+    pub fn is_demo(&self) -> bool {
+        self.session_token.as_deref() == Some(DEMO_SESSION_TOKEN)
+    }
+}
+
+// Client-side delay between login attempts after repeated failures
+#[derive(Resource, Default)]
+pub struct LoginBackoff {
+    pub failed_attempts: u32,
+    pub retry_at: Option<std::time::Instant>,
+}
+
+impl LoginBackoff {
+    /// Failures allowed before logins start being delayed
+    const FREE_ATTEMPTS: u32 = 3;
+    const BASE_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+    const MAX_DELAY: std::time::Duration = std::time::Duration::from_secs(60);
+
+    /// Doubles the delay with each failure past the free attempts. A lockout
+    /// reported by the server takes precedence if it is longer.
+    pub fn record_failure(&mut self, server_retry_after: Option<std::time::Duration>) {
+        self.failed_attempts += 1;
+
+        let delay = match self.failed_attempts.checked_sub(Self::FREE_ATTEMPTS) {
+            Some(extra) => Self::BASE_DELAY.saturating_mul(2u32.saturating_pow(extra)).min(Self::MAX_DELAY),
+            None => std::time::Duration::ZERO,
+        };
+        let delay = delay.max(server_retry_after.unwrap_or_default());
+
+        if !delay.is_zero() {
+            self.retry_at = Some(std::time::Instant::now() + delay);
+        }
+    }
+
+    pub fn record_success(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Time left before another login may be attempted
+    pub fn remaining(&self) -> Option<std::time::Duration> {
+        self.retry_at
+            .map(|retry_at| retry_at.saturating_duration_since(std::time::Instant::now()))
+            .filter(|remaining| !remaining.is_zero())
     }
 }
 
@@ -278,25 +686,51 @@ pub struct AuthConfig {
     pub api_base_url: String,
     pub session_duration: std::time::Duration,
     pub auto_save_interval: std::time::Duration,
+    /// Saves for the active profile; pointed at the profile's directory when one is picked
+    pub save_directory: std::path::PathBuf,
+    /// Holds one directory per local profile
+    pub profiles_directory: std::path::PathBuf,
+    /// Where "Export my data" archives are written
+    pub export_directory: std::path::PathBuf,
 }
 
 impl Default for AuthConfig {
     fn default() -> Self {
         Self {
-            api_base_url: "https://api.yourgame.com".to_string(), // Replace with your actual API
+            api_base_url: "http://127.0.0.1:7878".to_string(), // Local backend; replace with your actual API
             session_duration: std::time::Duration::from_secs(24 * 60 * 60), // 24 hours
             auto_save_interval: std::time::Duration::from_secs(30), // 30 seconds
+            save_directory: std::path::PathBuf::from("saves"),
+            profiles_directory: std::path::PathBuf::from("saves/profiles"),
+            export_directory: std::path::PathBuf::from("exports"),
         }
     }
 }
 
+// Async runtime that backend requests are spawned on
+#[derive(Resource)]
+pub struct ApiRuntime(pub tokio::runtime::Runtime);
+
+impl Default for ApiRuntime {
+    fn default() -> Self {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .expect("Failed to start API runtime");
+        Self(runtime)
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct AuthFormData {
     pub email: String,
     pub password: String,
     pub confirm_password: String,
     pub username: String,
+    pub code: String,
     pub error_message: Option<String>,
+    pub info_message: Option<String>,
     pub is_loading: bool,
 }
 
@@ -306,7 +740,9 @@ impl AuthFormData {
         self.password.clear();
         self.confirm_password.clear();
         self.username.clear();
+        self.code.clear();
         self.error_message = None;
+        self.info_message = None;
         self.is_loading = false;
     }
 }
@@ -321,6 +757,7 @@ impl Plugin for ResourcesPlugin {
             .init_resource::<GameStats>()
             .init_resource::<PerformanceMetrics>()
             .init_resource::<InputConfig>()
+            .insert_resource(MovementTuning::load(std::path::Path::new(MOVEMENT_TUNING_PATH)))
             .init_resource::<LoadingProgress>()
             .init_resource::<SelectedCharacter>()
             .init_resource::<PreloadedAnimations>()
@@ -329,6 +766,11 @@ impl Plugin for ResourcesPlugin {
             .init_resource::<UserData>()
             .init_resource::<AuthSession>()
             .init_resource::<AuthConfig>()
-            .init_resource::<AuthFormData>();
+            .init_resource::<AuthFormData>()
+            .init_resource::<ApiRuntime>()
+            .init_resource::<LoginBackoff>()
+            // Cloud sync resources
+            .init_resource::<SyncRecord>()
+            .init_resource::<SyncStatus>();
     }
 }
\ No newline at end of file
@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use super::{AuthSession, UserPreferences};
+
+pub(crate) const PROFILE_FILE: &str = "profile.json";
+
+/// A local player on a shared machine. Each profile has its own directory
+/// holding this file and the `LocalSave`s of everyone who logs in under it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    /// Device preferences, applied to whoever logs in under this profile
+    #[serde(default)]
+    pub preferences: UserPreferences,
+    /// Last login, resumed when the profile is picked again
+    #[serde(default)]
+    pub session: Option<StoredSession>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoredSession {
+    pub user_id: String,
+    pub username: Option<String>,
+    pub session_token: String,
+    /// Seconds since the Unix epoch
+    pub expires_at: u64,
+}
+
+impl StoredSession {
+    pub fn from_auth_session(auth_session: &AuthSession, user_id: &str, username: Option<String>) -> Option<Self> {
+        let expires_at = auth_session.expires_at?.duration_since(UNIX_EPOCH).ok()?.as_secs();
+        Some(Self {
+            user_id: user_id.to_string(),
+            username,
+            session_token: auth_session.session_token.clone()?,
+            expires_at,
+        })
+    }
+
+    pub fn expires_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.expires_at)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at() <= SystemTime::now()
+    }
+}
+
+// The profile picked on the profile screen
+#[derive(Resource, Clone, Debug)]
+pub struct ActiveProfile(pub Profile);
+
+impl Profile {
+    pub fn new(name: &str) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.trim().to_string(),
+            preferences: UserPreferences::default(),
+            session: None,
+        }
+    }
+
+    pub fn directory(profiles_directory: &Path, id: &str) -> PathBuf {
+        profiles_directory.join(id)
+    }
+
+    /// Every profile on this machine, sorted by name
+    pub fn load_all(profiles_directory: &Path) -> Vec<Self> {
+        let Ok(entries) = fs::read_dir(profiles_directory) else {
+            return Vec::new();
+        };
+
+        let mut profiles: Vec<Self> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let path = entry.path().join(PROFILE_FILE);
+                let contents = fs::read_to_string(&path).ok()?;
+                serde_json::from_str(&contents)
+                    .map_err(|e| warn!("Ignoring unreadable profile {}: {}", path.display(), e))
+                    .ok()
+            })
+            .collect();
+
+        profiles.sort_by_key(|profile| profile.name.to_lowercase());
+        profiles
+    }
+
+    pub fn write(&self, profiles_directory: &Path) -> io::Result<()> {
+        let directory = Self::directory(profiles_directory, &self.id);
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        fs::create_dir_all(&directory)?;
+        let path = directory.join(PROFILE_FILE);
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, contents)?;
+        fs::rename(tmp_path, path)
+    }
+
+    /// Removes the profile along with all of its saves
+    pub fn delete(profiles_directory: &Path, id: &str) -> io::Result<()> {
+        match fs::remove_dir_all(Self::directory(profiles_directory, id)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn validate_profile_name(name: &str, profiles: &[Profile], except_id: Option<&str>) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name can't be empty".to_string());
+    }
+    if name.chars().count() > 24 {
+        return Err("Profile name must be 24 characters or fewer".to_string());
+    }
+    let taken = profiles.iter()
+        .any(|profile| Some(profile.id.as_str()) != except_id && profile.name.eq_ignore_ascii_case(name));
+    if taken {
+        return Err("A profile with that name already exists".to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use super::{migrate_save, CharacterType, GameStats, InputConfig, MigrationError, PlayerStats, UserData, PROFILE_FILE, USER_DATA_SCHEMA_VERSION};
+
+// What we know about the server copy of the current user's data
+#[derive(Resource, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct SyncRecord {
+    /// Server revision of the user data at the last successful sync
+    pub last_synced_revision: u64,
+    /// Server copy as of the last successful sync, used as the common ancestor when merging
+    pub base: Option<UserData>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncState {
+    #[default]
+    Idle,
+    Syncing,
+    Synced,
+    Offline,
+    /// The backend no longer accepts our session; progress stays local until the next login
+    SignedOut,
+    /// The backend refused the data or kept reporting conflicts; the next auto-save tries again
+    Failed,
+}
+
+#[derive(Resource, Default)]
+pub struct SyncStatus {
+    pub state: SyncState,
+    pub last_error: Option<String>,
+}
+
+/// The stats of one finished run
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Seconds since the Unix epoch
+    pub finished_at: u64,
+    /// `CharacterType::id` of the character played
+    pub character: String,
+    pub stats: GameStats,
+}
+
+impl RunRecord {
+    pub fn finished_now(character: CharacterType, stats: &GameStats) -> Self {
+        Self {
+            finished_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            character: character.id().to_string(),
+            stats: stats.clone(),
+        }
+    }
+}
+
+/// Everything stored on disk for one user
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LocalSave {
+    pub user_data: UserData,
+    #[serde(default)]
+    pub sync: SyncRecord,
+    /// Session token from the last online login, reused to sync after playing offline
+    #[serde(default)]
+    pub session_token: Option<String>,
+    /// bcrypt hash of the password, so the user can log in while offline
+    #[serde(default)]
+    pub password_hash: Option<String>,
+    /// The user's key bindings, or `None` to use the defaults
+    #[serde(default)]
+    pub input: Option<InputConfig>,
+    /// Every finished run on this machine, oldest first
+    #[serde(default)]
+    pub runs: Vec<RunRecord>,
+}
+
+impl LocalSave {
+    /// A save for data that just came from the server
+    pub fn from_server(user_data: UserData) -> Self {
+        Self {
+            sync: SyncRecord {
+                last_synced_revision: 0,
+                base: Some(user_data.clone()),
+            },
+            user_data,
+            session_token: None,
+            password_hash: None,
+            input: None,
+            runs: Vec::new(),
+        }
+    }
+
+    pub fn path(save_directory: &Path, user_id: &str) -> PathBuf {
+        let file_name: String = user_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        save_directory.join(format!("{}.json", file_name))
+    }
+
+    pub fn load(save_directory: &Path, user_id: &str) -> Option<Self> {
+        Self::read(&Self::path(save_directory, user_id))
+    }
+
+    pub fn find_by_email(save_directory: &Path, email: &str) -> Option<Self> {
+        Self::load_all(save_directory)
+            .into_iter()
+            .find(|save| save.user_data.email.as_deref()
+                .is_some_and(|saved| saved.eq_ignore_ascii_case(email.trim())))
+    }
+
+    /// Every save in the directory
+    pub fn load_all(save_directory: &Path) -> Vec<Self> {
+        let Ok(entries) = fs::read_dir(save_directory) else {
+            return Vec::new();
+        };
+
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+            .filter(|path| !path.ends_with(PROFILE_FILE))
+            .filter_map(|path| Self::read(&path))
+            .collect()
+    }
+
+    /// Parses a save file written by any supported version of the game
+    pub fn from_json(contents: &str) -> Result<Self, MigrationError> {
+        let mut value = serde_json::from_str(contents)?;
+        migrate_save(&mut value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    fn read(path: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(path).ok()?;
+        match Self::from_json(&contents) {
+            Ok(save) => Some(save),
+            Err(e) => {
+                warn!("Ignoring unreadable save file {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub fn write(&self, save_directory: &Path) -> io::Result<()> {
+        let user_id = self.user_data.user_id.as_deref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "save has no user id"))?;
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        fs::create_dir_all(save_directory)?;
+        let path = Self::path(save_directory, user_id);
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, contents)?;
+        fs::rename(tmp_path, path)
+    }
+
+    /// Writes the current progress, keeping any stored credentials
+    pub fn store_progress(save_directory: &Path, user_data: &UserData, sync: &SyncRecord) -> io::Result<()> {
+        let user_id = user_data.user_id.as_deref().unwrap_or_default();
+        let mut save = Self::load(save_directory, user_id)
+            .unwrap_or_else(|| Self::from_server(user_data.clone()));
+        save.user_data = user_data.clone();
+        save.sync = sync.clone();
+        save.write(save_directory)
+    }
+
+    /// Writes the user's key bindings, keeping everything else in the save
+    pub fn store_input(save_directory: &Path, user_data: &UserData, input: &InputConfig) -> io::Result<()> {
+        let user_id = user_data.user_id.as_deref().unwrap_or_default();
+        let mut save = Self::load(save_directory, user_id)
+            .unwrap_or_else(|| Self::from_server(user_data.clone()));
+        save.input = Some(input.clone());
+        save.write(save_directory)
+    }
+
+    /// Adds a finished run to the user's history, keeping everything else in the save
+    pub fn store_run(save_directory: &Path, user_data: &UserData, run: RunRecord) -> io::Result<()> {
+        let user_id = user_data.user_id.as_deref().unwrap_or_default();
+        let mut save = Self::load(save_directory, user_id)
+            .unwrap_or_else(|| Self::from_server(user_data.clone()));
+        save.runs.push(run);
+        save.write(save_directory)
+    }
+
+    pub fn remove(save_directory: &Path, user_id: &str) -> io::Result<()> {
+        match fs::remove_file(Self::path(save_directory, user_id)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn verify_password(&self, password: &str) -> bool {
+        self.password_hash.as_deref()
+            .is_some_and(|hash| bcrypt::verify(password, hash).unwrap_or(false))
+    }
+}
+
+/// Reconciles the local copy of a user's data with the server copy.
+///
+/// `base` is the server copy from the last successful sync. Progress counters
+/// only ever grow, so `level` and `high_score` take the maximum, lists take the
+/// union, and play time recorded locally since `base` is added on top of the
+/// server's total. Preferences are per device and keep the local values.
+pub fn merge_user_data(local: &UserData, remote: &UserData, base: Option<&UserData>) -> UserData {
+    let base_play_time = base.map_or(0.0, |base| base.player_stats.total_play_time);
+    let local_play_time = (local.player_stats.total_play_time - base_play_time).max(0.0);
+
+    UserData {
+        schema_version: USER_DATA_SCHEMA_VERSION,
+        user_id: remote.user_id.clone().or_else(|| local.user_id.clone()),
+        email: remote.email.clone().or_else(|| local.email.clone()),
+        username: remote.username.clone().or_else(|| local.username.clone()),
+        player_stats: PlayerStats {
+            level: local.player_stats.level.max(remote.player_stats.level),
+            total_play_time: remote.player_stats.total_play_time + local_play_time,
+            high_score: local.player_stats.high_score.max(remote.player_stats.high_score),
+            achievements: union(&remote.player_stats.achievements, &local.player_stats.achievements),
+            unlocked_characters: union(
+                &remote.player_stats.unlocked_characters,
+                &local.player_stats.unlocked_characters,
+            ),
+            unlocked_abilities: union(
+                &remote.player_stats.unlocked_abilities,
+                &local.player_stats.unlocked_abilities,
+            ),
+        },
+        preferences: local.preferences.clone(),
+    }
+}
+
+fn union(first: &[String], second: &[String]) -> Vec<String> {
+    let mut merged = first.to_vec();
+    for item in second {
+        if !merged.contains(item) {
+            merged.push(item.clone());
+        }
+    }
+    merged
+}
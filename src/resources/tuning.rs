@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+use super::CharacterType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const MOVEMENT_TUNING_PATH: &str = "assets/tuning/movement.json";
+
+/// How the player body is simulated
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ControllerMode {
+    /// A dynamic rigid body pushed around by velocity, with the solver resolving contacts
+    Dynamic,
+    /// A kinematic body moved by Rapier's character controller, which slides
+    /// along walls, climbs steps and snaps to the ground
+    Kinematic,
+}
+
+impl ControllerMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ControllerMode::Dynamic => "Dynamic",
+            ControllerMode::Kinematic => "Kinematic",
+        }
+    }
+}
+
+/// How the player's running, jumping and air moves feel. Rates are in units per
+/// second squared and applied with the frame time, so they behave the same
+/// at any frame rate. Loaded from `MOVEMENT_TUNING_PATH` at startup and
+/// editable live from the debug overlay.
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovementTuning {
+    /// Speeding up on the ground
+    pub ground_acceleration: f32,
+    /// Slowing down on the ground once input is released
+    pub ground_deceleration: f32,
+    /// Reversing direction on the ground
+    pub ground_turn_acceleration: f32,
+    /// Speeding up or reversing in the air
+    pub air_acceleration: f32,
+    /// Slowing down in the air once input is released
+    pub air_deceleration: f32,
+    /// Shapes analog input: stick deflection is raised to this power, so
+    /// values above 1 give finer control at low deflection
+    pub input_exponent: f32,
+    /// Upward speed at the start of a jump
+    pub jump_velocity: f32,
+    /// How long, in seconds, a jump press is remembered before landing
+    pub jump_buffer_time: f32,
+    /// How long, in seconds, the player can still jump after leaving the ground
+    pub coyote_time: f32,
+    /// Upward speed is multiplied by this when jump is released early
+    pub jump_cut_multiplier: f32,
+    /// Fastest the player falls while sliding down a wall
+    pub wall_slide_speed: f32,
+    /// Speed away from the wall at the start of a wall jump
+    pub wall_jump_horizontal: f32,
+    /// Upward speed at the start of a wall jump
+    pub wall_jump_vertical: f32,
+    /// How long, in seconds, movement input is ignored after a wall jump
+    pub wall_jump_lockout: f32,
+    /// How long, in seconds, climbing up from a ledge takes
+    pub ledge_climb_time: f32,
+    /// Jumps allowed in the air once double jump is unlocked
+    pub air_jumps: u32,
+    /// Upward speed at the start of an air jump
+    pub double_jump_velocity: f32,
+    /// Air dashes allowed before landing
+    pub air_dashes: u32,
+    /// Horizontal speed during an air dash
+    pub air_dash_speed: f32,
+    /// How long, in seconds, an air dash lasts
+    pub air_dash_time: f32,
+    /// Falling speed during a ground pound
+    pub ground_pound_speed: f32,
+    /// Player body used unless the character has its own entry below
+    pub controller: ControllerMode,
+    /// Per-character overrides of `controller`, keyed by `CharacterType::id`
+    pub character_controllers: BTreeMap<String, ControllerMode>,
+    /// Steepest slope, in degrees, the player can stand on or walk up
+    pub max_slope_angle: f32,
+    /// Tallest ledge the kinematic controller steps up without jumping
+    pub step_height: f32,
+    /// Gap below the feet the kinematic controller pulls down across, so
+    /// walking off small drops and down steps stays grounded
+    pub snap_to_ground: f32,
+    /// Ground acceleration and deceleration on ice are multiplied by this
+    pub ice_traction: f32,
+    /// Top speed on sticky ground is multiplied by this
+    pub sticky_speed_multiplier: f32,
+}
+
+impl Default for MovementTuning {
+    fn default() -> Self {
+        Self {
+            ground_acceleration: 35.0,
+            ground_deceleration: 50.0,
+            ground_turn_acceleration: 70.0,
+            air_acceleration: 18.0,
+            air_deceleration: 4.0,
+            input_exponent: 1.5,
+            jump_velocity: 8.0,
+            jump_buffer_time: 0.12,
+            coyote_time: 0.1,
+            jump_cut_multiplier: 0.45,
+            wall_slide_speed: 2.0,
+            wall_jump_horizontal: 6.0,
+            wall_jump_vertical: 8.5,
+            wall_jump_lockout: 0.2,
+            ledge_climb_time: 0.35,
+            air_jumps: 1,
+            double_jump_velocity: 7.0,
+            air_dashes: 1,
+            air_dash_speed: 14.0,
+            air_dash_time: 0.18,
+            ground_pound_speed: 20.0,
+            controller: ControllerMode::Dynamic,
+            character_controllers: BTreeMap::new(),
+            max_slope_angle: 45.0,
+            step_height: 0.25,
+            snap_to_ground: 0.2,
+            ice_traction: 0.15,
+            sticky_speed_multiplier: 0.45,
+        }
+    }
+}
+
+impl MovementTuning {
+    /// Reads the tuning file, falling back to the defaults if it's missing or broken
+    pub fn load(path: &Path) -> Self {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    warn!("Failed to read {}: {}", path.display(), e);
+                }
+                return Self::default();
+            }
+        };
+
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Ignoring invalid movement tuning in {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)
+    }
+
+    /// Player body for `character`, from its override or the global setting
+    pub fn controller_for(&self, character: CharacterType) -> ControllerMode {
+        self.character_controllers.get(character.id()).copied().unwrap_or(self.controller)
+    }
+
+    /// Lowest surface normal `y` that still counts as ground
+    pub fn min_ground_normal_y(&self) -> f32 {
+        self.max_slope_angle.to_radians().cos()
+    }
+
+    /// Target horizontal speed for an input from -1 to 1
+    pub fn target_speed(&self, input: f32, max_speed: f32) -> f32 {
+        let input = input.clamp(-1.0, 1.0);
+        input.abs().powf(self.input_exponent) * input.signum() * max_speed
+    }
+
+    /// Moves `current` toward `target` by the rate that fits the situation
+    pub fn next_velocity(&self, current: f32, target: f32, grounded: bool, delta_seconds: f32) -> f32 {
+        let reversing = current != 0.0 && target != 0.0 && current.signum() != target.signum();
+        let slowing = target.abs() < current.abs() && !reversing;
+
+        let rate = match (grounded, reversing, slowing) {
+            (true, true, _) => self.ground_turn_acceleration,
+            (true, false, true) => self.ground_deceleration,
+            (true, false, false) => self.ground_acceleration,
+            (false, _, true) => self.air_deceleration,
+            (false, _, false) => self.air_acceleration,
+        };
+
+        let max_change = rate * delta_seconds;
+        current + (target - current).clamp(-max_change, max_change)
+    }
+}
@@ -4,6 +4,7 @@ use bevy::prelude::*;
 pub enum GameState {
     #[default]
     Loading,
+    ProfileSelection,
     Authentication,
     CharacterSelection,
     MainMenu,
@@ -25,6 +26,9 @@ pub enum AuthState {
     #[default]
     Login,
     SignUp,
+    ForgotPassword,
+    ResetPassword,
+    VerifyEmail,
     Authenticating,
     Authenticated,
 }
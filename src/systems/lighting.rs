@@ -18,7 +18,7 @@ pub fn setup_lighting(
     });
 
     // Create detailed lamp posts positioned around the scene
-    let lamp_post_positions = vec![
+    let lamp_post_positions = [
         Vec3::new(-15.0, 0.0, -1.0),
         Vec3::new(15.0, 0.0, -1.0),
         Vec3::new(2.0, 0.0, 2.0),
@@ -63,7 +63,7 @@ pub fn setup_lighting(
     }
 
     // Create floor lights positioned around the scene
-    let floor_light_positions = vec![
+    let floor_light_positions = [
         (Vec3::new(-4.0, 0.05, 2.0), FloorLightType::Accent),
         (Vec3::new(4.0, 0.05, 2.0), FloorLightType::Accent),
         (Vec3::new(-4.0, 0.05, -2.0), FloorLightType::Accent),
@@ -125,7 +125,6 @@ pub fn setup_lighting(
             shadows_enabled: true,
             shadow_depth_bias: 0.02, // Reduce shadow acne
             shadow_normal_bias: 0.6, // Reduce shadow acne
-            ..default()
         },
         transform: Transform::from_rotation(Quat::from_euler(
             EulerRot::XYZ,
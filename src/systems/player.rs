@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use crate::components::Player;
 use crate::plugins::physics::Platform;
-use crate::resources::PlayerAnimations;
+use crate::resources::{ActionState, InputAction, PlayerAnimations};
 
 pub fn spawn_player(
     mut commands: Commands,
@@ -45,7 +45,8 @@ pub fn spawn_player(
                 is_dive_rolling: false,
                 flip_direction: Vec3::ZERO,
                 facing_left: false,
-                is_falling: false,
+                is_dead: false,
+                is_hanging: false,
             },
         )).with_children(|parent| {
             // Spawn the mesh as a child with an offset
@@ -66,7 +67,7 @@ pub fn spawn_player(
 }
 
 pub fn move_player(
-    keyboard: Res<Input<KeyCode>>,
+    actions: Res<ActionState>,
     mut player_query: Query<(Entity, &mut Transform, &mut Velocity, &mut Player), Without<Platform>>,
     rapier_context: Res<RapierContext>,
 ) {
@@ -106,13 +107,13 @@ pub fn move_player(
         let mut direction = 0.0;
 
         // Get input direction
-        if keyboard.pressed(KeyCode::A) {
+        if actions.pressed(InputAction::MoveLeft) {
             direction -= 1.0;
             player.is_moving = true;
             player.facing_left = true;
             transform.rotation = Quat::from_rotation_y(-std::f32::consts::PI);
         }
-        if keyboard.pressed(KeyCode::D) {
+        if actions.pressed(InputAction::MoveRight) {
             direction += 1.0;
             player.is_moving = true;
             player.facing_left = false;
@@ -133,12 +134,12 @@ pub fn move_player(
         }
 
         // Handle jumping when on ground
-        if player.is_grounded && keyboard.just_pressed(KeyCode::Space) {
+        if player.is_grounded && actions.just_pressed(InputAction::Jump) {
             velocity.linvel.y = 10.0; // Jump velocity
         }
 
         // Handle front flip trigger
-        if keyboard.just_pressed(KeyCode::W) && !player.is_front_flipping && player.is_grounded {
+        if actions.just_pressed(InputAction::FrontFlip) && !player.is_front_flipping && player.is_grounded {
             player.is_front_flipping = true;
 
             player.flip_direction = if player.facing_left {
@@ -150,7 +151,7 @@ pub fn move_player(
         }
 
         // Handle dive roll trigger
-        if keyboard.just_pressed(KeyCode::S) && !player.is_dive_rolling && player.is_grounded {
+        if actions.just_pressed(InputAction::DiveRoll) && !player.is_dive_rolling && player.is_grounded {
             player.is_dive_rolling = true;
 
             player.flip_direction = if player.facing_left {
@@ -158,7 +159,6 @@ pub fn move_player(
             } else {
                 Vec3::new(0.5, 0.0, 0.0)
             };
-            return;
         }
     }
 }
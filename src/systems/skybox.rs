@@ -29,7 +29,7 @@ pub fn reinterpret_cubemap(
     mut cubemap: ResMut<SkyCubeMap>,
     mut commands: Commands,
     mut skyboxes: Query<&mut Skybox>,
-    camera_query: Query<Entity, (With<Camera3d>, With<FollowCamera>, Without<Skybox>)>,
+    camera_query: Query<Entity, (With<FollowCamera>, Without<Skybox>)>,
 ) {
     if !cubemap.loaded && asset_server.load_state(&cubemap.image) == LoadState::Loaded {
         cubemap.loaded = true;
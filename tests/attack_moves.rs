@@ -0,0 +1,69 @@
+//! Combo rules for the melee moves. The timings are placeholder frame counts,
+//! so these check the rules relative to each move's own frames.
+
+use sidequest::components::{AttackMove, ATTACK_FPS};
+
+const GROUND_MOVES: [AttackMove; 3] = [AttackMove::Slash, AttackMove::Backhand, AttackMove::Finisher];
+const ALL_MOVES: [AttackMove; 4] = [AttackMove::Slash, AttackMove::Backhand, AttackMove::Finisher, AttackMove::AirSlash];
+
+// Seconds at the start of `frame`, nudged in so float error can't land on the previous frame
+fn at_frame(frame: u32) -> f32 {
+    (frame as f32 + 0.01) / ATTACK_FPS
+}
+
+#[test]
+fn ground_combo_runs_slash_backhand_finisher_and_stops() {
+    assert_eq!(AttackMove::Slash.next(), Some(AttackMove::Backhand));
+    assert_eq!(AttackMove::Backhand.next(), Some(AttackMove::Finisher));
+    assert_eq!(AttackMove::Finisher.next(), None);
+    assert_eq!(AttackMove::AirSlash.next(), None);
+
+    assert!(GROUND_MOVES.iter().all(|attack| !attack.is_air()));
+    assert!(AttackMove::AirSlash.is_air());
+}
+
+#[test]
+fn hitbox_is_out_only_during_the_active_frames() {
+    for attack in ALL_MOVES {
+        let (start, end) = attack.active_frames();
+        assert!(start < end && end <= attack.frames(), "{:?} has active frames outside the move", attack);
+
+        assert!(!attack.is_active_at(0.0), "{:?}", attack);
+        assert!(!attack.is_active_at(at_frame(start - 1)), "{:?}", attack);
+        assert!(attack.is_active_at(at_frame(start)), "{:?}", attack);
+        assert!(attack.is_active_at(at_frame(end - 1)), "{:?}", attack);
+        assert!(!attack.is_active_at(at_frame(end)), "{:?}", attack);
+        assert!(!attack.is_active_at(attack.duration()), "{:?}", attack);
+    }
+}
+
+#[test]
+fn presses_before_the_hitbox_are_not_chained() {
+    for attack in [AttackMove::Slash, AttackMove::Backhand] {
+        let (start, _) = attack.active_frames();
+        assert!(!attack.can_chain_at(0.0), "{:?}", attack);
+        assert!(!attack.can_chain_at(at_frame(start - 1)), "{:?}", attack);
+        assert!(attack.can_chain_at(at_frame(start)), "{:?}", attack);
+        // Late presses still chain, right up to the end of the move
+        assert!(attack.can_chain_at(at_frame(attack.frames() - 1)), "{:?}", attack);
+    }
+}
+
+#[test]
+fn combo_enders_never_chain() {
+    for attack in [AttackMove::Finisher, AttackMove::AirSlash] {
+        for frame in 0..attack.frames() {
+            assert!(!attack.can_chain_at(at_frame(frame)), "{:?} chained at frame {}", attack, frame);
+        }
+    }
+}
+
+#[test]
+fn duration_matches_the_frame_count() {
+    for attack in ALL_MOVES {
+        assert!((attack.duration() * ATTACK_FPS - attack.frames() as f32).abs() < 1e-4, "{:?}", attack);
+    }
+    // The finisher is the slowest and hardest hitting
+    assert!(GROUND_MOVES.iter().all(|attack| attack.duration() <= AttackMove::Finisher.duration()));
+    assert!(GROUND_MOVES.iter().all(|attack| attack.damage() <= AttackMove::Finisher.damage()));
+}
@@ -0,0 +1,155 @@
+//! The rules the auth form checks before sending anything, and the delay it
+//! adds between login attempts after repeated failures.
+
+use sidequest::api::validation::{
+    password_strength, validate_email, validate_existing_email, validate_password, validate_username,
+    PasswordStrength, MAX_USERNAME_LENGTH,
+};
+use sidequest::resources::LoginBackoff;
+use std::time::Duration;
+
+fn check(rule: fn(&str) -> Result<(), String>, cases: &[(&str, bool)]) {
+    for (input, valid) in cases {
+        assert_eq!(rule(input).is_ok(), *valid, "{:?} should be {}", input, if *valid { "valid" } else { "invalid" });
+    }
+}
+
+#[test]
+fn signup_email_must_have_a_dotted_ascii_domain() {
+    check(validate_email, &[
+        ("player@example.com", true),
+        ("  player@example.com  ", true),
+        ("first.last+tag@mail.example.co.uk", true),
+        ("a@my-domain.org", true),
+        ("", false),
+        ("   ", false),
+        ("player", false),
+        ("player@", false),
+        ("@example.com", false),
+        ("player@localhost", false),
+        ("player@exämple.com", false),
+        ("a@b@example.com", false),
+        ("player@-example.com", false),
+        ("player@example-.com", false),
+        ("player@example..com", false),
+        ("pla yer@example.com", false),
+    ]);
+}
+
+#[test]
+fn existing_email_only_needs_an_at_sign() {
+    check(validate_existing_email, &[
+        ("player@example.com", true),
+        ("user@localhost", true),
+        ("player@exämple.com", true),
+        ("  player@example.com", true),
+        ("", false),
+        ("   ", false),
+        ("player", false),
+    ]);
+}
+
+#[test]
+fn username_length_and_characters() {
+    let longest = "a".repeat(MAX_USERNAME_LENGTH);
+    let too_long = "a".repeat(MAX_USERNAME_LENGTH + 1);
+    check(validate_username, &[
+        ("abc", true),
+        ("Player_1", true),
+        ("dash-er", true),
+        ("9lives", true),
+        (&longest, true),
+        ("", false),
+        ("ab", false),
+        (&too_long, false),
+        ("_player", false),
+        ("-player", false),
+        ("two words", false),
+        ("émile", false),
+        ("name!", false),
+    ]);
+}
+
+#[test]
+fn password_needs_length_and_variety() {
+    check(validate_password, &[
+        ("abcdefg1", true),
+        ("abcdefghijkl", true),
+        ("Correct-Horse-9", true),
+        ("", false),
+        ("Ab1!", false),
+        ("abcdefgh", false),
+        ("12345678", false),
+    ]);
+}
+
+#[test]
+fn password_strength_scores_length_and_variety() {
+    let cases = [
+        ("", PasswordStrength::Weak),
+        ("abcdefg", PasswordStrength::Weak),
+        ("abcdefgh", PasswordStrength::Weak),
+        ("Ab1!", PasswordStrength::Fair),
+        ("abcdefg1", PasswordStrength::Fair),
+        ("abcdefghijkl", PasswordStrength::Fair),
+        ("Abcdefg1", PasswordStrength::Good),
+        ("abcdefghijk1", PasswordStrength::Good),
+        ("Abcdefghijk1", PasswordStrength::Strong),
+    ];
+    for (password, expected) in cases {
+        assert_eq!(password_strength(password), expected, "strength of {:?}", password);
+    }
+
+    assert_eq!(PasswordStrength::Weak.fraction(), 0.25);
+    assert_eq!(PasswordStrength::Strong.fraction(), 1.0);
+}
+
+#[test]
+fn login_backoff_starts_after_the_free_attempts_and_doubles() {
+    // The first failures retry straight away; the third one earns a delay
+    let mut backoff = LoginBackoff::default();
+    for _ in 0..2 {
+        backoff.record_failure(None);
+        assert_eq!(backoff.remaining(), None);
+    }
+
+    let expected_delays = [2, 4, 8, 16, 32, 60, 60];
+    for seconds in expected_delays {
+        backoff.record_failure(None);
+        let remaining = backoff.remaining().expect("login should be delayed");
+        assert!(remaining <= Duration::from_secs(seconds), "{:?} > {}s", remaining, seconds);
+        assert!(remaining > Duration::from_secs(seconds - 1), "{:?} < {}s", remaining, seconds - 1);
+    }
+}
+
+#[test]
+fn login_backoff_prefers_a_longer_server_lockout() {
+    let mut backoff = LoginBackoff::default();
+    backoff.record_failure(Some(Duration::from_secs(120)));
+    assert!(backoff.remaining().unwrap() > Duration::from_secs(119));
+
+    // A shorter server lockout doesn't cut the client delay short
+    let mut backoff = LoginBackoff::default();
+    for _ in 0..4 {
+        backoff.record_failure(Some(Duration::from_secs(1)));
+    }
+    assert!(backoff.remaining().unwrap() > Duration::from_secs(3));
+}
+
+#[test]
+fn login_backoff_clears_on_success() {
+    let mut backoff = LoginBackoff::default();
+    for _ in 0..6 {
+        backoff.record_failure(None);
+    }
+    assert!(backoff.remaining().is_some());
+
+    backoff.record_success();
+    assert_eq!(backoff.remaining(), None);
+    assert_eq!(backoff.failed_attempts, 0);
+
+    // The free attempts are available again
+    backoff.record_failure(None);
+    backoff.record_failure(None);
+    assert_eq!(backoff.remaining(), None);
+}
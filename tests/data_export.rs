@@ -0,0 +1,62 @@
+//! "Export My Data" covers everything kept for each account on this machine,
+//! including the history of finished runs, and never the stored credentials.
+
+use sidequest::resources::{
+    CharacterType, DataExport, GameStats, LocalSave, Profile, RunRecord, SyncRecord, UserData,
+};
+
+fn user(user_id: &str) -> UserData {
+    UserData {
+        user_id: Some(user_id.to_string()),
+        ..UserData::default()
+    }
+}
+
+fn stats(coins_collected: u32) -> GameStats {
+    GameStats {
+        play_time: 42.0,
+        coins_collected,
+        ..GameStats::default()
+    }
+}
+
+#[test]
+fn finished_runs_are_kept_in_order_alongside_progress() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut player = user("player");
+
+    LocalSave::store_run(dir.path(), &player, RunRecord::finished_now(CharacterType::Boss3, &stats(3))).unwrap();
+    player.player_stats.high_score = 7;
+    LocalSave::store_progress(dir.path(), &player, &SyncRecord::default()).unwrap();
+    LocalSave::store_run(dir.path(), &player, RunRecord::finished_now(CharacterType::SwordHero, &stats(7))).unwrap();
+
+    let save = LocalSave::load(dir.path(), "player").unwrap();
+    assert_eq!(save.user_data.player_stats.high_score, 7);
+    let runs: Vec<_> = save.runs.iter()
+        .map(|run| (run.character.as_str(), run.stats.coins_collected))
+        .collect();
+    assert_eq!(runs, [("Boss3", 3), ("SwordHero", 7)]);
+}
+
+#[test]
+fn export_includes_run_history_but_not_credentials() {
+    let dir = tempfile::tempdir().unwrap();
+    let player = user("player");
+
+    let mut save = LocalSave::from_server(player.clone());
+    save.session_token = Some("secret-token".to_string());
+    save.password_hash = Some("secret-hash".to_string());
+    save.write(dir.path()).unwrap();
+    LocalSave::store_run(dir.path(), &player, RunRecord::finished_now(CharacterType::Boss3, &stats(5))).unwrap();
+
+    let export = DataExport::new(&Profile::new("Player 1"), LocalSave::load_all(dir.path()));
+    assert_eq!(export.accounts.len(), 1);
+    assert_eq!(export.accounts[0].runs.len(), 1);
+    assert_eq!(export.accounts[0].runs[0].stats.coins_collected, 5);
+
+    let export_dir = tempfile::tempdir().unwrap();
+    let contents = std::fs::read_to_string(export.write(export_dir.path()).unwrap()).unwrap();
+    assert!(contents.contains("\"coins_collected\": 5"));
+    assert!(!contents.contains("secret-token"));
+    assert!(!contents.contains("secret-hash"));
+}
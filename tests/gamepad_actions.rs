@@ -0,0 +1,166 @@
+//! Keyboard and gamepad input must both reach gameplay as the same
+//! actions. These run the action mapping headless and feed it synthetic
+//! device events, the same way Bevy's gilrs backend would.
+
+use bevy::input::gamepad::{
+    GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadConnection, GamepadConnectionEvent,
+    GamepadEvent, GamepadInfo,
+};
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use sidequest::plugins::ActionMappingPlugin;
+use sidequest::resources::{ActionState, InputAction, InputConfig};
+
+const GAMEPAD: Gamepad = Gamepad { id: 0 };
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, bevy::input::InputPlugin, ActionMappingPlugin));
+    app.world.send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(
+        GAMEPAD,
+        GamepadConnection::Connected(GamepadInfo { name: "Test Pad".to_string() }),
+    )));
+    app.update();
+    app
+}
+
+fn button(app: &mut App, button_type: GamepadButtonType, value: f32) {
+    app.world.send_event(GamepadEvent::Button(GamepadButtonChangedEvent::new(GAMEPAD, button_type, value)));
+    app.update();
+}
+
+fn axis(app: &mut App, axis_type: GamepadAxisType, value: f32) {
+    app.world.send_event(GamepadEvent::Axis(GamepadAxisChangedEvent::new(GAMEPAD, axis_type, value)));
+    app.update();
+}
+
+fn key(app: &mut App, key_code: KeyCode, state: ButtonState) {
+    app.world.send_event(KeyboardInput {
+        scan_code: 0,
+        key_code: Some(key_code),
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+    app.update();
+}
+
+fn actions(app: &App) -> &ActionState {
+    app.world.resource::<ActionState>()
+}
+
+#[test]
+fn face_buttons_trigger_jump_flip_and_dive_roll() {
+    let mut app = app();
+
+    for (button_type, action) in [
+        (GamepadButtonType::South, InputAction::Jump),
+        (GamepadButtonType::West, InputAction::FrontFlip),
+        (GamepadButtonType::East, InputAction::DiveRoll),
+    ] {
+        button(&mut app, button_type, 1.0);
+        assert!(actions(&app).just_pressed(action), "{:?} should trigger {:?}", button_type, action);
+        button(&mut app, button_type, 0.0);
+        assert!(!actions(&app).pressed(action));
+    }
+}
+
+#[test]
+fn north_button_attacks() {
+    let mut app = app();
+
+    button(&mut app, GamepadButtonType::North, 1.0);
+    assert!(actions(&app).just_pressed(InputAction::Attack));
+    button(&mut app, GamepadButtonType::North, 0.0);
+    assert!(!actions(&app).pressed(InputAction::Attack));
+}
+
+#[test]
+fn held_button_is_only_just_pressed_once() {
+    let mut app = app();
+
+    button(&mut app, GamepadButtonType::South, 1.0);
+    assert!(actions(&app).just_pressed(InputAction::Jump));
+
+    app.update();
+    assert!(actions(&app).pressed(InputAction::Jump));
+    assert!(!actions(&app).just_pressed(InputAction::Jump));
+}
+
+#[test]
+fn start_pauses() {
+    let mut app = app();
+
+    button(&mut app, GamepadButtonType::Start, 1.0);
+    assert!(actions(&app).just_pressed(InputAction::Pause));
+}
+
+#[test]
+fn left_stick_gives_analog_movement() {
+    let mut app = app();
+
+    axis(&mut app, GamepadAxisType::LeftStickX, -0.6);
+    let movement = actions(&app).movement;
+    assert!(movement < 0.0 && movement > -1.0, "expected partial left movement, got {}", movement);
+    assert!(actions(&app).pressed(InputAction::MoveLeft));
+
+    axis(&mut app, GamepadAxisType::LeftStickX, 1.0);
+    assert_eq!(actions(&app).movement, 1.0);
+    assert!(actions(&app).just_pressed(InputAction::MoveRight));
+}
+
+#[test]
+fn left_stick_inside_deadzone_is_ignored() {
+    let mut app = app();
+
+    axis(&mut app, GamepadAxisType::LeftStickX, 0.1);
+    assert_eq!(actions(&app).movement, 0.0);
+    assert!(!actions(&app).pressed(InputAction::MoveRight));
+}
+
+#[test]
+fn dpad_moves_at_full_speed() {
+    let mut app = app();
+
+    button(&mut app, GamepadButtonType::DPadLeft, 1.0);
+    assert_eq!(actions(&app).movement, -1.0);
+    assert!(actions(&app).just_pressed(InputAction::MoveLeft));
+}
+
+#[test]
+fn right_stick_moves_free_camera() {
+    let mut app = app();
+
+    axis(&mut app, GamepadAxisType::RightStickX, 1.0);
+    axis(&mut app, GamepadAxisType::RightStickY, -1.0);
+    let camera = actions(&app).camera;
+    assert_eq!(camera.x, 1.0);
+    assert_eq!(camera.z, -1.0);
+    assert_eq!(camera.y, 0.0);
+}
+
+#[test]
+fn keyboard_uses_current_bindings() {
+    let mut app = app();
+
+    key(&mut app, KeyCode::Space, ButtonState::Pressed);
+    assert!(actions(&app).just_pressed(InputAction::Jump));
+    key(&mut app, KeyCode::Space, ButtonState::Released);
+
+    app.world.resource_mut::<InputConfig>().rebind(InputAction::Jump, KeyCode::K);
+    key(&mut app, KeyCode::K, ButtonState::Pressed);
+    assert!(actions(&app).just_pressed(InputAction::Jump));
+    assert!(!actions(&app).pressed(InputAction::CameraBackward));
+}
+
+#[test]
+fn keyboard_and_gamepad_combine() {
+    let mut app = app();
+
+    key(&mut app, KeyCode::A, ButtonState::Pressed);
+    assert_eq!(actions(&app).movement, -1.0);
+
+    // The stick takes over from the keys while it's pushed
+    axis(&mut app, GamepadAxisType::LeftStickX, 0.6);
+    assert!(actions(&app).movement > 0.0);
+}
@@ -0,0 +1,91 @@
+//! The acceleration curves behind running. Rates come from `MovementTuning`,
+//! so these use the defaults and check each case picks the right one.
+
+use sidequest::resources::MovementTuning;
+
+const MAX_SPEED: f32 = 10.0;
+const FRAME: f32 = 0.1;
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-4, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn full_input_targets_max_speed_in_either_direction() {
+    let tuning = MovementTuning::default();
+    assert_close(tuning.target_speed(1.0, MAX_SPEED), MAX_SPEED);
+    assert_close(tuning.target_speed(-1.0, MAX_SPEED), -MAX_SPEED);
+    assert_close(tuning.target_speed(0.0, MAX_SPEED), 0.0);
+
+    // Out of range input is clamped
+    assert_close(tuning.target_speed(3.0, MAX_SPEED), MAX_SPEED);
+    assert_close(tuning.target_speed(-3.0, MAX_SPEED), -MAX_SPEED);
+}
+
+#[test]
+fn analog_exponent_shapes_partial_input() {
+    let tuning = MovementTuning { input_exponent: 2.0, ..MovementTuning::default() };
+    assert_close(tuning.target_speed(0.5, MAX_SPEED), 2.5);
+    assert_close(tuning.target_speed(-0.5, MAX_SPEED), -2.5);
+
+    let linear = MovementTuning { input_exponent: 1.0, ..MovementTuning::default() };
+    assert_close(linear.target_speed(0.5, MAX_SPEED), 5.0);
+
+    // A larger exponent gives finer control near the center
+    let default = MovementTuning::default();
+    assert!(default.target_speed(0.3, MAX_SPEED) < linear.target_speed(0.3, MAX_SPEED));
+}
+
+#[test]
+fn grounded_acceleration_speeds_up_without_overshooting() {
+    let tuning = MovementTuning::default();
+    let step = tuning.ground_acceleration * FRAME;
+
+    assert_close(tuning.next_velocity(0.0, MAX_SPEED, true, FRAME), step);
+    assert_close(tuning.next_velocity(0.0, -MAX_SPEED, true, FRAME), -step);
+    assert_close(tuning.next_velocity(MAX_SPEED - 0.5, MAX_SPEED, true, FRAME), MAX_SPEED);
+}
+
+#[test]
+fn releasing_input_decelerates() {
+    let tuning = MovementTuning::default();
+
+    assert_close(tuning.next_velocity(MAX_SPEED, 0.0, true, FRAME), MAX_SPEED - tuning.ground_deceleration * FRAME);
+    assert_close(tuning.next_velocity(-MAX_SPEED, 0.0, true, FRAME), -MAX_SPEED + tuning.ground_deceleration * FRAME);
+    assert_close(tuning.next_velocity(MAX_SPEED, 0.0, false, FRAME), MAX_SPEED - tuning.air_deceleration * FRAME);
+
+    // Easing off to a lower speed in the same direction also decelerates
+    assert_close(tuning.next_velocity(MAX_SPEED, 2.0, true, FRAME), MAX_SPEED - tuning.ground_deceleration * FRAME);
+
+    // Stops at zero rather than drifting backwards
+    assert_close(tuning.next_velocity(0.5, 0.0, true, FRAME), 0.0);
+}
+
+#[test]
+fn turning_around_uses_the_turn_rate_on_the_ground() {
+    let tuning = MovementTuning::default();
+
+    assert_close(
+        tuning.next_velocity(5.0, -MAX_SPEED, true, FRAME),
+        5.0 - tuning.ground_turn_acceleration * FRAME,
+    );
+    assert_close(
+        tuning.next_velocity(-5.0, MAX_SPEED, true, FRAME),
+        -5.0 + tuning.ground_turn_acceleration * FRAME,
+    );
+
+    // The air has no separate turn rate
+    assert_close(
+        tuning.next_velocity(5.0, -MAX_SPEED, false, FRAME),
+        5.0 - tuning.air_acceleration * FRAME,
+    );
+}
+
+#[test]
+fn speed_change_scales_with_frame_time() {
+    let tuning = MovementTuning::default();
+
+    let one_frame = tuning.next_velocity(0.0, MAX_SPEED, true, FRAME);
+    let ten_frames = (0..10).fold(0.0, |velocity, _| tuning.next_velocity(velocity, MAX_SPEED, true, FRAME / 10.0));
+    assert_close(ten_frames, one_frame);
+}
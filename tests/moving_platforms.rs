@@ -0,0 +1,52 @@
+//! Moving platforms go back to the start of their path whenever the player
+//! spawns, so a platform the player set off is waiting for them again.
+
+use bevy::prelude::Vec3;
+use sidequest::plugins::{Easing, MovingPlatform, PathMode, PlatformPath};
+
+fn path(triggered: bool) -> PlatformPath {
+    PlatformPath {
+        waypoints: vec![Vec3::new(0.0, 2.0, 0.0), Vec3::new(0.0, 8.0, 0.0), Vec3::new(5.0, 8.0, 0.0)],
+        segment_time: 2.0,
+        pause_time: 0.5,
+        mode: PathMode::PingPong,
+        easing: Easing::Smooth,
+        triggered,
+    }
+}
+
+fn part_way(platform: &mut MovingPlatform) {
+    platform.started = true;
+    platform.segment = 2;
+    platform.forward = false;
+    platform.elapsed = 1.2;
+    platform.delta = Vec3::X;
+    platform.velocity = Vec3::X * 60.0;
+}
+
+#[test]
+fn triggered_platform_waits_for_the_player_again_after_a_reset() {
+    let mut platform = MovingPlatform::new(path(true));
+    assert!(!platform.started);
+
+    part_way(&mut platform);
+    platform.reset();
+
+    assert!(!platform.started);
+    assert_eq!(platform.segment, 0);
+    assert!(platform.forward);
+    assert_eq!(platform.elapsed, 0.0);
+    assert_eq!(platform.velocity, Vec3::ZERO);
+    assert_eq!(platform.start(), Some(Vec3::new(0.0, 2.0, 0.0)));
+}
+
+#[test]
+fn untriggered_platform_keeps_moving_after_a_reset() {
+    let mut platform = MovingPlatform::new(path(false));
+    part_way(&mut platform);
+    platform.reset();
+
+    assert!(platform.started);
+    assert_eq!(platform.segment, 0);
+    assert_eq!(platform.elapsed, 0.0);
+}
@@ -0,0 +1,145 @@
+//! Pausing has to leave the run exactly as it was, while going back to
+//! character selection starts the next run from scratch.
+
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use sidequest::components::*;
+use sidequest::events::HitEvent;
+use sidequest::plugins::{
+    ActiveCheckpoint, Breakable, CheckpointsPlugin, ControlsMenu, CorePlugin, Door, LogicPlugin, Prop, PropsPlugin,
+    Switch, TriggerVolume,
+};
+use sidequest::resources::{ActionState, GameStats, LoadingProgress, PerformanceMetrics};
+use sidequest::states::{GameState, PlayState};
+
+const CHECKPOINT: Vec3 = Vec3::new(39.0, 25.0, 0.0);
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), HierarchyPlugin))
+        .add_plugins((CorePlugin, CheckpointsPlugin, PropsPlugin, LogicPlugin))
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>()
+        .init_asset::<Scene>()
+        .init_asset::<AnimationClip>()
+        .add_state::<GameState>()
+        .add_state::<PlayState>()
+        .add_event::<HitEvent>()
+        .insert_resource(RapierContext::default())
+        .init_resource::<ActionState>()
+        .init_resource::<ControlsMenu>()
+        .init_resource::<DiagnosticsStore>()
+        .init_resource::<GameStats>()
+        .init_resource::<LoadingProgress>()
+        .init_resource::<PerformanceMetrics>();
+    enter(&mut app, GameState::CharacterSelection);
+    enter(&mut app, GameState::Playing);
+    app
+}
+
+fn enter(app: &mut App, state: GameState) {
+    app.world.resource_mut::<NextState<GameState>>().set(state);
+    app.update();
+}
+
+fn spawn_player(app: &mut App) -> Entity {
+    app.world.spawn((
+        TransformBundle::from_transform(Transform::from_translation(CHECKPOINT)),
+        Player {
+            speed: 5.0,
+            is_moving: false,
+            is_grounded: true,
+            is_front_flipping: false,
+            is_dive_rolling: false,
+            flip_direction: Vec3::ZERO,
+            facing_left: false,
+            is_dead: false,
+            is_hanging: false,
+        },
+        Health::new(3),
+    )).id()
+}
+
+fn reach_checkpoint(app: &mut App) {
+    let mut active = app.world.resource_mut::<ActiveCheckpoint>();
+    active.position = CHECKPOINT;
+}
+
+// Breaks every breakable crate and pushes every crate off its spot
+fn wreck_crates(app: &mut App) {
+    assert!(app.world.query::<&Breakable>().iter(&app.world).next().is_some(), "the level has no breakable crates");
+    for mut breakable in app.world.query::<&mut Breakable>().iter_mut(&mut app.world) {
+        breakable.broken = true;
+    }
+    for (prop, mut transform) in app.world.query::<(&Prop, &mut Transform)>().iter_mut(&mut app.world) {
+        transform.translation = prop.home + Vec3::X;
+    }
+}
+
+fn crates_are_wrecked(app: &mut App) -> bool {
+    let broken = app.world.query::<&Breakable>().iter(&app.world).all(|breakable| breakable.broken);
+    let pushed = app.world.query::<(&Prop, &Transform)>().iter(&app.world)
+        .all(|(prop, transform)| transform.translation != prop.home);
+    assert_eq!(broken, pushed, "only some of the crates were put back");
+    broken
+}
+
+// Flips every switch, sets off every trigger and opens the doors part way
+fn set_off_logic(app: &mut App) {
+    assert!(app.world.query::<&Switch>().iter(&app.world).next().is_some(), "the level has no switches");
+    for mut switch in app.world.query::<&mut Switch>().iter_mut(&mut app.world) {
+        switch.on = true;
+    }
+    for mut trigger in app.world.query::<&mut TriggerVolume>().iter_mut(&mut app.world) {
+        trigger.active = true;
+    }
+    for mut door in app.world.query::<&mut Door>().iter_mut(&mut app.world) {
+        door.progress = 0.5;
+    }
+}
+
+fn logic_is_set_off(app: &mut App) -> bool {
+    let switches = app.world.query::<&Switch>().iter(&app.world).all(|switch| switch.on);
+    let triggers = app.world.query::<&TriggerVolume>().iter(&app.world).all(|trigger| trigger.active);
+    let doors_closed = app.world.query::<&Door>().iter(&app.world).all(|door| door.progress == 0.0);
+    assert_eq!(switches, triggers, "only some of the logic was reset");
+    assert_eq!(!switches, doors_closed, "the doors don't match the switches");
+    switches
+}
+
+#[test]
+fn pausing_keeps_the_player_and_their_checkpoint() {
+    let mut app = app();
+    let player = spawn_player(&mut app);
+    app.update();
+    reach_checkpoint(&mut app);
+    wreck_crates(&mut app);
+    set_off_logic(&mut app);
+
+    enter(&mut app, GameState::Paused);
+    enter(&mut app, GameState::Playing);
+    app.update();
+
+    assert!(app.world.get_entity(player).is_some(), "pausing despawned the player");
+    assert_eq!(app.world.resource::<ActiveCheckpoint>().position, CHECKPOINT);
+    assert!(crates_are_wrecked(&mut app), "pausing put the crates back");
+    assert!(logic_is_set_off(&mut app), "pausing reset the switches");
+}
+
+#[test]
+fn restarting_starts_from_the_beginning() {
+    let mut app = app();
+    let player = spawn_player(&mut app);
+    app.update();
+    reach_checkpoint(&mut app);
+    wreck_crates(&mut app);
+    set_off_logic(&mut app);
+
+    enter(&mut app, GameState::CharacterSelection);
+
+    assert!(app.world.get_entity(player).is_none());
+    assert_eq!(app.world.resource::<ActiveCheckpoint>().position, ActiveCheckpoint::default().position);
+    assert!(!crates_are_wrecked(&mut app), "restarting left the crates broken");
+    assert!(!logic_is_set_off(&mut app), "restarting left the switches on");
+}
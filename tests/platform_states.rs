@@ -0,0 +1,95 @@
+//! Crumbling and timed platforms run on small state machines that the level
+//! resets whenever the player spawns or respawns.
+
+use sidequest::plugins::{CrumbleState, CrumblingPlatform, PlatformRhythm, TimedPlatform};
+
+const FRAME: f32 = 0.1;
+
+// Runs the platform for `seconds`, returning every change to whether it's solid
+fn run(platform: &mut CrumblingPlatform, seconds: f32, stood_on: bool) -> Vec<bool> {
+    (0..(seconds / FRAME).round() as u32)
+        .filter_map(|_| platform.advance(FRAME, stood_on))
+        .collect()
+}
+
+#[test]
+fn crumbling_platform_waits_until_stood_on() {
+    let mut platform = CrumblingPlatform::default();
+    assert!(run(&mut platform, 10.0, false).is_empty());
+    assert_eq!(platform.state, CrumbleState::Solid);
+    assert!(!platform.is_shaking());
+}
+
+#[test]
+fn crumbling_platform_shakes_falls_and_comes_back() {
+    let mut platform = CrumblingPlatform::default();
+    let (crumble_delay, respawn_delay) = (platform.crumble_delay, platform.respawn_delay);
+
+    assert_eq!(platform.advance(FRAME, true), None);
+    assert_eq!(platform.state, CrumbleState::Crumbling);
+    assert!(platform.is_shaking());
+
+    // Stepping off doesn't save it
+    let changes = run(&mut platform, crumble_delay + FRAME, false);
+    assert_eq!(changes, [false]);
+    assert_eq!(platform.state, CrumbleState::Fallen);
+    assert!(!platform.is_shaking());
+
+    let changes = run(&mut platform, respawn_delay + FRAME, false);
+    assert_eq!(changes, [true]);
+    assert_eq!(platform.state, CrumbleState::Solid);
+}
+
+#[test]
+fn crumbling_platform_reset_puts_it_back_mid_fall() {
+    for state_seconds in [0.3, 2.0] {
+        let mut platform = CrumblingPlatform::default();
+        let crumble_delay = platform.crumble_delay;
+        platform.advance(FRAME, true);
+        run(&mut platform, state_seconds, false);
+        assert_ne!(platform.state, CrumbleState::Solid);
+
+        platform.reset();
+        assert_eq!(platform.state, CrumbleState::Solid);
+        assert_eq!(platform.timer, 0.0);
+        // A full crumble delay from scratch the next time it's stood on
+        platform.advance(FRAME, true);
+        assert!(run(&mut platform, crumble_delay - 2.0 * FRAME, false).is_empty());
+    }
+}
+
+#[test]
+fn timed_platform_warns_before_each_gap() {
+    let mut rhythm = PlatformRhythm::default();
+    let solid_at = |rhythm: &mut PlatformRhythm, elapsed: f32| {
+        rhythm.elapsed = elapsed;
+        (rhythm.is_solid(0.0), rhythm.is_warning(0.0))
+    };
+
+    assert_eq!(solid_at(&mut rhythm, 0.0), (true, false));
+    assert_eq!(solid_at(&mut rhythm, 1.0), (true, false));
+    assert_eq!(solid_at(&mut rhythm, 2.0), (true, true));
+    assert_eq!(solid_at(&mut rhythm, 3.0), (false, false));
+    // The next beat starts solid again
+    assert_eq!(solid_at(&mut rhythm, 4.5), (true, false));
+}
+
+#[test]
+fn timed_platform_offset_runs_behind_the_beat() {
+    let rhythm = PlatformRhythm { elapsed: 0.5, ..PlatformRhythm::default() };
+
+    // Half a beat behind, two seconds back from 0.5s lands in the previous gap
+    assert!(rhythm.is_solid(0.0));
+    assert!(!rhythm.is_solid(0.5));
+    // A whole beat behind is the same as none
+    assert_eq!(rhythm.is_solid(1.0), rhythm.is_solid(0.0));
+}
+
+#[test]
+fn timed_platform_reset_is_solid() {
+    let mut platform = TimedPlatform::new(0.5);
+    platform.solid = false;
+    platform.reset();
+    assert!(platform.solid);
+    assert_eq!(platform.offset, 0.5);
+}
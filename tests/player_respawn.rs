@@ -0,0 +1,155 @@
+//! Dying has to clear whatever the player was in the middle of, or the state
+//! left behind follows them to the checkpoint. These run the player plugin
+//! headless with a hand-built player and no physics stepping.
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy_rapier3d::prelude::*;
+use sidequest::components::*;
+use sidequest::events::*;
+use sidequest::plugins::{ActiveCheckpoint, PlayerPlugin};
+use sidequest::resources::{GameStats, MovementTuning, PlayerAnimations, SelectedCharacter, UserData};
+use sidequest::states::GameState;
+use std::time::Duration;
+
+const FRAME: Duration = Duration::from_millis(50);
+const CHECKPOINT: Vec3 = Vec3::new(-5.0, 3.0, 0.0);
+const LEDGE: Vec3 = Vec3::new(12.0, 40.0, 0.0);
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), HierarchyPlugin, EventsPlugin, PlayerPlugin))
+        .init_asset::<AnimationClip>()
+        .add_state::<GameState>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+        .insert_resource(RapierContext::default())
+        .insert_resource(RapierConfiguration::default())
+        .init_resource::<MovementTuning>()
+        .init_resource::<GameStats>()
+        .init_resource::<UserData>()
+        .init_resource::<SelectedCharacter>()
+        .init_resource::<PlayerAnimations>()
+        .insert_resource(ActiveCheckpoint { entity: None, position: CHECKPOINT });
+    app.world.resource_mut::<NextState<GameState>>().set(GameState::Playing);
+    app.update();
+    app
+}
+
+fn spawn_hanging_player(app: &mut App) -> Entity {
+    app.world.spawn((
+        TransformBundle::from_transform(Transform::from_translation(LEDGE)),
+        RigidBody::Dynamic,
+        Velocity::zero(),
+        GravityScale(0.0),
+        Player {
+            speed: 5.0,
+            is_moving: false,
+            is_grounded: false,
+            is_front_flipping: false,
+            is_dive_rolling: false,
+            flip_direction: Vec3::ZERO,
+            facing_left: false,
+            is_dead: false,
+            is_hanging: true,
+        },
+        LedgeState {
+            hang_position: LEDGE,
+            climb_target: LEDGE + Vec3::new(0.6, 1.6, 0.0),
+            ..default()
+        },
+    )).insert((
+        JumpState::default(),
+        WallState::default(),
+        AbilityState::default(),
+        GroundState::default(),
+        HurtState::default(),
+        Health::new(3),
+        AttackState::default(),
+    )).id()
+}
+
+// Counts respawns, which reset every platform, projectile and enemy in the level
+#[derive(Resource, Default)]
+struct SpawnCount(usize);
+
+fn count_spawns(mut spawn_events: EventReader<PlayerSpawnEvent>, mut count: ResMut<SpawnCount>) {
+    count.0 += spawn_events.read().count();
+}
+
+fn run_for(app: &mut App, seconds: f32) {
+    for _ in 0..(seconds / FRAME.as_secs_f32()).ceil() as u32 {
+        app.update();
+    }
+}
+
+#[test]
+fn player_killed_while_hanging_respawns_at_the_checkpoint() {
+    let mut app = app();
+    let player = spawn_hanging_player(&mut app);
+    run_for(&mut app, 0.2);
+    assert_eq!(app.world.get::<Transform>(player).unwrap().translation, LEDGE);
+
+    // The wall climb laser sweeps across the ledge
+    app.world.send_event(PlayerHurtEvent {
+        entity: player,
+        damage: 3,
+        knockback: Vec3::ZERO,
+        cause: DeathCause::Laser,
+    });
+    app.update();
+    assert!(app.world.get::<Player>(player).unwrap().is_dead);
+
+    // Long enough for the death vignette and the respawn
+    run_for(&mut app, 2.0);
+
+    let state = app.world.get::<Player>(player).unwrap();
+    assert!(!state.is_dead);
+    assert!(!state.is_hanging);
+    assert_eq!(app.world.get::<LedgeState>(player).unwrap().climb_progress, None);
+    assert_eq!(app.world.get::<GravityScale>(player).unwrap().0, 1.0);
+    assert_eq!(app.world.get::<Transform>(player).unwrap().translation, CHECKPOINT);
+}
+
+#[test]
+fn each_death_respawns_the_player_once() {
+    let mut app = app();
+    app.init_resource::<SpawnCount>().add_systems(Update, count_spawns);
+    let player = spawn_hanging_player(&mut app);
+    app.update();
+
+    for deaths in 1..=2 {
+        app.world.send_event(PlayerHurtEvent {
+            entity: player,
+            damage: 3,
+            knockback: Vec3::ZERO,
+            cause: DeathCause::Laser,
+        });
+        run_for(&mut app, 2.0);
+        assert!(!app.world.get::<Player>(player).unwrap().is_dead);
+        assert_eq!(app.world.resource::<SpawnCount>().0, deaths);
+    }
+}
+
+#[test]
+fn hit_while_hanging_lets_go_of_the_ledge() {
+    let mut app = app();
+    let player = spawn_hanging_player(&mut app);
+    app.update();
+
+    let knockback = Vec3::new(-4.0, 5.0, 0.0);
+    app.world.send_event(PlayerHurtEvent {
+        entity: player,
+        damage: 1,
+        knockback,
+        cause: DeathCause::Projectile,
+    });
+    app.update();
+
+    let state = app.world.get::<Player>(player).unwrap();
+    assert!(!state.is_hanging);
+    assert!(!state.is_dead);
+    let ledge = app.world.get::<LedgeState>(player).unwrap();
+    assert_eq!(ledge.climb_progress, None);
+    assert!(ledge.regrab_timer > 0.0);
+    assert_eq!(app.world.get::<Velocity>(player).unwrap().linvel, knockback);
+}
@@ -0,0 +1,138 @@
+//! Older save files and backend responses must keep loading as the
+//! `UserData` schema evolves. Each fixture is a file as written by a past
+//! schema version.
+
+use serde_json::{json, Value};
+use sidequest::api::{self, AuthResponse, RemoteUserData};
+use sidequest::resources::{
+    migrate_user_data, user_data_from_value, LocalSave, MigrationError, PlayerStats, UserData,
+    UserPreferences, USER_DATA_SCHEMA_VERSION,
+};
+
+fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/migrations/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e))
+}
+
+fn fixture_value(name: &str) -> Value {
+    serde_json::from_str(&fixture(name)).expect("fixture is valid JSON")
+}
+
+fn expected_player() -> UserData {
+    UserData {
+        schema_version: USER_DATA_SCHEMA_VERSION,
+        user_id: Some("4b1f6c1e-2a0d-4d8e-9f3a-7c2b1e5d9a10".to_string()),
+        email: Some("player@example.com".to_string()),
+        username: Some("Player".to_string()),
+        player_stats: PlayerStats {
+            level: 3,
+            total_play_time: 512.5,
+            high_score: 42,
+            achievements: vec!["first_flip".to_string()],
+            unlocked_characters: vec!["Boss3".to_string(), "SwordHero".to_string()],
+            unlocked_abilities: Vec::new(),
+        },
+        preferences: UserPreferences {
+            master_volume: 0.9,
+            sfx_volume: 0.7,
+            music_volume: 0.5,
+            camera_sensitivity: 1.5,
+        },
+    }
+}
+
+#[test]
+fn v0_user_data_migrates_to_current() {
+    let user_data = user_data_from_value(fixture_value("user_data_v0.json")).unwrap();
+    assert_eq!(user_data, expected_player());
+}
+
+#[test]
+fn v1_user_data_migrates_to_current() {
+    let user_data = user_data_from_value(fixture_value("user_data_v1.json")).unwrap();
+    assert_eq!(user_data, expected_player());
+}
+
+#[test]
+fn v2_user_data_loads_unchanged() {
+    let user_data = user_data_from_value(fixture_value("user_data_v2.json")).unwrap();
+    assert_eq!(user_data, expected_player());
+}
+
+#[test]
+fn current_user_data_round_trips() {
+    let value = serde_json::to_value(expected_player()).unwrap();
+    assert_eq!(value["schema_version"], json!(USER_DATA_SCHEMA_VERSION));
+    assert_eq!(user_data_from_value(value).unwrap(), expected_player());
+}
+
+#[test]
+fn migration_is_idempotent() {
+    let mut once = fixture_value("user_data_v0.json");
+    migrate_user_data(&mut once).unwrap();
+    let mut twice = once.clone();
+    migrate_user_data(&mut twice).unwrap();
+    assert_eq!(once, twice);
+}
+
+#[test]
+fn v0_signup_response_gets_default_stats() {
+    let response: AuthResponse = api::parse_response(&fixture("signup_response_v0.json")).unwrap();
+    let user_data = response.user_data.expect("response has user data");
+
+    assert_eq!(user_data.schema_version, USER_DATA_SCHEMA_VERSION);
+    assert_eq!(user_data.username.as_deref(), Some("Newcomer"));
+    assert_eq!(user_data.player_stats, PlayerStats::default());
+    assert_eq!(user_data.preferences, UserPreferences::default());
+}
+
+#[test]
+fn failed_auth_response_without_user_data_parses() {
+    let body = json!({ "success": false, "message": "Invalid email or password" }).to_string();
+    let response: AuthResponse = api::parse_response(&body).unwrap();
+    assert!(!response.success);
+    assert!(response.user_data.is_none());
+}
+
+#[test]
+fn v0_remote_user_data_migrates() {
+    let body = json!({ "user_data": fixture_value("user_data_v0.json"), "revision": 7 }).to_string();
+    let remote: RemoteUserData = api::parse_response(&body).unwrap();
+    assert_eq!(remote.revision, 7);
+    assert_eq!(remote.user_data, expected_player());
+}
+
+#[test]
+fn v0_save_file_migrates_user_data_and_sync_base() {
+    let save = LocalSave::from_json(&fixture("save_v0.json")).unwrap();
+
+    assert_eq!(save.user_data.schema_version, USER_DATA_SCHEMA_VERSION);
+    assert_eq!(save.user_data.player_stats.high_score, 50);
+    assert_eq!(save.sync.last_synced_revision, 4);
+    assert!(save.session_token.is_some());
+    assert!(save.password_hash.is_some());
+    // Saves from before per-user bindings fall back to the defaults
+    assert!(save.input.is_none());
+
+    let base = save.sync.base.expect("save has a sync base");
+    assert_eq!(base.schema_version, USER_DATA_SCHEMA_VERSION);
+    assert_eq!(base.player_stats, PlayerStats::default());
+}
+
+#[test]
+fn newer_schema_is_rejected() {
+    let mut value = fixture_value("user_data_v2.json");
+    value["schema_version"] = json!(USER_DATA_SCHEMA_VERSION + 1);
+
+    match user_data_from_value(value) {
+        Err(MigrationError::UnsupportedVersion(version)) => {
+            assert_eq!(version, USER_DATA_SCHEMA_VERSION as u64 + 1)
+        }
+        other => panic!("expected UnsupportedVersion, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn non_object_user_data_is_rejected() {
+    assert!(matches!(user_data_from_value(json!([1, 2, 3])), Err(MigrationError::NotAnObject)));
+}
@@ -0,0 +1,133 @@
+//! Merging the local and server copies of a user's data when syncing.
+//! Progress made on either side must survive, and must only be counted once
+//! however many times a sync is retried.
+
+use sidequest::resources::{merge_user_data, PlayerStats, UserData, UserPreferences};
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+fn user(level: u32, play_time: f32, high_score: u32) -> UserData {
+    UserData {
+        user_id: Some("4b1f6c1e-2a0d-4d8e-9f3a-7c2b1e5d9a10".to_string()),
+        email: Some("player@example.com".to_string()),
+        username: Some("Player".to_string()),
+        player_stats: PlayerStats {
+            level,
+            total_play_time: play_time,
+            high_score,
+            ..PlayerStats::default()
+        },
+        ..UserData::default()
+    }
+}
+
+#[test]
+fn level_and_high_score_take_the_maximum() {
+    let base = user(2, 100.0, 10);
+    let local = user(4, 100.0, 12);
+    let remote = user(3, 100.0, 30);
+
+    let merged = merge_user_data(&local, &remote, Some(&base));
+    assert_eq!(merged.player_stats.level, 4);
+    assert_eq!(merged.player_stats.high_score, 30);
+}
+
+#[test]
+fn play_time_adds_local_time_since_base_to_the_server_total() {
+    let base = user(1, 100.0, 0);
+    let local = user(1, 130.0, 0);
+    let remote = user(1, 110.0, 0);
+
+    let merged = merge_user_data(&local, &remote, Some(&base));
+    assert_eq!(merged.player_stats.total_play_time, 140.0);
+}
+
+#[test]
+fn play_time_without_base_adds_all_local_time() {
+    // Progress made entirely offline has never reached the server
+    let local = user(1, 45.0, 0);
+    let remote = user(1, 20.0, 0);
+
+    let merged = merge_user_data(&local, &remote, None);
+    assert_eq!(merged.player_stats.total_play_time, 65.0);
+}
+
+#[test]
+fn local_play_time_behind_base_adds_nothing() {
+    let base = user(1, 100.0, 0);
+    let local = user(1, 80.0, 0);
+    let remote = user(1, 120.0, 0);
+
+    let merged = merge_user_data(&local, &remote, Some(&base));
+    assert_eq!(merged.player_stats.total_play_time, 120.0);
+}
+
+#[test]
+fn lists_take_the_union_without_duplicates() {
+    let mut local = user(1, 0.0, 0);
+    local.player_stats.achievements = strings(&["first_flip", "dive_roll"]);
+    local.player_stats.unlocked_characters = strings(&["Boss3"]);
+    local.player_stats.unlocked_abilities = strings(&["double_jump"]);
+    let mut remote = user(1, 0.0, 0);
+    remote.player_stats.achievements = strings(&["first_flip", "ledge_grab"]);
+    remote.player_stats.unlocked_characters = strings(&["Boss3", "SwordHero"]);
+
+    let merged = merge_user_data(&local, &remote, None);
+    assert_eq!(merged.player_stats.achievements, strings(&["first_flip", "ledge_grab", "dive_roll"]));
+    assert_eq!(merged.player_stats.unlocked_characters, strings(&["Boss3", "SwordHero"]));
+    assert_eq!(merged.player_stats.unlocked_abilities, strings(&["double_jump"]));
+}
+
+#[test]
+fn identity_comes_from_the_server_and_preferences_stay_local() {
+    let mut local = user(1, 0.0, 0);
+    local.username = Some("Old name".to_string());
+    local.preferences.master_volume = 0.2;
+    let mut remote = user(1, 0.0, 0);
+    remote.username = Some("New name".to_string());
+    remote.email = None;
+    remote.preferences = UserPreferences::default();
+
+    let merged = merge_user_data(&local, &remote, None);
+    assert_eq!(merged.username.as_deref(), Some("New name"));
+    assert_eq!(merged.email.as_deref(), Some("player@example.com"));
+    assert_eq!(merged.preferences.master_volume, 0.2);
+}
+
+#[test]
+fn conflicting_retry_counts_local_progress_once() {
+    let base = user(1, 100.0, 5);
+    let local = user(2, 130.0, 8);
+
+    // Another device stored 10s of play between our fetch and our upload...
+    let remote = user(1, 110.0, 5);
+    let first_attempt = merge_user_data(&local, &remote, Some(&base));
+    assert_eq!(first_attempt.player_stats.total_play_time, 140.0);
+
+    // ...so the upload conflicts, and the retry merges against its newer
+    // copy, which has 5s more on top. The merge starts from the same local
+    // copy and base, not from the failed attempt.
+    let newer_remote = user(1, 115.0, 9);
+    let retry = merge_user_data(&local, &newer_remote, Some(&base));
+    assert_eq!(retry.player_stats.total_play_time, 145.0);
+    assert_eq!(retry.player_stats.level, 2);
+    assert_eq!(retry.player_stats.high_score, 9);
+
+    // Merging again once the server has the result changes nothing
+    assert_eq!(merge_user_data(&retry, &retry, Some(&retry)), retry);
+}
+
+#[test]
+fn progress_made_during_a_sync_is_kept() {
+    let sent = user(2, 145.0, 9);
+    let mut current = user(2, 150.0, 11);
+    current.player_stats.achievements = strings(&["first_flip"]);
+
+    // The sync stored `sent`, which becomes the base for what happened since
+    let merged = merge_user_data(&current, &sent, Some(&sent));
+    assert_eq!(merged.player_stats.total_play_time, 150.0);
+    assert_eq!(merged.player_stats.high_score, 11);
+    assert_eq!(merged.player_stats.achievements, strings(&["first_flip"]));
+}
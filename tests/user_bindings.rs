@@ -0,0 +1,66 @@
+//! Key bindings are kept in each user's local save, so several accounts
+//! sharing a profile directory each keep their own controls.
+
+use bevy::prelude::KeyCode;
+use sidequest::resources::{InputAction, InputConfig, LocalSave, SyncRecord, UserData};
+
+fn user(user_id: &str) -> UserData {
+    UserData {
+        user_id: Some(user_id.to_string()),
+        ..UserData::default()
+    }
+}
+
+fn bindings(save_directory: &std::path::Path, user_id: &str) -> Option<InputConfig> {
+    LocalSave::load(save_directory, user_id).and_then(|save| save.input)
+}
+
+#[test]
+fn each_user_keeps_their_own_bindings() {
+    let dir = tempfile::tempdir().unwrap();
+    let alice = user("alice");
+    let bob = user("bob");
+
+    let mut alice_input = InputConfig::default();
+    alice_input.rebind(InputAction::Jump, KeyCode::K);
+    LocalSave::store_input(dir.path(), &alice, &alice_input).unwrap();
+    LocalSave::store_progress(dir.path(), &bob, &SyncRecord::default()).unwrap();
+
+    assert_eq!(bindings(dir.path(), "alice"), Some(alice_input));
+    assert_eq!(bindings(dir.path(), "bob"), None);
+}
+
+#[test]
+fn saving_progress_keeps_the_bindings() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut player = user("player");
+
+    let mut input = InputConfig::default();
+    input.rebind(InputAction::DiveRoll, KeyCode::L);
+    LocalSave::store_input(dir.path(), &player, &input).unwrap();
+
+    player.player_stats.high_score = 12;
+    LocalSave::store_progress(dir.path(), &player, &SyncRecord::default()).unwrap();
+
+    let save = LocalSave::load(dir.path(), "player").unwrap();
+    assert_eq!(save.user_data.player_stats.high_score, 12);
+    assert_eq!(save.input, Some(input));
+}
+
+#[test]
+fn saving_bindings_keeps_the_progress_and_credentials() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut player = user("player");
+    player.player_stats.level = 4;
+
+    let mut save = LocalSave::from_server(player.clone());
+    save.session_token = Some("token".to_string());
+    save.write(dir.path()).unwrap();
+
+    LocalSave::store_input(dir.path(), &player, &InputConfig::default()).unwrap();
+
+    let save = LocalSave::load(dir.path(), "player").unwrap();
+    assert_eq!(save.user_data.player_stats.level, 4);
+    assert_eq!(save.session_token.as_deref(), Some("token"));
+    assert_eq!(save.input, Some(InputConfig::default()));
+}